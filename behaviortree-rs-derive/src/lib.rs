@@ -46,10 +46,10 @@ impl ToMap<Punctuated<syn::Meta, Comma>, syn::Ident, Option<proc_macro2::TokenSt
                                     quote! { #path }
                                 }
                                 else {
-                                    return Err(syn::Error::new_spanned(&arg.value, "argument value should be a:  variable, literal, path, function call"))
+                                    return Err(syn::Error::new_spanned(&arg.value, "argument value should be a variable, literal, path or function call"))
                                 };
 
-                                Ok((arg.path.get_ident().unwrap().clone(), Some(value)))
+                                Ok((arg.path.require_ident()?.clone(), Some(value)))
                             }
                             else {
                                 Err(syn::Error::new_spanned(&arg.value, "argument value should be a string literal"))
@@ -60,7 +60,7 @@ impl ToMap<Punctuated<syn::Meta, Comma>, syn::Ident, Option<proc_macro2::TokenSt
                         }
                     }
                     syn::Meta::Path(arg) => {
-                        Ok((arg.get_ident().unwrap().clone(), None))
+                        Ok((arg.require_ident()?.clone(), None))
                     }
                     _ => Err(syn::Error::new_spanned(m, "argument type should be Path or NameValue: `#[bt(default)]`, or `#[bt(default = \"String::new()\")]`"))
                 }
@@ -126,14 +126,23 @@ impl ConcatTokenStream for proc_macro2::TokenStream {
     }
 }
 
+/// Node types accepted as the first argument of `#[bt_node(...)]`
+const NODE_TYPES: [&str; 5] = [
+    "SyncActionNode",
+    "StatefulActionNode",
+    "ConditionNode",
+    "ControlNode",
+    "DecoratorNode",
+];
+
 fn create_bt_node(
-    args: TokenStream,
+    args: proc_macro2::TokenStream,
     mut item: ItemStruct,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let args_parsed =
-        syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated.parse(args)?;
+        syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated.parse2(args)?;
 
-    if args_parsed.empty_or_trailing() {
+    if args_parsed.is_empty() {
         return Err(syn::Error::new_spanned(
             args_parsed,
             "you must specify at least one argument: node type",
@@ -148,15 +157,17 @@ fn create_bt_node(
     let arg = args_parsed_iter.next().unwrap();
 
     // Require parameter to be ident, no prefix path
-    arg.require_ident()?;
+    let type_ident = arg.require_ident()?.to_string();
 
-    let type_ident = arg.get_ident().unwrap().to_string();
-    // return Err(syn::Error::new_spanned(arg, format!("{type_ident}")));
+    if !NODE_TYPES.contains(&type_ident.as_str()) {
+        return Err(syn::Error::new_spanned(
+            arg,
+            format!("unsupported node type `{type_ident}`: must be one of {}", NODE_TYPES.join(", ")),
+        ));
+    }
 
     let runtime_str = if let Some(runtime) = args_parsed_iter.next() {
-        runtime.require_ident()?;
-
-        let ident = runtime.get_ident().unwrap().to_string();
+        let ident = runtime.require_ident()?.to_string();
 
         match ident.as_str() {
             "Async" | "Sync" => {}
@@ -173,6 +184,13 @@ fn create_bt_node(
         String::from("Async")
     };
 
+    if let Some(extra) = args_parsed_iter.next() {
+        return Err(syn::Error::new_spanned(
+            extra,
+            "unexpected argument: expected a node type and an optional runtime, e.g. `#[bt_node(SyncActionNode, Async)]`",
+        ));
+    }
+
     let item_ident = &item.ident;
 
     let mut default_fields = proc_macro2::TokenStream::new();
@@ -193,6 +211,13 @@ fn create_bt_node(
                             a.parse_args_with(Punctuated::parse_terminated)?;
                         let args_map = args.to_map()?;
 
+                        if let Some(unknown) = args_map.keys().find(|k| *k != "default") {
+                            return Err(syn::Error::new_spanned(
+                                unknown,
+                                format!("unknown argument `{unknown}`: the only supported argument is `default`"),
+                            ));
+                        }
+
                         // If the default argument was included
                         if let Some(value) = args_map.get(&syn::parse_str("default")?) {
                             used_default = true;
@@ -249,6 +274,10 @@ fn create_bt_node(
                     // Add proper derive macros
                     derives.push(quote! { ::behaviortree_rs::derive::ActionNode, ::behaviortree_rs::derive::SyncActionNode });
                 }
+                "ConditionNode" => {
+                    // Ticks like a synchronous action
+                    derives.push(quote! { ::behaviortree_rs::derive::ConditionNode, ::behaviortree_rs::derive::SyncActionNode });
+                }
                 "StatefulActionNode" => {
                     // Add StatefulActionNode-specific fields
                     fields.named.push(
@@ -290,23 +319,25 @@ fn create_bt_node(
                             });
                         }
                         "Sync" => {
+                            // `spawn_blocking()` can't borrow `self`, so the callbacks
+                            // block in place instead
                             extra_impls = extra_impls.concat_blocks(quote! {
                                 impl ::behaviortree_rs::nodes::action::AsyncStatefulActionNode for #item_ident {
                                     fn on_start(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_start(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_start(self))
                                         })
                                     }
 
                                     fn on_running(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_running(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_running(self))
                                         })
                                     }
 
                                     fn on_halted(&mut self) -> ::behaviortree_rs::sync::BoxFuture<()>{
                                         ::std::boxed::Box::pin(async move {
-                                            ::behaviortree_rs::sync::spawn_blocking(move || ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_halted(self)).await
+                                            ::behaviortree_rs::sync::block_in_place(|| ::behaviortree_rs::nodes::action::SyncStatefulActionNode::on_halted(self))
                                         })
                                     }
                                 }
@@ -337,7 +368,7 @@ fn create_bt_node(
                     // Add proper derive macros
                     derives.push(quote! { ::behaviortree_rs::derive::DecoratorNode });
                 }
-                _ => return Err(syn::Error::new_spanned(arg, "unsupported node type")),
+                _ => unreachable!(),
             }
         }
         _ => {
@@ -396,7 +427,14 @@ fn create_bt_node(
             }
         });
 
-    match runtime_str.as_str() {
+    // Stateful actions tick and halt through their `on_*()` callbacks, which
+    // are bridged between the runtimes above. Only the unused traits are added.
+    let tick_runtime = match type_ident.as_str() {
+        "StatefulActionNode" => "Async",
+        _ => runtime_str.as_str(),
+    };
+
+    match tick_runtime {
         "Async" => {
             extra_impls = extra_impls.concat_blocks(quote! {
                 impl ::behaviortree_rs::nodes::SyncTick for #item_ident {
@@ -466,6 +504,7 @@ fn create_bt_node(
 /// Supported options:
 /// - `SyncActionNode`
 /// - `StatefulActionNode`
+/// - `ConditionNode`, which ticks like a `SyncActionNode`
 /// - `ControlNode`
 /// - `DecoratorNode`
 ///
@@ -573,7 +612,7 @@ fn create_bt_node(
 pub fn bt_node(args: TokenStream, input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as ItemStruct);
 
-    create_bt_node(args, item)
+    create_bt_node(args.into(), item)
         .unwrap_or_else(syn::Error::into_compile_error)
         .into()
 }
@@ -618,6 +657,14 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
             fn into_boxed(self) -> Box<dyn ::behaviortree_rs::nodes::TreeNodeBase> {
                 Box::new(self)
             }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
         }
 
         impl ::behaviortree_rs::nodes::TreeNodeBase for #ident {}
@@ -631,9 +678,32 @@ pub fn derive_tree_node(input: TokenStream) -> TokenStream {
 pub fn derive_action_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let ident = input.ident;
+    let expanded = action_node_impls(&input.ident, quote! { Action });
+
+    TokenStream::from(expanded)
+}
+
+/// Like `ActionNode`, but the node's type is `NodeType::Condition`.
+#[proc_macro_derive(ConditionNode)]
+pub fn derive_condition_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+    let action_impls = action_node_impls(ident, quote! { Condition });
 
     let expanded = quote! {
+        #action_impls
+
+        impl ::behaviortree_rs::nodes::ConditionNode for #ident {}
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// The traits shared by actions and conditions, with `node_type` as the
+/// variant of `NodeType` the node reports.
+fn action_node_impls(ident: &Ident, node_type: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
         impl ::behaviortree_rs::nodes::ActionNode for #ident {
             fn execute_action_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                 ::std::boxed::Box::pin(async move {
@@ -647,14 +717,14 @@ pub fn derive_action_node(input: TokenStream) -> TokenStream {
 
         impl ::behaviortree_rs::nodes::ActionNodeBase for #ident {}
 
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {}
+
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
-                ::behaviortree_rs::basic_types::NodeType::Action
+                ::behaviortree_rs::basic_types::NodeType::#node_type
             }
         }
-    };
-
-    TokenStream::from(expanded)
+    }
 }
 
 #[proc_macro_derive(ControlNode)]
@@ -662,6 +732,11 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let ident = input.ident;
+    let pre_condition_check = pre_condition_check();
+    let tick = breakpoint_checks(quote! {
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+        <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await
+    });
 
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::ControlNode for #ident {
@@ -682,6 +757,7 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
                                 unsafe {
                                     ::behaviortree_rs::nodes::AsyncHalt::halt(&mut *child_ptr).await;
                                 }
+                                child.config().check_halted_condition().await;
                             }
                             Ok(child.reset_status())
                         }
@@ -693,7 +769,8 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
             fn halt_children(&mut self, start: usize) -> ::behaviortree_rs::sync::BoxFuture<Result<(), ::behaviortree_rs::nodes::NodeError>> {
                 ::std::boxed::Box::pin(async move {
 
-                    if start >= self.children.len() {
+                    // `start == len` is an empty range, e.g. for a node without children
+                    if start > self.children.len() {
                         return Err(::behaviortree_rs::nodes::NodeError::IndexError);
                     }
 
@@ -712,19 +789,44 @@ pub fn derive_control_node(input: TokenStream) -> TokenStream {
                     self.halt_children(0).await.unwrap();
                 })
             }
+
+            fn tick_child(&mut self, index: usize) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                ::std::boxed::Box::pin(async move {
+                    match self.children.get_mut(index) {
+                        Some(child) => ::behaviortree_rs::nodes::ExecuteTick::execute_tick(&mut **child).await,
+                        None => Err(::behaviortree_rs::nodes::NodeError::IndexError),
+                    }
+                })
+            }
         }
 
         impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
             fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                 ::std::boxed::Box::pin(async move {
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                    <Self as ::behaviortree_rs::nodes::AsyncTick>::tick(self).await
+                    #pre_condition_check
+
+                    #tick
                 })
             }
         }
 
         impl ::behaviortree_rs::nodes::ControlNodeBase for #ident {}
 
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {
+            fn child_nodes(&self) -> ::std::vec::Vec<&::behaviortree_rs::nodes::TreeNodePtr> {
+                self.children.iter().collect()
+            }
+
+            fn child_nodes_mut(&mut self) -> ::std::vec::Vec<&mut ::behaviortree_rs::nodes::TreeNodePtr> {
+                self.children.iter_mut().collect()
+            }
+
+            fn attach_child(&mut self, child: ::behaviortree_rs::nodes::TreeNodePtr) -> Result<Option<::behaviortree_rs::nodes::TreeNodePtr>, ::behaviortree_rs::nodes::NodeError> {
+                self.children.push(child);
+                Ok(None)
+            }
+        }
+
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
                 ::behaviortree_rs::basic_types::NodeType::Control
@@ -740,6 +842,11 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let ident = input.ident;
+    let pre_condition_check = pre_condition_check();
+    let tick = breakpoint_checks(quote! {
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
+        self.tick().await
+    });
 
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::DecoratorNode for #ident {
@@ -769,12 +876,22 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
                             unsafe {
                                 ::behaviortree_rs::nodes::AsyncHalt::halt(&mut *child_ptr).await;
                             }
+                            child.config().check_halted_condition().await;
                         }
 
                         child.reset_status();
                     }
                 })
             }
+
+            fn tick_child(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
+                ::std::boxed::Box::pin(async move {
+                    match self.child.as_mut() {
+                        Some(child) => ::behaviortree_rs::nodes::ExecuteTick::execute_tick(&mut **child).await,
+                        None => Err(::behaviortree_rs::nodes::NodeError::ChildMissing),
+                    }
+                })
+            }
         }
 
         impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
@@ -784,14 +901,29 @@ pub fn derive_decorator_node(input: TokenStream) -> TokenStream {
                         return Err(::behaviortree_rs::nodes::NodeError::ChildMissing);
                     }
 
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
-                    self.tick().await
+                    #pre_condition_check
+
+                    #tick
                 })
             }
         }
 
         impl ::behaviortree_rs::nodes::DecoratorNodeBase for #ident {}
 
+        impl ::behaviortree_rs::nodes::NodeChildren for #ident {
+            fn child_nodes(&self) -> ::std::vec::Vec<&::behaviortree_rs::nodes::TreeNodePtr> {
+                self.child.iter().collect()
+            }
+
+            fn child_nodes_mut(&mut self) -> ::std::vec::Vec<&mut ::behaviortree_rs::nodes::TreeNodePtr> {
+                self.child.iter_mut().collect()
+            }
+
+            fn attach_child(&mut self, child: ::behaviortree_rs::nodes::TreeNodePtr) -> Result<Option<::behaviortree_rs::nodes::TreeNodePtr>, ::behaviortree_rs::nodes::NodeError> {
+                Ok(self.child.replace(child))
+            }
+        }
+
         impl ::behaviortree_rs::nodes::GetNodeType for #ident {
             fn node_type(&self) -> ::behaviortree_rs::basic_types::NodeType {
                 ::behaviortree_rs::basic_types::NodeType::Decorator
@@ -808,16 +940,22 @@ pub fn derive_sync_action_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let ident = input.ident;
+    let pre_condition_check = pre_condition_check();
+    let tick = breakpoint_checks(quote! {
+        ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
+        match <Self as ::behaviortree_rs::nodes::ActionNode>::execute_action_tick(self).await? {
+            ::behaviortree_rs::basic_types::NodeStatus::Running => Err(::behaviortree_rs::nodes::NodeError::StatusError(self.config.path.clone(), "Running".to_string())),
+            status => Ok(status)
+        }
+    });
 
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::ExecuteTick for #ident {
             fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                 ::std::boxed::Box::pin(async move {
-                    ::log::debug!("[behaviortree_rs]: {}::tick()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::name(self));
-                    match <Self as ::behaviortree_rs::nodes::ActionNode>::execute_action_tick(self).await? {
-                        ::behaviortree_rs::basic_types::NodeStatus::Running => Err(::behaviortree_rs::nodes::NodeError::StatusError(self.config.path.clone(), "Running".to_string())),
-                        status => Ok(status)
-                    }
+                    #pre_condition_check
+
+                    #tick
                 })
             }
         }
@@ -832,36 +970,44 @@ pub fn derive_stateful_action_node(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let ident = input.ident;
+    let pre_condition_check = pre_condition_check();
+    let tick = breakpoint_checks(quote! {
+        let prev_status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
+
+        let new_status = match prev_status {
+            ::behaviortree_rs::basic_types::NodeStatus::Idle => {
+                ::log::debug!("[behaviortree_rs]: {}::on_start()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+                let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self).await?;
+                ::behaviortree_rs::nodes::action::trace_transition(<Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self), ::behaviortree_rs::nodes::action::StatefulTransition::Start, Some(&new_status));
+                if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
+                    return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_start()", self.config.path), "Idle".to_string()))
+                }
+                new_status
+            }
+            ::behaviortree_rs::basic_types::NodeStatus::Running => {
+                ::log::debug!("[behaviortree_rs]: {}::on_running()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
+                let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self).await?;
+                ::behaviortree_rs::nodes::action::trace_transition(<Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self), ::behaviortree_rs::nodes::action::StatefulTransition::Running, Some(&new_status));
+                if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
+                    return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_running()", self.config.path), "Idle".to_string()))
+                }
+                new_status
+            }
+            prev_status => prev_status
+        };
+
+        <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::set_status(self, new_status.clone());
+
+        Ok(new_status)
+    });
 
     let expanded = quote! {
         impl ::behaviortree_rs::nodes::ExecuteTick for #ident where #ident: ::behaviortree_rs::nodes::AsyncStatefulActionNode {
             fn execute_tick(&mut self) -> ::behaviortree_rs::sync::BoxFuture<::behaviortree_rs::NodeResult> {
                 ::std::boxed::Box::pin(async move {
-                    let prev_status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
-
-                    let new_status = match prev_status {
-                        ::behaviortree_rs::basic_types::NodeStatus::Idle => {
-                            ::log::debug!("[behaviortree_rs]: {}::on_start()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                            let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_start(self).await?;
-                            if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
-                                return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_start()", self.config.path), "Idle".to_string()))
-                            }
-                            new_status
-                        }
-                        ::behaviortree_rs::basic_types::NodeStatus::Running => {
-                            ::log::debug!("[behaviortree_rs]: {}::on_running()", <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self));
-                            let new_status = ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_running(self).await?;
-                            if matches!(new_status, ::behaviortree_rs::basic_types::NodeStatus::Idle) {
-                                return Err(::behaviortree_rs::nodes::NodeError::StatusError(format!("{}::on_running()", self.config.path), "Idle".to_string()))
-                            }
-                            new_status
-                        }
-                        prev_status => prev_status
-                    };
+                    #pre_condition_check
 
-                    <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::set_status(self, new_status.clone());
-
-                    Ok(new_status)
+                    #tick
                 })
             }
         }
@@ -872,7 +1018,8 @@ pub fn derive_stateful_action_node(input: TokenStream) -> TokenStream {
                     self.halt_requested = true;
 
                     if matches!(<Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self), ::behaviortree_rs::basic_types::NodeStatus::Running) {
-                        self.on_halted().await;
+                        ::behaviortree_rs::nodes::action::AsyncStatefulActionNode::on_halted(self).await;
+                        ::behaviortree_rs::nodes::action::trace_transition(<Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::path(self), ::behaviortree_rs::nodes::action::StatefulTransition::Halted, None);
                     }
 
                     <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::reset_status(self);
@@ -903,13 +1050,114 @@ pub fn derive_from_string(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Implements `FromString` and `BTToString` with a parser generated for the
+/// type, instead of delegating to `FromStr` like `#[derive(FromString)]`.
+///
+/// For an enum whose variants have no fields, a string is parsed as the
+/// variant of the same name, ignoring ASCII case, and an unknown name gives a
+/// `ParseEnumError` listing the variants. A tuple struct with one field is
+/// parsed like that field.
+#[proc_macro_derive(EnumFromString)]
+pub fn derive_enum_from_string(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    create_enum_from_string(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn create_enum_from_string(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    match &input.data {
+        syn::Data::Enum(data) => {
+            let with_fields = data
+                .variants
+                .iter()
+                .find(|variant| !matches!(variant.fields, syn::Fields::Unit));
+            if let Some(variant) = with_fields {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    "expected an enum whose variants have no fields",
+                ));
+            }
+
+            let variants: Vec<_> = data.variants.iter().map(|v| &v.ident).collect();
+            let names: Vec<_> = variants.iter().map(|v| v.to_string()).collect();
+            let enum_name = ident.to_string();
+
+            Ok(quote! {
+                impl #impl_generics ::behaviortree_rs::basic_types::FromString for #ident #ty_generics #where_clause {
+                    type Err = ::behaviortree_rs::basic_types::ParseEnumError;
+
+                    fn from_string(value: impl AsRef<str>) -> Result<Self, Self::Err> {
+                        let value = value.as_ref();
+                        #(
+                            if value.eq_ignore_ascii_case(#names) {
+                                return Ok(Self::#variants);
+                            }
+                        )*
+
+                        Err(::behaviortree_rs::basic_types::ParseEnumError::new(value, #enum_name, &[#(#names),*]))
+                    }
+                }
+
+                impl #impl_generics ::behaviortree_rs::basic_types::BTToString for #ident #ty_generics #where_clause {
+                    fn bt_to_string(&self) -> String {
+                        match self {
+                            #( Self::#variants => ::std::string::String::from(#names), )*
+                        }
+                    }
+                }
+            })
+        }
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Unnamed(fields),
+            ..
+        }) if fields.unnamed.len() == 1 => {
+            let field_type = &fields.unnamed[0].ty;
+
+            Ok(quote! {
+                impl #impl_generics ::behaviortree_rs::basic_types::FromString for #ident #ty_generics #where_clause {
+                    type Err = <#field_type as ::behaviortree_rs::basic_types::FromString>::Err;
+
+                    fn from_string(value: impl AsRef<str>) -> Result<Self, Self::Err> {
+                        <#field_type as ::behaviortree_rs::basic_types::FromString>::from_string(value).map(Self)
+                    }
+                }
+
+                impl #impl_generics ::behaviortree_rs::basic_types::BTToString for #ident #ty_generics #where_clause {
+                    fn bt_to_string(&self) -> String {
+                        ::behaviortree_rs::basic_types::BTToString::bt_to_string(&self.0)
+                    }
+                }
+            })
+        }
+        _ => Err(syn::Error::new_spanned(
+            ident,
+            "expected an enum whose variants have no fields, or a tuple struct with one field",
+        )),
+    }
+}
+
 struct NodeRegistration {
     factory: syn::Ident,
     name: proc_macro2::TokenStream,
     node_type: syn::Type,
+    description: Option<syn::Expr>,
     params: Punctuated<syn::Expr, Comma>,
 }
 
+/// Returns true if the next tokens are `description = ...`.
+fn peek_description(input: syn::parse::ParseStream) -> bool {
+    let fork = input.fork();
+    match fork.parse::<syn::Ident>() {
+        Ok(ident) => ident == "description" && fork.peek(Token![=]) && !fork.peek(Token![==]),
+        Err(_) => false,
+    }
+}
+
 impl Parse for NodeRegistration {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let factory = input.parse()?;
@@ -923,6 +1171,19 @@ impl Parse for NodeRegistration {
         if !input.is_empty() {
             input.parse::<Token![,]>()?;
         }
+
+        // An optional `description = <expr>` comes before the node parameters
+        let description = if peek_description(input) {
+            input.parse::<syn::Ident>()?;
+            input.parse::<Token![=]>()?;
+            let description = input.parse::<syn::Expr>()?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+            Some(description)
+        } else {
+            None
+        };
     
         let params = input.parse_terminated(syn::Expr::parse, Token![,])?;
 
@@ -930,6 +1191,7 @@ impl Parse for NodeRegistration {
             factory,
             name: node_name,
             node_type,
+            description,
             params,
         })
     }
@@ -940,6 +1202,7 @@ fn build_node(node: &NodeRegistration) -> proc_macro2::TokenStream {
         factory: _,
         name,
         node_type,
+        description: _,
         params
     } = node;
 
@@ -956,7 +1219,7 @@ fn build_node(node: &NodeRegistration) -> proc_macro2::TokenStream {
                 node_type: <#node_type as ::behaviortree_rs::nodes::GetNodeType>::node_type(&node),
                 registration_id: #name.into(),
                 ports: <#node_type as ::behaviortree_rs::nodes::NodePorts>::provided_ports(&node),
-                description: ::std::string::String::new(),
+                description: ::std::clone::Clone::clone(&description),
             };
             <#node_type as ::behaviortree_rs::nodes::TreeNodeDefaults>::config_mut(&mut node).set_manifest(::std::sync::Arc::new(manifest));
             node
@@ -964,6 +1227,51 @@ fn build_node(node: &NodeRegistration) -> proc_macro2::TokenStream {
     }
 }
 
+/// Code run at the start of every generated `execute_tick()`. Returns early
+/// if one of the node's pre-conditions decides its status, after running the
+/// post-conditions for it and reporting it.
+fn pre_condition_check() -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
+            let pre_status = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_pre_conditions(&status).await?;
+
+            if let ::std::option::Option::Some(pre_status) = pre_status {
+                if matches!(status, ::behaviortree_rs::basic_types::NodeStatus::Running) {
+                    ::behaviortree_rs::nodes::AsyncHalt::halt(self).await;
+                    <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_halted_condition().await;
+                }
+                <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::reset_status(self);
+                <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_post_conditions(&pre_status).await?;
+                <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).report_status(&status, &pre_status);
+
+                return Ok(pre_status);
+            }
+        }
+    }
+}
+
+/// Wraps the tick of a generated `execute_tick()` in the breakpoint checks
+/// of `AsyncTree::tick_with_breakpoints()`, runs the post-conditions for the
+/// resulting status, and reports it for `AsyncTree::enable_groot2_publisher()`
+/// and `AsyncTree::set_status_callback()`.
+fn breakpoint_checks(tick: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let status_before = <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::status(self);
+            <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_breakpoint(::behaviortree_rs::tree::BreakpointPhase::BeforeTick, &status_before).await;
+
+            let result: ::behaviortree_rs::NodeResult = async { #tick }.await;
+            let status = result?;
+            <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_post_conditions(&status).await?;
+
+            <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).check_breakpoint(::behaviortree_rs::tree::BreakpointPhase::AfterTick, &status).await;
+            <Self as ::behaviortree_rs::nodes::TreeNodeDefaults>::config(self).report_status(&status_before, &status);
+            Ok(status)
+        }
+    }
+}
+
 fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream, node_type: NodeTypeInternal) -> TokenStream {
     let node_registration = parse_macro_input!(input as NodeRegistration);
 
@@ -983,6 +1291,11 @@ fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream,
             }
         });
 
+    let description = match &node_registration.description {
+        Some(description) => quote! { ::std::string::ToString::to_string(&#description) },
+        None => quote! { ::std::string::String::new() },
+    };
+
     let node = build_node(&node_registration);
 
     let extra_steps = match node_type {
@@ -1001,6 +1314,8 @@ fn register_node(input: TokenStream, node_type_token: proc_macro2::TokenStream,
 
             #param_clone_expr
 
+            let description: ::std::string::String = #description;
+
             let node_fn = move |
                 config: ::behaviortree_rs::nodes::NodeConfig,
                 mut children: ::std::vec::Vec<::std::boxed::Box<dyn ::behaviortree_rs::nodes::TreeNodeBase + Send + Sync>>
@@ -1042,11 +1357,25 @@ enum NodeTypeInternal {
 /// 
 /// register_action_node!(factory, "TestNode", TestNode, arg1, arg2);
 /// ```
+/// 
+/// A description for the node's manifest can be given before the parameters:
+/// 
+/// ```ignore
+/// register_action_node!(factory, "TestNode", TestNode, description = "Says hello", arg1, arg2);
+/// ```
 #[proc_macro]
 pub fn register_action_node(input: TokenStream) -> TokenStream {
     register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Action }, NodeTypeInternal::Action)
 }
 
+/// Registers a Condition type node with the factory, e.g. one created with
+/// `#[bt_node(ConditionNode)]`. Takes the same arguments as
+/// `register_action_node!`.
+#[proc_macro]
+pub fn register_condition_node(input: TokenStream) -> TokenStream {
+    register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Condition }, NodeTypeInternal::Action)
+}
+
 /// Registers an Control type node with the factory.
 /// 
 /// **NOTE:** During tree creation, a new node is created using the parameters
@@ -1063,6 +1392,12 @@ pub fn register_action_node(input: TokenStream) -> TokenStream {
 /// 
 /// register_control_node!(factory, "TestNode", TestNode, arg1, arg2);
 /// ```
+/// 
+/// A description for the node's manifest can be given before the parameters:
+/// 
+/// ```ignore
+/// register_control_node!(factory, "TestNode", TestNode, description = "Says hello", arg1, arg2);
+/// ```
 #[proc_macro]
 pub fn register_control_node(input: TokenStream) -> TokenStream {
     register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Control }, NodeTypeInternal::Control)
@@ -1084,6 +1419,12 @@ pub fn register_control_node(input: TokenStream) -> TokenStream {
 /// 
 /// register_decorator_node!(factory, "TestNode", TestNode, arg1, arg2);
 /// ```
+/// 
+/// A description for the node's manifest can be given before the parameters:
+/// 
+/// ```ignore
+/// register_decorator_node!(factory, "TestNode", TestNode, description = "Says hello", arg1, arg2);
+/// ```
 #[proc_macro]
 pub fn register_decorator_node(input: TokenStream) -> TokenStream {
     register_node(input, quote! { ::behaviortree_rs::basic_types::NodeType::Decorator }, NodeTypeInternal::Decorator)
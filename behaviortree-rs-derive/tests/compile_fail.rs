@@ -0,0 +1,8 @@
+/// Each file in `tests/compile_fail` misuses a macro and must fail to compile
+/// with the error in the `.stderr` file next to it. After changing an error
+/// message, update them with `TRYBUILD=overwrite cargo test`.
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
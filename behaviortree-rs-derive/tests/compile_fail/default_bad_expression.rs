@@ -0,0 +1,9 @@
+use behaviortree_rs::bt_node;
+
+#[bt_node(SyncActionNode)]
+struct MyNode {
+    #[bt(default = "1 +")]
+    foo: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use behaviortree_rs::derive::EnumFromString;
+
+#[derive(EnumFromString)]
+enum Shape {
+    Circle(f64),
+    Square,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use behaviortree_rs::derive::EnumFromString;
+
+#[derive(EnumFromString)]
+struct Point(f64, f64);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use behaviortree_rs::bt_node;
+
+#[bt_node()]
+struct MyNode {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use behaviortree_rs::bt_node;
+
+#[bt_node(nodes::ControlNode)]
+struct MyNode {}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use behaviortree_rs::bt_node;
+
+#[bt_node(SyncActionNode)]
+struct MyNode(u32);
+
+fn main() {}
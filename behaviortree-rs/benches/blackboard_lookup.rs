@@ -0,0 +1,51 @@
+//! Cost of reading a key of the root through a 4-level hierarchy of
+//! auto-remapped Blackboards, like nested subtrees with `_autoremap="true"`.
+//!
+//! `uncached` is the first lookup from a new leaf, which walks the parent
+//! chain as every lookup did before entries were cached. `cached` is a
+//! repeated lookup. `global` reads the key as `@key`, which skips the
+//! intermediate levels.
+
+use behaviortree_rs::Blackboard;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+/// Returns the root and the leaf of a 4-level hierarchy with auto-remapping.
+fn hierarchy() -> (Blackboard, Blackboard) {
+    let mut root = Blackboard::create();
+    root.set_sync("battery", 80u32);
+
+    let mut leaf = root.clone();
+    for _ in 0..3 {
+        leaf = Blackboard::with_parent_sync(&leaf);
+        leaf.enable_auto_remapping_sync(true);
+    }
+
+    (root, leaf)
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blackboard_4_levels");
+
+    group.bench_function("uncached", |b| {
+        b.iter_batched(
+            || hierarchy().1,
+            |mut leaf| black_box(leaf.get_sync::<u32>("battery")),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let (_root, mut leaf) = hierarchy();
+    leaf.get_sync::<u32>("battery");
+    group.bench_function("cached", |b| {
+        b.iter(|| black_box(leaf.get_sync::<u32>("battery")))
+    });
+
+    group.bench_function("global", |b| {
+        b.iter(|| black_box(leaf.get_sync::<u32>("@battery")))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+use behaviortree_rs::{
+    basic_types::NodeStatus,
+    nodes::action::TestNodeConfig,
+    tree::{SubstitutionRule, TreeBuilder},
+    Blackboard,
+};
+
+/// A robot that finds a cup and picks it up. None of its nodes are
+/// implemented yet: substitution rules replace them with test nodes, so the
+/// tree's logic can be tried out on its own.
+const XML: &str = r#"
+    <root main_tree_to_execute="main">
+        <BehaviorTree ID="main">
+            <Sequence>
+                <FindCup />
+                <Fallback>
+                    <PickUp object="cup" />
+                    <AskForHelp />
+                </Fallback>
+            </Sequence>
+        </BehaviorTree>
+    </root>
+"#;
+
+fn main() {
+    let mut tree = TreeBuilder::new()
+        .register(|factory| {
+            // Searching takes three ticks
+            factory.add_substitution_rule(
+                "FindCup",
+                SubstitutionRule::TestNode(TestNodeConfig {
+                    running_ticks: 3,
+                    ..Default::default()
+                }),
+            );
+            // The first grasp slips
+            factory.add_substitution_rule(
+                "Pick*",
+                SubstitutionRule::TestNode(TestNodeConfig {
+                    statuses: vec![NodeStatus::Failure, NodeStatus::Success],
+                    ..Default::default()
+                }),
+            );
+            // Nobody is around to help
+            factory.add_substitution_rule(
+                "*",
+                SubstitutionRule::TestNode(TestNodeConfig {
+                    statuses: vec![NodeStatus::Failure],
+                    ..Default::default()
+                }),
+            );
+        })
+        .xml(XML)
+        .build_sync(&Blackboard::create())
+        .unwrap();
+
+    for attempt in 1..=2 {
+        let mut ticks = 1;
+        let mut status = tree.tick_once().unwrap();
+        while status == NodeStatus::Running {
+            status = tree.tick_once().unwrap();
+            ticks += 1;
+        }
+
+        println!("Attempt {attempt}: {status} after {ticks} ticks");
+    }
+}
@@ -0,0 +1,114 @@
+use behaviortree_rs::{
+    basic_types::{NodeStatus, PortsList},
+    bt_node,
+    macros::{define_ports, input_port, register_action_node},
+    nodes::{AsyncHalt, AsyncTick, NodePorts, NodeResult},
+    sync::BoxFuture,
+    tree::TreeBuilder,
+    Blackboard,
+};
+
+/// A guard that patrols until an intruder comes close, chases it, and
+/// attacks once it's in reach. The state lives in the `{state}` entry:
+/// `Match` runs the branch of the current state, `SetState` switches to
+/// another one, and `InState` checks it.
+const XML: &str = r#"
+    <root main_tree_to_execute="main">
+        <BehaviorTree ID="main">
+            <Sequence>
+                <Match variable="{state}" enum="GuardState" cases="Patrol;Chase;Attack">
+                    <Fallback>
+                        <Sequence>
+                            <ScriptCondition code="distance < 10" />
+                            <SetState enum="GuardState" state="Chase" />
+                        </Sequence>
+                        <Say message="patrolling" />
+                    </Fallback>
+                    <Fallback>
+                        <Sequence>
+                            <ScriptCondition code="distance < 2" />
+                            <SetState enum="GuardState" state="Attack" />
+                        </Sequence>
+                        <Sequence>
+                            <ScriptCondition code="distance > 15" />
+                            <SetState enum="GuardState" state="Patrol" />
+                        </Sequence>
+                        <Say message="chasing" />
+                    </Fallback>
+                    <Sequence>
+                        <Say message="attacking" />
+                        <SetState enum="GuardState" state="Chase" />
+                    </Sequence>
+                </Match>
+                <Fallback>
+                    <InState enum="GuardState" state="Patrol" />
+                    <Say message="sounding the alarm" />
+                </Fallback>
+            </Sequence>
+        </BehaviorTree>
+    </root>
+"#;
+
+#[derive(Clone, Debug)]
+enum GuardState {
+    Patrol,
+    Chase,
+    Attack,
+}
+
+impl std::fmt::Display for GuardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// Prints its `message` port.
+#[bt_node(SyncActionNode)]
+struct Say {}
+
+impl AsyncTick for Say {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let message: String = self.config.get_input("message").await?;
+            println!("  {message}");
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for Say {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("message"))
+    }
+}
+
+impl AsyncHalt for Say {}
+
+fn main() {
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("state", GuardState::Patrol);
+
+    let mut tree = TreeBuilder::new()
+        .register(|factory| {
+            factory.register_enum(
+                "GuardState",
+                [GuardState::Patrol, GuardState::Chase, GuardState::Attack],
+            );
+            register_action_node!(factory, "Say", Say);
+        })
+        .xml(XML)
+        .build_sync(&blackboard)
+        .unwrap();
+
+    // How far away the intruder is in each tick
+    for distance in [20_i64, 8, 5, 1, 3, 20] {
+        blackboard.set_sync("distance", distance);
+        println!("Intruder at {distance}m:");
+
+        tree.tick_once().unwrap();
+
+        let state = blackboard.get_exact_sync::<GuardState>("state").unwrap();
+        println!("  -> {state}");
+    }
+}
@@ -0,0 +1,65 @@
+use behaviortree_rs::{
+    basic_types::{NodeStatus, PortsList},
+    bt_node,
+    macros::{define_ports, input_port, register_action_node},
+    nodes::{AsyncHalt, AsyncTick, NodePorts, NodeResult},
+    sync::BoxFuture,
+    tree::TreeBuilder,
+    Blackboard,
+};
+
+const XML: &str = r#"
+    <root main_tree_to_execute="main">
+        <BehaviorTree ID="main">
+            <Sequence>
+                <SaySomething message="hello" />
+                <SubTree ID="greet" greeting="{greeting}" />
+            </Sequence>
+        </BehaviorTree>
+
+        <BehaviorTree ID="greet">
+            <SaySomething message="{greeting}" />
+        </BehaviorTree>
+    </root>
+"#;
+
+/// Prints its `message` port, prefixed with `prefix`.
+#[bt_node(SyncActionNode)]
+struct SaySomething {
+    prefix: String,
+}
+
+impl AsyncTick for SaySomething {
+    fn tick(&mut self) -> BoxFuture<'_, NodeResult> {
+        Box::pin(async move {
+            let message: String = self.config.get_input("message").await?;
+            println!("{}{message}", self.prefix);
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for SaySomething {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("message"))
+    }
+}
+
+impl AsyncHalt for SaySomething {}
+
+fn main() {
+    let prefix = String::from("Robot says: ");
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("greeting", String::from("how are you?"));
+
+    let mut tree = TreeBuilder::new()
+        .register(|factory| register_action_node!(factory, "SaySomething", SaySomething, prefix))
+        .xml(XML)
+        .build_sync(&blackboard)
+        .unwrap();
+
+    let status = tree.tick_while_running().unwrap();
+    println!("Tree finished with {status:?}");
+}
@@ -11,6 +11,7 @@ use crate::{
 
 /// Specifies all types of nodes that can be used in a behavior tree.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum NodeType {
     Undefined,
     /// Leaf node that executes an action
@@ -94,6 +95,30 @@ pub enum ParseNodeStatusError {
     NoMatch,
 }
 
+/// Error of the `FromString` implementation generated by
+/// `#[derive(EnumFromString)]` for a string that isn't a variant name.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("[{value}] isn't a variant of {enum_name}. Expected one of: {}", variants.join(", "))]
+pub struct ParseEnumError {
+    pub value: String,
+    pub enum_name: &'static str,
+    pub variants: &'static [&'static str],
+}
+
+impl ParseEnumError {
+    pub fn new(
+        value: impl Into<String>,
+        enum_name: &'static str,
+        variants: &'static [&'static str],
+    ) -> ParseEnumError {
+        Self {
+            value: value.into(),
+            enum_name,
+            variants,
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ParseNodeTypeError {
     #[error("string didn't match any NodeType values")]
@@ -107,6 +132,7 @@ pub enum ParsePortDirectionError {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum PortDirection {
     Input,
     Output,
@@ -125,6 +151,107 @@ impl std::fmt::Display for PortDirection {
     }
 }
 
+/// Resolves a parallel threshold against the number of children. Negative
+/// values count from the end, Python-style, so `-1` means all children.
+pub fn resolve_parallel_threshold(threshold: i32, children_count: usize) -> usize {
+    if threshold < 0 {
+        ((children_count as i32) + threshold + 1).max(0) as usize
+    } else {
+        threshold as usize
+    }
+}
+
+/// Decides the status of a parallel node from the latest status of each child,
+/// following the BT.CPP rules:
+///
+/// - `Skipped` if every child was skipped.
+/// - `Success` once the number of successful children reaches `success_threshold`.
+///   If the threshold is negative (relative to the child count), skipped children
+///   count as successful too.
+/// - `Failure` once enough children failed that `success_threshold` can't be
+///   reached anymore, or the number of failures reaches `failure_threshold`.
+/// - `Running` otherwise.
+///
+/// Thresholds are resolved with `resolve_parallel_threshold()`.
+///
+/// # Examples
+///
+/// ```
+/// use behaviortree_rs::basic_types::{aggregate_parallel, NodeStatus};
+///
+/// let statuses = [NodeStatus::Success, NodeStatus::Running, NodeStatus::Failure];
+///
+/// assert_eq!(aggregate_parallel(&statuses, 1, 2), NodeStatus::Success);
+/// assert_eq!(aggregate_parallel(&statuses, -1, 2), NodeStatus::Failure);
+/// assert_eq!(aggregate_parallel(&statuses, 2, 2), NodeStatus::Running);
+/// ```
+pub fn aggregate_parallel(
+    statuses: &[NodeStatus],
+    success_threshold: i32,
+    failure_threshold: i32,
+) -> NodeStatus {
+    let children_count = statuses.len();
+    let required_success_count = resolve_parallel_threshold(success_threshold, children_count);
+    let required_failure_count = resolve_parallel_threshold(failure_threshold, children_count);
+
+    let count = |status: NodeStatus| statuses.iter().filter(|s| **s == status).count();
+    let success_count = count(NodeStatus::Success);
+    let failure_count = count(NodeStatus::Failure);
+    let skipped_count = count(NodeStatus::Skipped);
+
+    if children_count > 0 && skipped_count == children_count {
+        NodeStatus::Skipped
+    } else if success_count >= required_success_count
+        || (success_threshold < 0 && success_count + skipped_count >= required_success_count)
+    {
+        NodeStatus::Success
+    } else if children_count - failure_count < required_success_count
+        || failure_count >= required_failure_count
+    {
+        NodeStatus::Failure
+    } else {
+        NodeStatus::Running
+    }
+}
+
+/// Decides the status of a parallel node where only the children at the
+/// indexes in `required` have to succeed:
+///
+/// - `Failure` as soon as a required child fails.
+/// - `Skipped` if all children were skipped.
+/// - `Success` once every required child succeeded or was skipped.
+/// - `Running` otherwise.
+///
+/// Failures of the other children are ignored. Indexes outside of `statuses`
+/// are ignored too.
+///
+/// # Examples
+///
+/// ```
+/// use behaviortree_rs::basic_types::{aggregate_parallel_required, NodeStatus};
+///
+/// let statuses = [NodeStatus::Success, NodeStatus::Running, NodeStatus::Failure];
+///
+/// assert_eq!(aggregate_parallel_required(&statuses, &[0]), NodeStatus::Success);
+/// assert_eq!(aggregate_parallel_required(&statuses, &[0, 1]), NodeStatus::Running);
+/// assert_eq!(aggregate_parallel_required(&statuses, &[1, 2]), NodeStatus::Failure);
+/// ```
+pub fn aggregate_parallel_required(statuses: &[NodeStatus], required: &[usize]) -> NodeStatus {
+    let required_statuses = || required.iter().filter_map(|i| statuses.get(*i));
+
+    if required_statuses().any(|status| *status == NodeStatus::Failure) {
+        NodeStatus::Failure
+    } else if statuses.iter().all(|status| *status == NodeStatus::Skipped) {
+        NodeStatus::Skipped
+    } else if required_statuses()
+        .all(|status| matches!(status, NodeStatus::Success | NodeStatus::Skipped))
+    {
+        NodeStatus::Success
+    } else {
+        NodeStatus::Running
+    }
+}
+
 // ===========================
 // Converting string to types
 // ===========================
@@ -141,6 +268,10 @@ impl std::fmt::Display for PortDirection {
 /// You can also just implement `FromString` yourself, but it's recommended
 /// to implement `FromStr` that also provides the `::parse()` function.
 ///
+/// For an enum whose variants have no fields, or a tuple struct wrapping
+/// another type, derive `EnumFromString` instead, which doesn't need `FromStr`.
+/// Enum variants are then parsed from their names, ignoring ASCII case.
+///
 /// # Example
 ///
 /// ```
@@ -263,6 +394,14 @@ impl FromString for NodeType {
     }
 }
 
+impl FromStr for NodeType {
+    type Err = ParseNodeTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <NodeType as FromString>::from_string(s)
+    }
+}
+
 impl FromString for PortDirection {
     type Err = ParsePortDirectionError;
 
@@ -276,10 +415,70 @@ impl FromString for PortDirection {
     }
 }
 
+/// Prefix of a port value or blackboard string that holds JSON, as in
+/// BehaviorTree.CPP: `json:{"x": 1.0, "y": 2.0}`.
+#[cfg(feature = "serde")]
+pub const JSON_PREFIX: &str = "json:";
+
+/// Deserializes `value` from JSON, with or without the `json:` prefix. Types
+/// that implement `serde::Deserialize` can use it to implement `FromString`,
+/// so that port values, default values and blackboard strings such as
+/// `json:{"x": 1.0, "y": 2.0}` can be read as them.
+///
+/// Requires the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use behaviortree_rs::basic_types::{from_json_string, FromString};
+///
+/// #[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+/// struct Position2D {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// impl FromString for Position2D {
+///     type Err = serde_json::Error;
+///
+///     fn from_string(value: impl AsRef<str>) -> Result<Self, Self::Err> {
+///         from_json_string(value)
+///     }
+/// }
+///
+/// let position = Position2D::from_string(r#"json:{"x": 9, "y": 10}"#).unwrap();
+/// assert_eq!(position, Position2D { x: 9.0, y: 10.0 });
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_json_string<T>(value: impl AsRef<str>) -> Result<T, serde_json::Error>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let value = value.as_ref().trim_start();
+    serde_json::from_str(value.strip_prefix(JSON_PREFIX).unwrap_or(value))
+}
+
+/// Any JSON, e.g. to keep nested values in the blackboard.
+#[cfg(feature = "serde")]
+impl FromString for serde_json::Value {
+    type Err = serde_json::Error;
+
+    fn from_string(value: impl AsRef<str>) -> Result<serde_json::Value, Self::Err> {
+        from_json_string(value)
+    }
+}
+
 pub trait BTToString {
     fn bt_to_string(&self) -> String;
 }
 
+#[cfg(feature = "serde")]
+impl BTToString for serde_json::Value {
+    fn bt_to_string(&self) -> String {
+        format!("{JSON_PREFIX}{self}")
+    }
+}
+
 impl BTToString for String {
     fn bt_to_string(&self) -> String {
         self.clone()
@@ -315,9 +514,16 @@ impl_into_string!(
 pub type PortsList = HashMap<String, PortInfo>;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TreeNodeManifest {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub node_type: NodeType,
+    #[cfg_attr(feature = "serde", serde(rename = "id"))]
     pub registration_id: String,
+    /// Serialized as a list of ports sorted by name, each with its
+    /// `name`, `direction`, `default` and `description`, plus `type` for
+    /// typed ports.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_ports"))]
     pub ports: PortsList,
     pub description: String,
 }
@@ -338,12 +544,52 @@ impl TreeNodeManifest {
     }
 }
 
+#[cfg(feature = "serde")]
+fn serialize_ports<S: serde::Serializer>(
+    ports: &PortsList,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(serde::Serialize)]
+    struct NamedPort<'a> {
+        name: &'a str,
+        #[serde(flatten)]
+        info: &'a PortInfo,
+    }
+
+    let mut ports: Vec<NamedPort> = ports
+        .iter()
+        .map(|(name, info)| NamedPort { name, info })
+        .collect();
+    ports.sort_by(|a, b| a.name.cmp(b.name));
+
+    serializer.collect_seq(ports)
+}
+
 // ===========================
 // Ports
 // ===========================
 
+/// XML attributes that are consumed by the parser itself and never passed to a
+/// node as ports. Nodes can't declare ports with these names.
+pub const RESERVED_ATTRIBUTES: &[&str] = &[
+    "name",
+    "ID",
+    "_autoremap",
+    "_isolated",
+    "_shared",
+    "_failureIf",
+    "_successIf",
+    "_skipIf",
+    "_while",
+    "_onSuccess",
+    "_onFailure",
+    "_onHalted",
+    "_post",
+];
+
 pub trait PortChecks {
     fn is_allowed_port_name(&self) -> bool;
+    fn is_reserved_attribute(&self) -> bool;
 }
 
 impl<T: AsRef<str>> PortChecks for T {
@@ -361,6 +607,10 @@ impl<T: AsRef<str>> PortChecks for T {
             !(name == "name" || name == "ID")
         }
     }
+
+    fn is_reserved_attribute(&self) -> bool {
+        RESERVED_ATTRIBUTES.contains(&self.as_ref())
+    }
 }
 
 pub type PortsRemapping = HashMap<String, String>;
@@ -383,10 +633,22 @@ where
 impl<T> PortValue for T where T: Any + PortClone + Debug + BTToString {}
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PortInfo {
+    #[cfg_attr(feature = "serde", serde(rename = "direction"))]
     r#type: PortDirection,
     description: String,
+    #[cfg_attr(feature = "serde", serde(rename = "default"))]
     default_value: Option<String>,
+    /// Keep leading and trailing whitespace of the port value
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raw: bool,
+    /// `std::any::type_name()` of the value, for typed ports
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "type", skip_serializing_if = "Option::is_none")
+    )]
+    type_name: Option<&'static str>,
 }
 
 impl PortInfo {
@@ -395,6 +657,8 @@ impl PortInfo {
             r#type: direction,
             description: String::new(),
             default_value: None,
+            raw: false,
+            type_name: None,
         }
     }
 
@@ -418,9 +682,34 @@ impl PortInfo {
         self.description = description
     }
 
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
     pub fn direction(&self) -> &PortDirection {
         &self.r#type
     }
+
+    /// Input values are trimmed before they're parsed, and an all-whitespace
+    /// value counts as unset. A raw port is read exactly as written instead.
+    pub fn set_raw(&mut self, raw: bool) {
+        self.raw = raw
+    }
+
+    pub fn is_raw(&self) -> bool {
+        self.raw
+    }
+
+    /// Records `T` as the type of the port's value, so it shows up in the
+    /// node's manifest. Ports are untyped by default.
+    pub fn set_type<T: ?Sized>(&mut self) {
+        self.type_name = Some(std::any::type_name::<T>())
+    }
+
+    /// Returns the fully-qualified name of the port's type, if it has one.
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.type_name
+    }
 }
 
 pub struct Port(String, PortInfo);
@@ -438,6 +727,18 @@ impl Port {
         self
     }
 
+    /// Marks the port as raw, see `PortInfo::set_raw()`.
+    pub fn raw(mut self) -> Port {
+        self.1.set_raw(true);
+        self
+    }
+
+    /// Records `T` as the port's type, see `PortInfo::set_type()`.
+    pub fn typed<T: ?Sized>(mut self) -> Port {
+        self.1.set_type::<T>();
+        self
+    }
+
     pub fn input(name: &str) -> Port {
         Self::input_description(name, "")
     }
@@ -488,3 +789,138 @@ impl AttrsToMap for Attributes<'_> {
         Ok(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use NodeStatus::{Failure, Idle, Running, Skipped, Success};
+
+    #[test]
+    fn node_type_strings() {
+        let node_types = [
+            NodeType::Undefined,
+            NodeType::Action,
+            NodeType::Condition,
+            NodeType::Control,
+            NodeType::Decorator,
+            NodeType::SubTree,
+        ];
+
+        for node_type in node_types {
+            assert_eq!(
+                node_type.to_string().parse::<NodeType>().unwrap(),
+                node_type
+            );
+        }
+
+        assert_eq!("Control".parse::<NodeType>().unwrap(), NodeType::Control);
+        assert!("control".parse::<NodeType>().is_err());
+    }
+
+    #[test]
+    fn parallel_thresholds() {
+        assert_eq!(resolve_parallel_threshold(2, 4), 2);
+        assert_eq!(resolve_parallel_threshold(0, 4), 0);
+        assert_eq!(resolve_parallel_threshold(-1, 4), 4);
+        assert_eq!(resolve_parallel_threshold(-2, 4), 3);
+        assert_eq!(resolve_parallel_threshold(-10, 4), 0);
+    }
+
+    #[test]
+    fn parallel_success() {
+        assert_eq!(
+            aggregate_parallel(&[Success, Running, Running], 1, 1),
+            Success
+        );
+        assert_eq!(
+            aggregate_parallel(&[Success, Success, Running], 2, -1),
+            Success
+        );
+        assert_eq!(
+            aggregate_parallel(&[Success, Success, Success], -1, -1),
+            Success
+        );
+        assert_eq!(
+            aggregate_parallel(&[Success, Success, Running], -2, -1),
+            Success
+        );
+        // Skipped children count as successful only for relative thresholds
+        assert_eq!(
+            aggregate_parallel(&[Success, Skipped, Skipped], -1, 1),
+            Success
+        );
+        assert_eq!(
+            aggregate_parallel(&[Success, Skipped, Skipped], 3, 1),
+            Running
+        );
+        assert_eq!(
+            aggregate_parallel(&[Success, Skipped, Running], 2, 2),
+            Running
+        );
+        // Success is checked before failure
+        assert_eq!(aggregate_parallel(&[Success, Failure], 1, 1), Success);
+    }
+
+    #[test]
+    fn parallel_failure() {
+        assert_eq!(
+            aggregate_parallel(&[Failure, Running, Running], 1, 1),
+            Failure
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Running, Running], -1, 2),
+            Failure
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Failure, Running], 1, 2),
+            Failure
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Failure, Failure], 1, -1),
+            Failure
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Success, Running], 1, -1),
+            Success
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Running, Running], 2, 2),
+            Running
+        );
+        assert_eq!(
+            aggregate_parallel(&[Failure, Failure, Running], 1, -1),
+            Running
+        );
+    }
+
+    #[test]
+    fn parallel_running() {
+        assert_eq!(aggregate_parallel(&[Running, Running], -1, 1), Running);
+        assert_eq!(aggregate_parallel(&[Idle, Idle, Idle], -1, -1), Running);
+        assert_eq!(
+            aggregate_parallel(&[Success, Idle, Running], -1, -1),
+            Running
+        );
+    }
+
+    #[test]
+    fn parallel_skipped() {
+        assert_eq!(
+            aggregate_parallel(&[Skipped, Skipped, Skipped], 1, 1),
+            Skipped
+        );
+        assert_eq!(
+            aggregate_parallel(&[Skipped, Skipped, Skipped], 2, -1),
+            Skipped
+        );
+        // A relative success threshold is met by skipped children, unless
+        // all of them were skipped
+        assert_eq!(
+            aggregate_parallel(&[Skipped, Skipped, Skipped], -1, 1),
+            Skipped
+        );
+        assert_eq!(aggregate_parallel(&[Skipped, Success], -1, 1), Success);
+        assert_eq!(aggregate_parallel(&[], -1, -1), Success);
+    }
+}
@@ -1,9 +1,23 @@
-use std::{any::Any, collections::HashMap, sync::Arc};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use futures::future::BoxFuture;
-use tokio::sync::{Mutex, RwLock};
+use log::warn;
+use thiserror::Error;
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Mutex, OwnedMappedMutexGuard, OwnedMutexGuard, RwLock,
+};
 
-use crate::basic_types::{FromString, ParseStr};
+use crate::basic_types::{FromString, NodeStatus, ParseStr};
 
 /// Trait that provides `strip_bb_pointer()` for all `AsRef<str>`,
 /// which includes `String` and `&str`.
@@ -73,6 +87,11 @@ where
 ///
 /// Provides methods `get<T>()`, `get_exact<T>()`, and `set<T>()`.
 ///
+/// A key starting with `@`, e.g. `@battery`, refers to the entry `battery`
+/// of the root of the parent chain, see `root()`, like the global entries of
+/// BehaviorTree.CPP. This holds for every method that takes a key, and also
+/// applies to ports, e.g. `level="{@battery}"`.
+///
 /// ## get
 ///
 /// When reading from the Blackboard, a String will attempt to be coerced to
@@ -89,6 +108,10 @@ where
 pub struct Blackboard {
     data: Arc<RwLock<BlackboardData>>,
     parent_bb: Box<Option<Blackboard>>,
+    /// Counts the entries removed from any Blackboard of the hierarchy, and
+    /// the changes of its remapping rules, shared by all of them. Children
+    /// check it before using the entries of a parent they cached.
+    removals: Arc<AtomicU64>,
 }
 
 #[derive(Debug)]
@@ -96,11 +119,187 @@ pub struct BlackboardData {
     storage: HashMap<String, EntryPtr>,
     internal_to_external: HashMap<String, String>,
     auto_remapping: bool,
+    /// Limit on the number of keys in `storage`, see `Blackboard::set_max_entries()`
+    max_entries: Option<usize>,
+    eviction_policy: EvictionPolicy,
+    /// When each key was last used, only tracked while there's a limit
+    last_used: HashMap<String, u64>,
+    clock: u64,
+    /// Subscribers of each key, with the key they subscribed as. See
+    /// `Blackboard::subscribe()`.
+    subscribers: HashMap<String, Vec<(String, UnboundedSender<ValueChange>)>>,
+    /// Keys in `storage` that hold an entry of a parent, cached by a lookup
+    /// through remapping. They don't count toward `max_entries`.
+    remapped: HashSet<String>,
+    /// Value of `Blackboard::removals` when the cached entries were last
+    /// known to be valid
+    removals_seen: u64,
+}
+
+impl BlackboardData {
+    fn new(removals_seen: u64) -> BlackboardData {
+        Self {
+            storage: HashMap::new(),
+            internal_to_external: HashMap::new(),
+            auto_remapping: false,
+            max_entries: None,
+            eviction_policy: EvictionPolicy::Reject,
+            last_used: HashMap::new(),
+            clock: 0,
+            subscribers: HashMap::new(),
+            remapped: HashSet::new(),
+            removals_seen,
+        }
+    }
+
+    /// Number of keys in `storage` that hold entries of this Blackboard
+    fn own_len(&self) -> usize {
+        self.storage.len() - self.remapped.len()
+    }
+
+    /// Drops the cached entries of parents if an entry was removed anywhere in
+    /// the hierarchy since they were cached. They're looked up again then.
+    fn drop_stale_entries(&mut self, removals: &AtomicU64) {
+        let removals = removals.load(Ordering::Acquire);
+        if removals == self.removals_seen {
+            return;
+        }
+
+        for key in self.remapped.drain() {
+            self.storage.remove(&key);
+        }
+        self.removals_seen = removals;
+    }
+
+    /// Caches `entry` of a parent at `key`. Unlike `insert()`, this never
+    /// fails and never evicts another entry.
+    fn cache(&mut self, key: &str, entry: EntryPtr) {
+        self.storage.insert(key.to_string(), entry);
+        self.remapped.insert(key.to_string());
+    }
+
+    /// Marks `key` as used, for `EvictionPolicy::LeastRecentlyUsed`.
+    fn touch(&mut self, key: &str) {
+        if self.max_entries.is_some() {
+            self.clock += 1;
+            self.last_used.insert(key.to_string(), self.clock);
+        }
+    }
+
+    /// Stores `entry` at `key`, making room for a new key according to the
+    /// eviction policy if the Blackboard is full. Evictions are counted in
+    /// `removals`.
+    fn insert(
+        &mut self,
+        key: &str,
+        entry: EntryPtr,
+        removals: &AtomicU64,
+    ) -> Result<(), BlackboardFullError> {
+        if let Some(max_entries) = self.max_entries {
+            while !self.storage.contains_key(key) && self.own_len() >= max_entries {
+                let evicted = match self.eviction_policy {
+                    EvictionPolicy::Reject => None,
+                    // Evicting can't make room if there can't be any entries
+                    EvictionPolicy::LeastRecentlyUsed if max_entries == 0 => None,
+                    EvictionPolicy::LeastRecentlyUsed => self
+                        .storage
+                        .keys()
+                        .filter(|key| !self.remapped.contains(*key))
+                        // Keys without a timestamp were added before the limit
+                        .min_by_key(|key| self.last_used.get(*key).copied().unwrap_or(0))
+                        .cloned(),
+                };
+
+                match evicted {
+                    Some(evicted) => {
+                        self.storage.remove(&evicted);
+                        self.last_used.remove(&evicted);
+                        removals.fetch_add(1, Ordering::AcqRel);
+                    }
+                    None => {
+                        return Err(BlackboardFullError {
+                            key: key.to_string(),
+                            max_entries,
+                        })
+                    }
+                }
+            }
+        }
+
+        self.storage.insert(key.to_string(), entry);
+        self.touch(key);
+
+        Ok(())
+    }
+}
+
+/// What a `Blackboard` with a limit on its entries does when a new key
+/// is added while it's full. See `Blackboard::set_max_entries()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Keep the existing entries and refuse the new key.
+    Reject,
+    /// Remove the entry that was read or written least recently.
+    LeastRecentlyUsed,
+}
+
+/// Sent to the subscribers of a key when it's set, see `Blackboard::subscribe()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    /// The key as it was subscribed to
+    pub key: String,
+    /// `std::any::type_name()` of the new value
+    pub type_name: &'static str,
+    /// `sequence_id()` of the entry after the change
+    pub sequence_id: u64,
+}
+
+/// Returned when a key can't be added to a full `Blackboard`.
+#[derive(Debug, Clone, Error)]
+#[error("Blackboard is full ({max_entries} entries), can't add key [{key}]")]
+pub struct BlackboardFullError {
+    pub key: String,
+    pub max_entries: usize,
+}
+
+/// Returned by `Blackboard::try_get<T>()` when it can't return a value.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum BlackboardError {
+    #[error("Couldn't find entry in blackboard [{0}]")]
+    /// There's no value at the key
+    Missing(String),
+    #[error("Blackboard entry [{key}] is of type [{found}], expected [{expected}]")]
+    /// The value at `key` isn't a `T`, nor a string that can be parsed into
+    /// one. `found` is the type name of the stored value.
+    TypeMismatch {
+        key: String,
+        expected: &'static str,
+        found: &'static str,
+    },
 }
 
 #[derive(Debug)]
 pub struct Entry {
     pub value: Box<dyn Any + Send>,
+    /// `std::any::type_name()` of `value`, captured when it was set
+    pub type_name: &'static str,
+    /// How often the value was set, 0 for an entry that was never set.
+    /// `get<T>()` storing a parsed string doesn't count.
+    pub sequence_id: u64,
+}
+
+impl Entry {
+    /// Replaces the value, keeping `type_name` in sync.
+    pub fn set<T: Any + Send>(&mut self, value: T) {
+        self.replace(value);
+        self.sequence_id = self.sequence_id.wrapping_add(1);
+    }
+
+    /// Replaces the value without counting it as a write.
+    fn replace<T: Any + Send>(&mut self, value: T) {
+        self.value = Box::new(value);
+        self.type_name = std::any::type_name::<T>();
+    }
 }
 
 pub type BlackboardPtr = Arc<RwLock<Blackboard>>;
@@ -108,15 +307,36 @@ pub type BlackboardDataPtr = Arc<RwLock<BlackboardData>>;
 
 pub type EntryPtr = Arc<Mutex<Entry>>;
 
+/// Mutable access to a value stored in a `Blackboard`, returned by
+/// `Blackboard::get_mut<T>()`. The entry is locked until it's dropped.
+pub type EntryMut<T> = OwnedMappedMutexGuard<Entry, T>;
+
+/// Shared access to a value stored in a `Blackboard`, returned by
+/// `Blackboard::get_ref<T>()`. The entry is locked until it's dropped.
+#[derive(Debug)]
+pub struct EntryRef<T>(OwnedMappedMutexGuard<Entry, T>);
+
+impl<T> Deref for EntryRef<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
 impl Blackboard {
     fn new(parent_bb: Option<Blackboard>) -> Blackboard {
+        // The whole hierarchy counts removals together
+        let removals = match &parent_bb {
+            Some(parent_bb) => Arc::clone(&parent_bb.removals),
+            None => Arc::new(AtomicU64::new(0)),
+        };
+        let removals_seen = removals.load(Ordering::Acquire);
+
         Self {
-            data: Arc::new(RwLock::new(BlackboardData {
-                storage: HashMap::new(),
-                internal_to_external: HashMap::new(),
-                auto_remapping: false,
-            })),
+            data: Arc::new(RwLock::new(BlackboardData::new(removals_seen))),
             parent_bb: Box::new(parent_bb),
+            removals,
         }
     }
 
@@ -125,6 +345,10 @@ impl Blackboard {
     }
 
     /// Creates a Blackboard with `parent_bb` as the parent. Returned as a new `BlackboardPtr`.
+    ///
+    /// The parent chain is captured when the child is created and can't be
+    /// changed afterwards, so a blackboard can never become its own ancestor.
+    /// Lookups through remapping always end at the root.
     pub async fn with_parent(parent_bb: &Blackboard) -> Blackboard {
         Self::new(Some(parent_bb.clone()))
     }
@@ -138,14 +362,31 @@ impl Blackboard {
 
     /// Creates a Blackboard with no parent and returns it as a `BlackboardPtr`.
     pub fn create() -> Blackboard {
-        Self {
-            parent_bb: Box::new(None),
-            data: Arc::new(RwLock::new(BlackboardData {
-                storage: HashMap::new(),
-                internal_to_external: HashMap::new(),
-                auto_remapping: false,
-            })),
+        Self::new(None)
+    }
+
+    /// Returns the root of the parent chain, which holds the tree's global
+    /// entries. A `Blackboard` without a parent is its own root.
+    pub fn root(&self) -> Blackboard {
+        let mut blackboard = self.clone();
+        while let Some(parent) = blackboard.parent() {
+            blackboard = parent;
         }
+
+        blackboard
+    }
+
+    /// Returns the root and `key` without its prefix if `key` starts with
+    /// `@`. Every method that takes a key dispatches such keys to the root
+    /// this way, so they never name a literal `@` entry.
+    fn global_key<'a>(&self, key: &'a str) -> Option<(Blackboard, &'a str)> {
+        key.strip_prefix('@').map(|key| (self.root(), key))
+    }
+
+    /// Returns `true` if both are handles to the same Blackboard, i.e. one
+    /// is a clone of the other.
+    pub fn ptr_eq(&self, other: &Blackboard) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
     }
 
     /// Enables the Blackboard to use autoremapping when getting values from
@@ -153,6 +394,8 @@ impl Blackboard {
     /// explicit remapping rule.
     pub async fn enable_auto_remapping(&mut self, use_remapping: bool) {
         self.data.write().await.auto_remapping = use_remapping;
+        // Entries cached through the old rules may be hidden now
+        self.removals.fetch_add(1, Ordering::AcqRel);
     }
 
     /// Sync version of `enable_auto_remapping()`
@@ -172,6 +415,8 @@ impl Blackboard {
             .await
             .internal_to_external
             .insert(internal, external);
+        // Entries cached through the old rules may be hidden now
+        self.removals.fetch_add(1, Ordering::AcqRel);
     }
 
     /// Sync version of add_subtree_remapping
@@ -182,14 +427,384 @@ impl Blackboard {
         crate::sync::block_on(self.add_subtree_remapping(internal, external));
     }
 
+    /// Limits the number of keys this Blackboard stores to `max_entries`, or
+    /// removes the limit with `None`. This guards long-running trees against
+    /// unbounded growth, e.g. a node writing a new key on every tick.
+    ///
+    /// When a new key is added to a full Blackboard, `policy` decides what
+    /// happens:
+    /// - `EvictionPolicy::Reject`: the write fails. `try_set()` returns an
+    ///   error, while `set()` logs a warning and drops the value.
+    /// - `EvictionPolicy::LeastRecentlyUsed`: the entry that was read or
+    ///   written least recently is removed to make room.
+    ///
+    /// Writing to an existing key always succeeds. Entries of a parent that
+    /// are reached through remapping don't count toward the limit, and are
+    /// only evicted by the parent's own limit. Existing entries are kept if
+    /// the limit is lower than `len()`; they're only evicted as new keys are
+    /// added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::{Blackboard, EvictionPolicy};
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// blackboard.set_max_entries(Some(2), EvictionPolicy::LeastRecentlyUsed).await;
+    ///
+    /// blackboard.set("a", 1u32).await;
+    /// blackboard.set("b", 2u32).await;
+    /// blackboard.set("c", 3u32).await;
+    ///
+    /// assert_eq!(blackboard.len().await, 2);
+    /// assert_eq!(blackboard.get::<u32>("a").await, None);
+    /// # })
+    /// ```
+    pub async fn set_max_entries(&mut self, max_entries: Option<usize>, policy: EvictionPolicy) {
+        let mut blackboard = self.data.write().await;
+        blackboard.max_entries = max_entries;
+        blackboard.eviction_policy = policy;
+
+        if max_entries.is_none() {
+            blackboard.last_used.clear();
+        }
+    }
+
+    /// Sync version of `set_max_entries()`
+    ///
+    /// Limits the number of keys this Blackboard stores, or removes the limit with `None`.
+    pub fn set_max_entries_sync(&mut self, max_entries: Option<usize>, policy: EvictionPolicy) {
+        crate::sync::block_on(self.set_max_entries(max_entries, policy))
+    }
+
+    /// Returns the number of keys stored in this Blackboard. Entries of a
+    /// parent that are reached through remapping aren't counted.
+    pub async fn len(&self) -> usize {
+        self.data.read().await.own_len()
+    }
+
+    /// Sync version of `len()`
+    ///
+    /// Returns the number of keys stored in this Blackboard.
+    pub fn len_sync(&self) -> usize {
+        crate::sync::block_on(self.len())
+    }
+
+    /// Returns `true` if this Blackboard stores no keys.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Sync version of `is_empty()`
+    ///
+    /// Returns `true` if this Blackboard stores no keys.
+    pub fn is_empty_sync(&self) -> bool {
+        crate::sync::block_on(self.is_empty())
+    }
+
+    /// Removes the entry at `key` from this Blackboard, e.g. to reset a value
+    /// derived in a previous run of a tree. Returns `true` if this Blackboard
+    /// stored a value for `key` itself.
+    ///
+    /// A key that's remapped to a parent Blackboard, or reached through
+    /// auto-remapping, is left as it is in the parent, and the remapping
+    /// stays. Removing it only returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut parent = Blackboard::create();
+    /// parent.set("goal", String::from("kitchen")).await;
+    ///
+    /// let mut child = Blackboard::with_parent(&parent).await;
+    /// child.add_subtree_remapping(String::from("target"), String::from("goal")).await;
+    /// child.set("value_sqr", 4).await;
+    ///
+    /// assert!(child.remove("value_sqr").await);
+    /// assert!(!child.remove("target").await);
+    ///
+    /// assert_eq!(child.get::<i32>("value_sqr").await, None);
+    /// assert_eq!(child.get::<String>("target").await.as_deref(), Some("kitchen"));
+    /// # })
+    /// ```
+    pub async fn remove(&mut self, key: impl AsRef<str>) -> bool {
+        match self.global_key(key.as_ref()) {
+            Some((mut root, key)) => root.remove_here(key).await,
+            None => self.remove_here(key.as_ref()).await,
+        }
+    }
+
+    /// `remove()` for a key of this Blackboard, i.e. without a `@` prefix
+    async fn remove_here(&mut self, key: &str) -> bool {
+        let mut data = self.data.write().await;
+        let remapped = self.parent_bb.is_some()
+            && (data.auto_remapping || data.internal_to_external.contains_key(key));
+
+        data.last_used.remove(key);
+        let cached = data.remapped.remove(key);
+        let Some(entry) = data.storage.remove(key) else {
+            return false;
+        };
+        if !cached {
+            // Children may have cached the entry
+            self.removals.fetch_add(1, Ordering::AcqRel);
+        }
+        let is_set = !entry.lock().await.value.is::<()>();
+
+        !remapped && is_set
+    }
+
+    /// Sync version of `remove()`
+    ///
+    /// Removes the entry at `key` from this Blackboard. Returns `true` if this
+    /// Blackboard stored a value for `key` itself.
+    pub fn remove_sync(&mut self, key: impl AsRef<str>) -> bool {
+        crate::sync::block_on(self.remove(key))
+    }
+
+    /// Removes all entries of this Blackboard, like calling `remove()` for
+    /// every key. Parent Blackboards, remapping rules, subscriptions and the
+    /// limit set with `set_max_entries()` are kept.
+    pub async fn clear(&mut self) {
+        let mut data = self.data.write().await;
+        if data.own_len() > 0 {
+            self.removals.fetch_add(1, Ordering::AcqRel);
+        }
+        data.storage.clear();
+        data.last_used.clear();
+        data.remapped.clear();
+    }
+
+    /// Sync version of `clear()`
+    ///
+    /// Removes all entries of this Blackboard.
+    pub fn clear_sync(&mut self) {
+        crate::sync::block_on(self.clear())
+    }
+
+    /// Returns the keys that can be read from this Blackboard, sorted. Keys of
+    /// the parent Blackboards are included if they're reachable through
+    /// remapping, under the name they have here. Each key is only listed once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut parent = Blackboard::create();
+    /// parent.set("goal", String::from("kitchen")).await;
+    /// parent.set("speed", 2.5).await;
+    ///
+    /// let mut child = Blackboard::with_parent(&parent).await;
+    /// child.add_subtree_remapping(String::from("target"), String::from("goal")).await;
+    /// child.set("speed", 1.0).await;
+    ///
+    /// assert_eq!(child.keys().await, vec!["speed", "target"]);
+    /// # })
+    /// ```
+    pub async fn keys(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        for (key, entry) in self.visible_entries().await {
+            if !entry.lock().await.value.is::<()>() {
+                keys.push(key);
+            }
+        }
+
+        keys.sort();
+        keys
+    }
+
+    /// Sync version of `keys()`
+    ///
+    /// Returns the keys that can be read from this Blackboard, sorted.
+    pub fn keys_sync(&self) -> Vec<String> {
+        crate::sync::block_on(self.keys())
+    }
+
+    /// Returns the keys that can be read from this Blackboard with their
+    /// values as text, sorted by key, e.g. to show the Blackboard while
+    /// debugging. Keys are listed like in `keys()`.
+    ///
+    /// Values of the types that `import_from()` can copy are shown as text.
+    /// Any other value is shown as its type name in angle brackets, e.g.
+    /// `<alloc::vec::Vec<i32>>`.
+    pub async fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        for (key, entry) in self.visible_entries().await {
+            let entry = entry.lock().await;
+            if entry.value.is::<()>() {
+                continue;
+            }
+
+            let value =
+                display_value(&*entry.value).unwrap_or_else(|| format!("<{}>", entry.type_name));
+            entries.push((key, value));
+        }
+
+        entries.sort();
+        entries
+    }
+
+    /// Returns the value at `key` as text, like `entries()` shows it, e.g. to
+    /// compare a value of any type to a string. Returns `None` if the key is
+    /// missing or its value isn't of a type `import_from()` can copy.
+    pub async fn get_text(&mut self, key: impl AsRef<str>) -> Option<String> {
+        let entry = self.get_entry(key.as_ref()).await?;
+        let entry = entry.lock().await;
+        display_value(&*entry.value)
+    }
+
+    /// Sync version of `get_text()`
+    ///
+    /// Returns the value at `key` as text, like `entries()` shows it.
+    pub fn get_text_sync(&mut self, key: impl AsRef<str>) -> Option<String> {
+        crate::sync::block_on(self.get_text(key))
+    }
+
+    /// Sync version of `entries()`
+    ///
+    /// Returns the keys that can be read from this Blackboard with their
+    /// values as text, sorted by key.
+    pub fn entries_sync(&self) -> Vec<(String, String)> {
+        crate::sync::block_on(self.entries())
+    }
+
+    /// Returns the entries of this Blackboard and those of its parents that
+    /// are reachable through remapping, by the key they have here.
+    fn visible_entries(&self) -> BoxFuture<HashMap<String, EntryPtr>> {
+        Box::pin(async move {
+            let data = self.data.read().await;
+            let mut entries = HashMap::new();
+
+            if let Some(parent_bb) = self.parent_bb.as_ref() {
+                let mut parent_entries = parent_bb.visible_entries().await;
+
+                for (internal, external) in data.internal_to_external.iter() {
+                    if let Some(entry) = parent_entries.get(external) {
+                        entries.insert(internal.clone(), Arc::clone(entry));
+                    }
+                }
+                if data.auto_remapping {
+                    for (key, entry) in parent_entries.drain() {
+                        entries.entry(key).or_insert(entry);
+                    }
+                }
+            }
+
+            for (key, entry) in data.storage.iter() {
+                entries.insert(key.clone(), Arc::clone(entry));
+            }
+
+            entries
+        })
+    }
+
+    /// Copies the entries of `other` into this Blackboard, e.g. to hand off
+    /// the state of one tree to the next. Keys that already have a value here
+    /// are only replaced if `overwrite` is true. Remapping rules of this
+    /// Blackboard apply as for `set()`.
+    ///
+    /// Values are stored as `dyn Any`, so only values of these types can be
+    /// copied: the numeric types, `bool`, `char`, `String`, `&'static str` and
+    /// `NodeStatus`. Entries of any other type are left out, as are entries
+    /// that don't fit under the limit set with `set_max_entries()`; their
+    /// keys are returned, sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut planning = Blackboard::create();
+    /// planning.set("goal", String::from("kitchen")).await;
+    /// planning.set("path", vec![1, 2, 3]).await;
+    ///
+    /// let mut execution = Blackboard::create();
+    /// let skipped = execution.import_from(&planning, false).await;
+    ///
+    /// assert_eq!(execution.get::<String>("goal").await.as_deref(), Some("kitchen"));
+    /// assert_eq!(skipped, vec![String::from("path")]);
+    /// # })
+    /// ```
+    pub async fn import_from(&mut self, other: &Blackboard, overwrite: bool) -> Vec<String> {
+        let mut values = Vec::new();
+        let mut skipped = Vec::new();
+
+        // Copy the values first, so no lock of `other` is held while writing,
+        // which could be the same Blackboard
+        {
+            let other = other.data.read().await;
+            for (key, entry) in other.storage.iter() {
+                let entry = entry.lock().await;
+
+                if entry.value.is::<()>() {
+                    // Created, but never set
+                    continue;
+                }
+
+                match clone_value(&*entry.value) {
+                    Some(value) => values.push((key.clone(), value, entry.type_name)),
+                    None => skipped.push(key.clone()),
+                }
+            }
+        }
+
+        for (key, value, type_name) in values {
+            if !overwrite {
+                if let Some(entry) = self.get_entry(&key).await {
+                    if !entry.lock().await.value.is::<()>() {
+                        continue;
+                    }
+                }
+            }
+
+            let Ok(entry) = self.create_entry(&key).await else {
+                skipped.push(key);
+                continue;
+            };
+            {
+                let mut entry = entry.lock().await;
+                entry.value = value;
+                entry.type_name = type_name;
+                entry.sequence_id = entry.sequence_id.wrapping_add(1);
+            }
+
+            self.notify(&key, &entry).await;
+        }
+
+        skipped.sort();
+        skipped
+    }
+
+    /// Sync version of `import_from()`
+    ///
+    /// Copies the entries of `other` into this Blackboard. Returns the keys of
+    /// entries that couldn't be copied.
+    pub fn import_from_sync(&mut self, other: &Blackboard, overwrite: bool) -> Vec<String> {
+        crate::sync::block_on(self.import_from(other, overwrite))
+    }
+
     /// Get an Rc to the Entry
     fn get_entry<'a>(&'a mut self, key: &'a str) -> BoxFuture<Option<EntryPtr>> {
         Box::pin(async move {
+            if let Some((mut root, key)) = self.global_key(key) {
+                return root.get_entry(key).await;
+            }
+
             let mut blackboard = self.data.write().await;
+            blackboard.drop_stale_entries(&self.removals);
 
             // Try to get the key
             if let Some(entry) = blackboard.storage.get(key) {
-                return Some(Arc::clone(entry));
+                let entry = Arc::clone(entry);
+                blackboard.touch(key);
+                return Some(entry);
             }
             // Couldn't find key. Try remapping if we have a parent
             else if let Some(parent_bb) = self.parent_bb.as_mut() {
@@ -198,9 +813,7 @@ impl Blackboard {
                     let parent_entry = parent_bb.get_entry(new_key).await;
 
                     if let Some(value) = &parent_entry {
-                        blackboard
-                            .storage
-                            .insert(key.to_string(), Arc::clone(value));
+                        blackboard.cache(key, Arc::clone(value));
                     }
 
                     return parent_entry;
@@ -208,7 +821,16 @@ impl Blackboard {
                 // Use auto remapping
                 else if blackboard.auto_remapping {
                     // Return the value of the parent's `get()`
-                    return parent_bb.get_entry(key).await;
+                    let parent_entry = parent_bb.get_entry(key).await;
+
+                    // Cache the resolved entry so deep hierarchies don't walk
+                    // the parent chain on every lookup. The entry is shared, so
+                    // writes from any level stay visible.
+                    if let Some(value) = &parent_entry {
+                        blackboard.cache(key, Arc::clone(value));
+                    }
+
+                    return parent_entry;
                 }
             }
 
@@ -258,9 +880,9 @@ impl Blackboard {
 
             // Try to parse String into T
             if let Ok(value) = <String as ParseStr<T>>::parse_str(&value) {
-                // Update value with the value type instead of just a string
-                let mut t = entry.lock().await;
-                t.value = Box::new(value.clone());
+                // Update value with the value type instead of just a string.
+                // The value stays the same, so this isn't a write.
+                entry.lock().await.replace(value.clone());
                 return Some(value);
             }
         }
@@ -374,6 +996,76 @@ impl Blackboard {
         futures::executor::block_on(self.get(key))
     }
 
+    /// Version of `get<T>` that tells why it couldn't return a value:
+    /// `BlackboardError::Missing` if there's no value at `key`, or
+    /// `BlackboardError::TypeMismatch` if the value isn't a `T` and couldn't be
+    /// parsed into one. Remapping rules and string conversion work as for
+    /// `get<T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::{Blackboard, BlackboardError};
+    ///
+    /// let mut blackboard = Blackboard::create();
+    ///
+    /// blackboard.set("foo", 132u32).await;
+    /// assert_eq!(blackboard.try_get::<u32>("foo").await, Ok(132u32));
+    ///
+    /// assert_eq!(
+    ///     blackboard.try_get::<u32>("bar").await,
+    ///     Err(BlackboardError::Missing(String::from("bar")))
+    /// );
+    /// assert_eq!(
+    ///     blackboard.try_get::<bool>("foo").await,
+    ///     Err(BlackboardError::TypeMismatch {
+    ///         key: String::from("foo"),
+    ///         expected: "bool",
+    ///         found: "u32",
+    ///     })
+    /// );
+    /// # })
+    /// ```
+    pub async fn try_get<T>(&mut self, key: impl AsRef<str>) -> Result<T, BlackboardError>
+    where
+        T: Any + Clone + FromString + Send,
+    {
+        let key = key.as_ref();
+
+        let found = match self.get_entry(key).await {
+            Some(entry) => {
+                let entry = entry.lock().await;
+                // Created, but never set
+                if entry.value.is::<()>() {
+                    None
+                } else {
+                    Some(entry.type_name)
+                }
+            }
+            None => None,
+        };
+        let Some(found) = found else {
+            return Err(BlackboardError::Missing(key.to_string()));
+        };
+
+        self.get(key).await.ok_or(BlackboardError::TypeMismatch {
+            key: key.to_string(),
+            expected: std::any::type_name::<T>(),
+            found,
+        })
+    }
+
+    /// Sync version of `try_get<T>`
+    ///
+    /// Returns the value at `key`, or why it couldn't.
+    pub fn try_get_sync<T>(&mut self, key: impl AsRef<str>) -> Result<T, BlackboardError>
+    where
+        T: Any + Clone + FromString + Send,
+    {
+        futures::executor::block_on(self.try_get(key))
+    }
+
     /// Version of `get<T>` that does _not_ try to convert from string if the type
     /// doesn't match. This method has the benefit of not requiring the trait
     /// `FromString`, which allows you to avoid implementing the trait for
@@ -438,7 +1130,18 @@ impl Blackboard {
         futures::executor::block_on(self.get_exact(key))
     }
 
-    /// Sets the `value` in the Blackboard at `key`.
+    /// Returns mutable access to the value at `key` if it's stored as `T`, to
+    /// change a large value in place instead of with a `get()`/`set()` round
+    /// trip. Like `get_exact<T>()`, this doesn't convert strings.
+    ///
+    /// Remapped keys are looked up like in `get<T>()`, so the value belongs to
+    /// the `Blackboard` that owns the entry and changes are seen everywhere
+    /// it's remapped to.
+    ///
+    /// The entry is locked until the returned guard is dropped, and any other
+    /// access to it waits until then. Don't keep it across an `.await` that
+    /// might use the same key. Getting it counts as a write for
+    /// `sequence_id()`.
     ///
     /// # Examples
     ///
@@ -447,29 +1150,316 @@ impl Blackboard {
     /// use behaviortree_rs::blackboard::Blackboard;
     ///
     /// let mut blackboard = Blackboard::create();
+    /// blackboard.set("points", vec![1u32, 2]).await;
     ///
-    /// blackboard.set("foo", 132u32).await;
-    /// assert_eq!(blackboard.get::<u32>("foo").await, Some(132u32));
-    ///
-    /// blackboard.set("bar", "100").await;
-    ///
-    /// assert_eq!(blackboard.get::<String>("bar").await, Some(String::from("100")));
-    /// assert_eq!(blackboard.get::<u32>("bar").await, Some(100u32));
+    /// blackboard.get_mut::<Vec<u32>>("points").await.unwrap().push(3);
+    /// assert_eq!(blackboard.get_exact::<Vec<u32>>("points").await, Some(vec![1, 2, 3]));
     /// # })
     /// ```
-    pub async fn set<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
-        let key = key.as_ref().to_string();
+    pub async fn get_mut<T>(&mut self, key: impl AsRef<str>) -> Option<EntryMut<T>>
+    where
+        T: Any + Send,
+    {
+        let entry = self.get_entry(key.as_ref()).await?;
+        let mut entry = entry.lock_owned().await;
+        if !entry.value.is::<T>() {
+            return None;
+        }
+        entry.sequence_id = entry.sequence_id.wrapping_add(1);
 
-        let mut blackboard = self.data.write().await;
+        OwnedMutexGuard::try_map(entry, |entry| entry.value.downcast_mut::<T>()).ok()
+    }
 
-        if let Some(entry) = blackboard.storage.get_mut(&key) {
-            entry.lock().await.value = Box::new(value);
-        } else {
-            drop(blackboard);
-            let entry = self.create_entry(&key).await;
+    /// Moves the value at `key` out if it's stored as `T`, leaving
+    /// `T::default()` in its place. Unlike `get_mut()`, this doesn't count as
+    /// a write: the caller writes the value back with `set()`, which also
+    /// notifies the subscribers.
+    pub(crate) async fn take<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: Any + Send + Default,
+    {
+        let entry = self.get_entry(key).await?;
+        let mut entry = entry.lock().await;
+        entry.value.downcast_mut::<T>().map(std::mem::take)
+    }
 
-            // Set value of new entry
-            entry.lock().await.value = Box::new(value);
+    /// Sync version of `get_mut<T>()`
+    ///
+    /// Returns mutable access to the value at `key` if it's stored as `T`.
+    pub fn get_mut_sync<T>(&mut self, key: impl AsRef<str>) -> Option<EntryMut<T>>
+    where
+        T: Any + Send,
+    {
+        futures::executor::block_on(self.get_mut(key))
+    }
+
+    /// Returns a reference to the value at `key` if it's stored as `T`, to
+    /// read a large value without cloning it. Like `get_exact<T>()`, this
+    /// doesn't convert strings, and remapped keys are looked up like in
+    /// `get<T>()`.
+    ///
+    /// The entry is locked until the returned guard is dropped, like with
+    /// `get_mut<T>()`, so don't keep it across an `.await` that might use the
+    /// same key. Unlike `get_mut<T>()`, this doesn't count as a write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// blackboard.set("points", vec![1u32, 2, 3]).await;
+    ///
+    /// let points = blackboard.get_ref::<Vec<u32>>("points").await.unwrap();
+    /// assert_eq!(points.len(), 3);
+    /// # })
+    /// ```
+    pub async fn get_ref<T>(&mut self, key: impl AsRef<str>) -> Option<EntryRef<T>>
+    where
+        T: Any + Send,
+    {
+        let entry = self.get_entry(key.as_ref()).await?;
+        let entry = entry.lock_owned().await;
+
+        OwnedMutexGuard::try_map(entry, |entry| entry.value.downcast_mut::<T>())
+            .ok()
+            .map(EntryRef)
+    }
+
+    /// Sync version of `get_ref<T>()`
+    ///
+    /// Returns a reference to the value at `key` if it's stored as `T`.
+    pub fn get_ref_sync<T>(&mut self, key: impl AsRef<str>) -> Option<EntryRef<T>>
+    where
+        T: Any + Send,
+    {
+        futures::executor::block_on(self.get_ref(key))
+    }
+
+    /// Returns the type name of the value at `key`, as given by
+    /// `std::any::type_name()` when the value was set. Returns `None` if
+    /// there's no value at `key`. Remapping rules apply as for `get()`.
+    ///
+    /// Note that `get<T>()` replaces a string value with the parsed `T`, which
+    /// also changes the type name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    ///
+    /// blackboard.set("foo", 132u32).await;
+    /// assert_eq!(blackboard.entry_type("foo").await, Some("u32"));
+    /// assert_eq!(blackboard.entry_type("bar").await, None);
+    /// # })
+    /// ```
+    pub async fn entry_type(&mut self, key: impl AsRef<str>) -> Option<&'static str> {
+        let entry = self.get_entry(key.as_ref()).await?;
+        let entry = entry.lock().await;
+
+        // Created, but never set
+        if entry.value.is::<()>() {
+            return None;
+        }
+
+        Some(entry.type_name)
+    }
+
+    /// Sync version of `entry_type()`
+    ///
+    /// Returns the type name of the value at `key`, or `None` if there's no value.
+    pub fn entry_type_sync(&mut self, key: impl AsRef<str>) -> Option<&'static str> {
+        futures::executor::block_on(self.entry_type(key))
+    }
+
+    /// Returns how often the entry at `key` was set, or `None` if there's no
+    /// entry. Every `set()` counts, even with an unchanged value, so
+    /// comparing two results tells whether the entry was written in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    ///
+    /// blackboard.set("foo", 1u32).await;
+    /// blackboard.set("foo", 1u32).await;
+    /// assert_eq!(blackboard.sequence_id("foo").await, Some(2));
+    /// assert_eq!(blackboard.sequence_id("bar").await, None);
+    /// # })
+    /// ```
+    pub async fn sequence_id(&mut self, key: impl AsRef<str>) -> Option<u64> {
+        let entry = self.get_entry(key.as_ref()).await?;
+        let sequence_id = entry.lock().await.sequence_id;
+
+        Some(sequence_id)
+    }
+
+    /// Sync version of `sequence_id()`
+    ///
+    /// Returns how often the entry at `key` was set, or `None` if there's no entry.
+    pub fn sequence_id_sync(&mut self, key: impl AsRef<str>) -> Option<u64> {
+        futures::executor::block_on(self.sequence_id(key))
+    }
+
+    /// Returns a receiver that gets a `ValueChange` whenever `key` is set
+    /// with `set()`, `try_set()`, `import_from()` or `TypedEntry::update()`,
+    /// after the value is written. Changes made through `get_mut()` aren't sent.
+    ///
+    /// A key that's remapped to a parent Blackboard, explicitly or with
+    /// auto-remapping, shares the parent's entry. The subscription is kept
+    /// where the entry is stored, so setting the key under either name
+    /// notifies the subscribers of both names, in the order they subscribed.
+    /// The remapping rules are resolved when subscribing.
+    ///
+    /// Changes are sent in the order of the writes. Dropping the receiver
+    /// ends the subscription.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// let mut changes = blackboard.subscribe("target").await;
+    ///
+    /// blackboard.set("target", 3u32).await;
+    /// let change = changes.recv().await.unwrap();
+    /// assert_eq!(change.key, "target");
+    /// assert_eq!(change.sequence_id, 1);
+    /// # })
+    /// ```
+    pub async fn subscribe(&self, key: impl AsRef<str>) -> UnboundedReceiver<ValueChange> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscribe_with(key.as_ref(), sender).await;
+
+        receiver
+    }
+
+    /// Like `subscribe()`, but sends the changes to `sender`, so several keys
+    /// can share one receiver. Subscribing the same name and channel again
+    /// does nothing.
+    pub(crate) async fn subscribe_with(&self, key: &str, sender: UnboundedSender<ValueChange>) {
+        let (owner, owner_key) = self.owner(key).await;
+
+        let mut data = owner.data.write().await;
+        let subscribers = data.subscribers.entry(owner_key).or_default();
+        // Receivers that were dropped are removed here as well, so a key
+        // that's never set doesn't keep them
+        subscribers.retain(|(_, sender)| !sender.is_closed());
+        if !subscribers
+            .iter()
+            .any(|(name, other)| name == key && other.same_channel(&sender))
+        {
+            subscribers.push((key.to_string(), sender));
+        }
+    }
+
+    /// Sync version of `subscribe()`
+    ///
+    /// Returns a receiver that gets a `ValueChange` whenever `key` is set.
+    pub fn subscribe_sync(&self, key: impl AsRef<str>) -> UnboundedReceiver<ValueChange> {
+        futures::executor::block_on(self.subscribe(key))
+    }
+
+    /// Returns the Blackboard that stores the entry for `key`, following the
+    /// remapping rules to the parents, and the key there.
+    async fn owner(&self, key: &str) -> (Blackboard, String) {
+        if let Some((root, key)) = self.global_key(key) {
+            return (root, key.to_string());
+        }
+
+        let mut blackboard = self.clone();
+        let mut key = key.to_string();
+
+        while let Some(parent) = blackboard.parent() {
+            let parent_key = {
+                let data = blackboard.data.read().await;
+                match data.internal_to_external.get(&key) {
+                    Some(external) => external.clone(),
+                    None if data.auto_remapping => key,
+                    None => break,
+                }
+            };
+
+            blackboard = parent;
+            key = parent_key;
+        }
+
+        (blackboard, key)
+    }
+
+    /// Sends a `ValueChange` for `entry` to the subscribers of `key`.
+    async fn notify(&self, key: &str, entry: &EntryPtr) {
+        let (type_name, sequence_id) = {
+            let entry = entry.lock().await;
+            (entry.type_name, entry.sequence_id)
+        };
+        let (owner, owner_key) = self.owner(key).await;
+
+        let mut data = owner.data.write().await;
+        if let Some(subscribers) = data.subscribers.get_mut(&owner_key) {
+            // Receivers that were dropped are removed
+            subscribers.retain(|(key, sender)| {
+                let change = ValueChange {
+                    key: key.clone(),
+                    type_name,
+                    sequence_id,
+                };
+                sender.send(change).is_ok()
+            });
+        }
+    }
+
+    /// Returns the `sequence_id()` of every entry stored in this Blackboard,
+    /// without looking at parents.
+    pub(crate) async fn sequence_ids(&self) -> HashMap<String, u64> {
+        let entries: Vec<(String, EntryPtr)> = self
+            .data
+            .read()
+            .await
+            .storage
+            .iter()
+            .map(|(key, entry)| (key.clone(), Arc::clone(entry)))
+            .collect();
+
+        let mut sequence_ids = HashMap::new();
+        for (key, entry) in entries {
+            sequence_ids.insert(key, entry.lock().await.sequence_id);
+        }
+
+        sequence_ids
+    }
+
+    /// Sets the `value` in the Blackboard at `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    ///
+    /// blackboard.set("foo", 132u32).await;
+    /// assert_eq!(blackboard.get::<u32>("foo").await, Some(132u32));
+    ///
+    /// blackboard.set("bar", "100").await;
+    ///
+    /// assert_eq!(blackboard.get::<String>("bar").await, Some(String::from("100")));
+    /// assert_eq!(blackboard.get::<u32>("bar").await, Some(100u32));
+    /// # })
+    /// ```
+    pub async fn set<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
+        if let Err(e) = self.try_set(key, value).await {
+            warn!("{e}");
         }
     }
 
@@ -498,15 +1488,139 @@ impl Blackboard {
         futures::executor::block_on(self.set(key, value))
     }
 
-    fn create_entry<'a>(&'a mut self, key: &'a (impl AsRef<str> + Sync)) -> BoxFuture<EntryPtr> {
+    /// Like `set()`, but returns an error instead of dropping the value if
+    /// `key` is new and the Blackboard is full. See `set_max_entries()`.
+    pub async fn try_set<T: Any + Send + 'static>(
+        &mut self,
+        key: impl AsRef<str>,
+        value: T,
+    ) -> Result<(), BlackboardFullError> {
+        match self.global_key(key.as_ref()) {
+            Some((mut root, key)) => root.try_set_here(key, value).await,
+            None => self.try_set_here(key.as_ref(), value).await,
+        }
+    }
+
+    /// `try_set()` for a key of this Blackboard, i.e. without a `@` prefix
+    async fn try_set_here<T: Any + Send + 'static>(
+        &mut self,
+        key: &str,
+        value: T,
+    ) -> Result<(), BlackboardFullError> {
+        let mut blackboard = self.data.write().await;
+        // A cached entry that was removed from its parent must not be written
+        blackboard.drop_stale_entries(&self.removals);
+
+        let entry = if let Some(entry) = blackboard.storage.get(key) {
+            let entry = Arc::clone(entry);
+            blackboard.touch(key);
+            drop(blackboard);
+
+            entry.lock().await.set(value);
+            entry
+        } else {
+            drop(blackboard);
+            let entry = self.create_entry(&key).await?;
+
+            // Set value of new entry
+            entry.lock().await.set(value);
+            entry
+        };
+
+        self.notify(key, &entry).await;
+
+        Ok(())
+    }
+
+    /// Sync version of `try_set<T>`
+    ///
+    /// Sets the `value` at `key`, or returns an error if `key` is new and the
+    /// Blackboard is full.
+    pub fn try_set_sync<T: Any + Send + 'static>(
+        &mut self,
+        key: impl AsRef<str>,
+        value: T,
+    ) -> Result<(), BlackboardFullError> {
+        futures::executor::block_on(self.try_set(key, value))
+    }
+
+    /// Returns a `TypedEntry<T>` handle for `key`, which reads and writes
+    /// the entry as `T` without repeating the key or the type on every call.
+    /// The handle shares this `Blackboard`, so remapping rules apply as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let blackboard = Blackboard::create();
+    /// let mut counter = blackboard.entry::<u32>("counter");
+    ///
+    /// counter.set(1).await;
+    /// counter.update(|value| value.unwrap_or_default() + 1).await;
+    /// assert_eq!(counter.get().await, Some(2));
+    /// # })
+    /// ```
+    pub fn entry<T>(&self, key: impl AsRef<str>) -> TypedEntry<T>
+    where
+        T: Any + Clone + Send,
+    {
+        TypedEntry {
+            blackboard: self.clone(),
+            key: key.as_ref().to_string(),
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns a view of this `Blackboard` that prefixes every key with
+    /// `prefix` and `NAMESPACE_SEPARATOR`, so `"goal"` in the namespace `"nav"`
+    /// is the entry `"nav/goal"`. Keys starting with `@` are global and are
+    /// used as they are.
+    ///
+    /// The view shares this `Blackboard`, so remapping rules apply to the
+    /// prefixed keys as usual.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::blackboard::Blackboard;
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// let mut nav = blackboard.namespace("nav");
+    ///
+    /// nav.set("goal", 42u32).await;
+    /// assert_eq!(blackboard.get::<u32>("nav/goal").await, Some(42));
+    /// # })
+    /// ```
+    pub fn namespace(&self, prefix: impl AsRef<str>) -> NamespacedBlackboard {
+        NamespacedBlackboard {
+            blackboard: self.clone(),
+            prefix: prefix
+                .as_ref()
+                .trim_end_matches(NAMESPACE_SEPARATOR)
+                .to_string(),
+        }
+    }
+
+    fn create_entry<'a>(
+        &'a mut self,
+        key: &'a (impl AsRef<str> + Sync),
+    ) -> BoxFuture<'a, Result<EntryPtr, BlackboardFullError>> {
         Box::pin(async move {
+            if let Some((mut root, key)) = self.global_key(key.as_ref()) {
+                return root.create_entry(&key).await;
+            }
+
             let entry;
 
             let mut blackboard = self.data.write().await;
+            blackboard.drop_stale_entries(&self.removals);
 
             // If the entry already exists
             if let Some(existing_entry) = blackboard.storage.get(key.as_ref()) {
-                return Arc::clone(existing_entry);
+                return Ok(Arc::clone(existing_entry));
             }
             // Use explicit remapping rule
             else if blackboard.internal_to_external.contains_key(key.as_ref())
@@ -519,32 +1633,348 @@ impl Blackboard {
                     .as_mut()
                     .unwrap()
                     .create_entry(remapped_key)
-                    .await;
+                    .await?;
+                blackboard.cache(key.as_ref(), Arc::clone(&entry));
             }
             // Use autoremapping
             else if blackboard.auto_remapping && self.parent_bb.is_some() {
-                entry = (*self.parent_bb).as_mut().unwrap().create_entry(key).await;
+                entry = (*self.parent_bb)
+                    .as_mut()
+                    .unwrap()
+                    .create_entry(key)
+                    .await?;
+                blackboard.cache(key.as_ref(), Arc::clone(&entry));
             }
             // No remapping or no parent blackboard
             else {
                 // Create an entry with an empty placeholder value
                 entry = Arc::new(Mutex::new(Entry {
                     value: Box::new(()),
+                    type_name: std::any::type_name::<()>(),
+                    sequence_id: 0,
                 }));
+                blackboard.insert(key.as_ref(), Arc::clone(&entry), &self.removals)?;
             }
 
-            blackboard
-                .storage
-                .insert(key.as_ref().to_string(), Arc::clone(&entry));
-            entry
+            Ok(entry)
         })
     }
 }
 
+/// Copies `value` if it's one of the types `Blackboard::import_from()` supports.
+fn clone_value(value: &(dyn Any + Send)) -> Option<Box<dyn Any + Send>> {
+    macro_rules! try_clone {
+        ($($t:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$t>() {
+                    return Some(Box::new(value.clone()));
+                }
+            )*
+        };
+    }
+
+    try_clone!(
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        f32,
+        f64,
+        bool,
+        char,
+        String,
+        &'static str,
+        NodeStatus
+    );
+
+    None
+}
+
+/// Returns `value` as text if it's of a type that `clone_value()` can copy.
+fn display_value(value: &(dyn Any + Send)) -> Option<String> {
+    macro_rules! try_display {
+        ($($t:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$t>() {
+                    return Some(value.to_string());
+                }
+            )*
+        };
+    }
+
+    try_display!(
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        f32,
+        f64,
+        bool,
+        char,
+        String,
+        &'static str,
+        NodeStatus
+    );
+
+    None
+}
+
+/// Typed handle to a single `Blackboard` entry, created by `Blackboard::entry<T>()`.
+#[derive(Debug, Clone)]
+pub struct TypedEntry<T> {
+    blackboard: Blackboard,
+    key: String,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedEntry<T>
+where
+    T: Any + Clone + Send,
+{
+    /// The key this handle reads and writes.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the value as `T`, converting from a string if needed. See `Blackboard::get<T>()`.
+    pub async fn get(&mut self) -> Option<T>
+    where
+        T: FromString,
+    {
+        self.blackboard.get(&self.key).await
+    }
+
+    /// Sync version of `get()`
+    ///
+    /// Returns the value as `T`, converting from a string if needed. See `Blackboard::get<T>()`.
+    pub fn get_sync(&mut self) -> Option<T>
+    where
+        T: FromString,
+    {
+        futures::executor::block_on(self.get())
+    }
+
+    /// Returns the value only if it's stored as `T`. See `Blackboard::get_exact<T>()`.
+    pub async fn get_exact(&mut self) -> Option<T> {
+        self.blackboard.get_exact(&self.key).await
+    }
+
+    /// Sync version of `get_exact()`
+    ///
+    /// Returns the value only if it's stored as `T`. See `Blackboard::get_exact<T>()`.
+    pub fn get_exact_sync(&mut self) -> Option<T> {
+        futures::executor::block_on(self.get_exact())
+    }
+
+    /// Sets the entry to `value`.
+    pub async fn set(&mut self, value: T) {
+        self.blackboard.set(&self.key, value).await
+    }
+
+    /// Sync version of `set()`
+    ///
+    /// Sets the entry to `value`.
+    pub fn set_sync(&mut self, value: T) {
+        futures::executor::block_on(self.set(value))
+    }
+
+    /// Replaces the value with the result of `f` and returns the new value.
+    /// `f` receives the current value, or `None` if the entry is missing or
+    /// not stored as `T`. The entry stays locked while `f` runs, so no other
+    /// write can happen in between. The subscribers of the key are notified
+    /// as with `Blackboard::set()`.
+    ///
+    /// If the entry is new and the Blackboard is full, the value is returned
+    /// without being stored, as with `Blackboard::set()`.
+    pub async fn update<F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        let entry = match self.blackboard.create_entry(&self.key).await {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!("{e}");
+                return f(None);
+            }
+        };
+        let value = {
+            let mut entry = entry.lock().await;
+            let value = f(entry.value.downcast_ref::<T>().cloned());
+            entry.set(value.clone());
+            value
+        };
+
+        self.blackboard.notify(&self.key, &entry).await;
+
+        value
+    }
+
+    /// Sync version of `update()`
+    ///
+    /// Replaces the value with the result of `f` and returns the new value.
+    /// `f` receives the current value, or `None` if the entry is missing or
+    /// not stored as `T`. The entry stays locked while `f` runs, so no other
+    /// write can happen in between.
+    pub fn update_sync<F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(Option<T>) -> T,
+    {
+        futures::executor::block_on(self.update(f))
+    }
+}
+
+/// Separates the namespace from the rest of a blackboard key, as in `nav/goal`.
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// View of a `Blackboard` that keeps its keys in a namespace, created by
+/// `Blackboard::namespace()`.
+#[derive(Debug, Clone)]
+pub struct NamespacedBlackboard {
+    blackboard: Blackboard,
+    prefix: String,
+}
+
+impl NamespacedBlackboard {
+    /// The namespace, without the trailing separator.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The underlying `Blackboard`.
+    pub fn blackboard(&self) -> &Blackboard {
+        &self.blackboard
+    }
+
+    /// Returns the key used in the underlying `Blackboard` for `key`.
+    pub fn key(&self, key: impl AsRef<str>) -> String {
+        let key = key.as_ref();
+
+        match key.starts_with('@') {
+            true => key.to_string(),
+            false => format!("{}{NAMESPACE_SEPARATOR}{key}", self.prefix),
+        }
+    }
+
+    /// Returns a namespace nested in this one, e.g. `nav/local`.
+    pub fn namespace(&self, prefix: impl AsRef<str>) -> NamespacedBlackboard {
+        self.blackboard.namespace(self.key(prefix))
+    }
+
+    /// Returns the value at `key` in this namespace. See `Blackboard::get<T>()`.
+    pub async fn get<T>(&mut self, key: impl AsRef<str>) -> Option<T>
+    where
+        T: Any + Clone + FromString + Send,
+    {
+        let key = self.key(key);
+        self.blackboard.get(key).await
+    }
+
+    /// Sync version of `get<T>()`
+    ///
+    /// Returns the value at `key` in this namespace. See `Blackboard::get<T>()`.
+    pub fn get_sync<T>(&mut self, key: impl AsRef<str>) -> Option<T>
+    where
+        T: Any + Clone + FromString + Send,
+    {
+        futures::executor::block_on(self.get(key))
+    }
+
+    /// Returns the value at `key` in this namespace only if it's stored as `T`.
+    /// See `Blackboard::get_exact<T>()`.
+    pub async fn get_exact<T>(&mut self, key: impl AsRef<str>) -> Option<T>
+    where
+        T: Any + Clone,
+    {
+        let key = self.key(key);
+        self.blackboard.get_exact(key).await
+    }
+
+    /// Sync version of `get_exact<T>()`
+    ///
+    /// Returns the value at `key` in this namespace only if it's stored as `T`.
+    /// See `Blackboard::get_exact<T>()`.
+    pub fn get_exact_sync<T>(&mut self, key: impl AsRef<str>) -> Option<T>
+    where
+        T: Any + Clone,
+    {
+        futures::executor::block_on(self.get_exact(key))
+    }
+
+    /// Sets `value` at `key` in this namespace.
+    pub async fn set<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
+        let key = self.key(key);
+        self.blackboard.set(key, value).await
+    }
+
+    /// Sync version of `set<T>()`
+    ///
+    /// Sets `value` at `key` in this namespace.
+    pub fn set_sync<T: Any + Send + 'static>(&mut self, key: impl AsRef<str>, value: T) {
+        futures::executor::block_on(self.set(key, value))
+    }
+
+    /// Returns a `TypedEntry<T>` handle for `key` in this namespace.
+    pub fn entry<T>(&self, key: impl AsRef<str>) -> TypedEntry<T>
+    where
+        T: Any + Clone + Send,
+    {
+        self.blackboard.entry(self.key(key))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn try_get() {
+        let mut root_bb = Blackboard::create();
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb
+            .add_subtree_remapping(String::from("target"), String::from("goal"))
+            .await;
+
+        root_bb.set("goal", "42").await;
+        root_bb.create_entry(&"unset").await.unwrap();
+
+        // Remapped, and parsed from the string
+        assert_eq!(child_bb.try_get::<u32>("target").await, Ok(42u32));
+        assert_eq!(
+            child_bb.try_get::<bool>("target").await,
+            Err(BlackboardError::TypeMismatch {
+                key: String::from("target"),
+                expected: "bool",
+                found: "u32",
+            })
+        );
+        assert_eq!(
+            root_bb.try_get::<u32>("unset").await,
+            Err(BlackboardError::Missing(String::from("unset")))
+        );
+        assert_eq!(
+            child_bb.try_get::<u32>("goal").await,
+            Err(BlackboardError::Missing(String::from("goal")))
+        );
+        // `get` still folds both into `None`
+        assert_eq!(child_bb.get::<bool>("target").await, None);
+    }
+
     // TODO: add other tests
 
     #[tokio::test]
@@ -650,6 +2080,211 @@ mod tests {
         assert_eq!(child3_bb.get::<u32>("foo").await, None);
     }
 
+    #[tokio::test]
+    async fn auto_remapping_cache() {
+        let mut root_bb = Blackboard::create();
+        let mut child1_bb = Blackboard::with_parent(&root_bb).await;
+        let mut child2_bb = Blackboard::with_parent(&child1_bb).await;
+        let mut child3_bb = Blackboard::with_parent(&child2_bb).await;
+
+        child1_bb.enable_auto_remapping(true).await;
+        child2_bb.enable_auto_remapping(true).await;
+        child3_bb.enable_auto_remapping(true).await;
+
+        root_bb.set("foo", 123u32).await;
+
+        // First lookup walks the chain and caches the entry at each level
+        assert_eq!(child3_bb.get::<u32>("foo").await, Some(123));
+        assert!(child3_bb.data.read().await.storage.contains_key("foo"));
+
+        // Writes from either end are still visible through the cached entry
+        root_bb.set("foo", 456u32).await;
+        assert_eq!(child3_bb.get::<u32>("foo").await, Some(456));
+        child3_bb.set("foo", 789u32).await;
+        assert_eq!(root_bb.get::<u32>("foo").await, Some(789));
+
+        // An intermediate level that shadows the key wins over the root
+        let mut root_bb = Blackboard::create();
+        let mut child1_bb = Blackboard::with_parent(&root_bb).await;
+        let mut child2_bb = Blackboard::with_parent(&child1_bb).await;
+        let mut child3_bb = Blackboard::with_parent(&child2_bb).await;
+
+        child2_bb.enable_auto_remapping(true).await;
+        child3_bb.enable_auto_remapping(true).await;
+
+        root_bb.set("foo", 1u32).await;
+        child2_bb.set("foo", 2u32).await;
+
+        assert_eq!(child3_bb.get::<u32>("foo").await, Some(2));
+        root_bb.set("foo", 3u32).await;
+        assert_eq!(child3_bb.get::<u32>("foo").await, Some(2));
+        // child2 forwarded its write to child1, which doesn't remap further
+        assert_eq!(child1_bb.get::<u32>("foo").await, Some(2));
+        assert_eq!(root_bb.get::<u32>("foo").await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn auto_remapping_cache_shadowing() {
+        // Four levels, where level 2 stores the key itself
+        let mut root_bb = Blackboard::create();
+        let mut level2_bb = Blackboard::with_parent(&root_bb).await;
+        let mut level3_bb = Blackboard::with_parent(&level2_bb).await;
+        let mut level4_bb = Blackboard::with_parent(&level3_bb).await;
+        level3_bb.enable_auto_remapping(true).await;
+        level4_bb.enable_auto_remapping(true).await;
+
+        root_bb.set("foo", 1u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, None);
+
+        // Level 2's entry shadows the root's, also through the cache
+        level2_bb.set("foo", 2u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(2));
+        assert!(level4_bb.data.read().await.storage.contains_key("foo"));
+        level2_bb.set("foo", 3u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(3));
+        root_bb.set("foo", 4u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(3));
+        level4_bb.set("foo", 5u32).await;
+        assert_eq!(level2_bb.get::<u32>("foo").await, Some(5));
+        assert_eq!(root_bb.get::<u32>("foo").await, Some(4));
+
+        // Once level 2 removes its entry, the cached one isn't read anymore
+        assert!(level2_bb.remove("foo").await);
+        assert_eq!(level4_bb.get::<u32>("foo").await, None);
+        level2_bb.set("foo", 6u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(6));
+
+        // Level 2 starts remapping to the root after level 4 cached its entry
+        level2_bb.clear().await;
+        level2_bb.enable_auto_remapping(true).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(4));
+        level2_bb.set("foo", 7u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(7));
+
+        // ... and stops again, so its own entry shadows the cached one
+        level2_bb.enable_auto_remapping(false).await;
+        level2_bb.set("foo", 8u32).await;
+        assert_eq!(level4_bb.get::<u32>("foo").await, Some(8));
+        assert_eq!(root_bb.get::<u32>("foo").await, Some(7));
+
+        // Same for a new explicit remapping rule
+        let mut other_bb = Blackboard::with_parent(&root_bb).await;
+        other_bb.enable_auto_remapping(true).await;
+        root_bb.set("bar", 9u32).await;
+        assert_eq!(other_bb.get::<u32>("foo").await, Some(7));
+        other_bb
+            .add_subtree_remapping(String::from("foo"), String::from("bar"))
+            .await;
+        assert_eq!(other_bb.get::<u32>("foo").await, Some(9));
+    }
+
+    #[tokio::test]
+    async fn parent_chain_terminates() {
+        // Reusing a variable for a child of its own descendant still builds a
+        // new, finite chain
+        let mut bb = Blackboard::create();
+        bb.set("root", 1u32).await;
+        let mut child_bb = Blackboard::with_parent(&bb).await;
+        child_bb.enable_auto_remapping(true).await;
+        bb = Blackboard::with_parent(&child_bb).await;
+        bb.enable_auto_remapping(true).await;
+
+        assert_eq!(bb.get::<u32>("root").await, Some(1));
+        assert_eq!(bb.get::<u32>("missing").await, None);
+
+        // Remapping a key onto the same name at every level doesn't loop
+        let root_bb = Blackboard::create();
+        let mut child1_bb = Blackboard::with_parent(&root_bb).await;
+        let mut child2_bb = Blackboard::with_parent(&child1_bb).await;
+        child1_bb
+            .add_subtree_remapping("foo".to_string(), "foo".to_string())
+            .await;
+        child2_bb
+            .add_subtree_remapping("foo".to_string(), "foo".to_string())
+            .await;
+
+        assert_eq!(child2_bb.get::<u32>("foo").await, None);
+        child2_bb.set("foo", 2u32).await;
+        assert_eq!(child1_bb.get::<u32>("foo").await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn root_keys() {
+        let mut root_bb = Blackboard::create();
+        let child_bb = Blackboard::with_parent(&root_bb).await;
+        let mut grandchild_bb = Blackboard::with_parent(&child_bb).await;
+        assert!(grandchild_bb.root().ptr_eq(&root_bb));
+
+        // `@key` goes to the root, without any remapping
+        root_bb.set("battery", 80u32).await;
+        assert_eq!(grandchild_bb.get::<u32>("@battery").await, Some(80));
+        assert_eq!(grandchild_bb.get::<u32>("battery").await, None);
+
+        grandchild_bb.set("@mode", String::from("auto")).await;
+        assert_eq!(
+            root_bb.get_exact::<String>("mode").await,
+            Some(String::from("auto"))
+        );
+        assert_eq!(
+            root_bb.get_exact::<String>("@mode").await,
+            Some(String::from("auto"))
+        );
+        assert_eq!(grandchild_bb.get_exact::<String>("mode").await, None);
+        assert_eq!(
+            grandchild_bb.entry_type("@mode").await,
+            Some(std::any::type_name::<String>())
+        );
+
+        // Typed handles, removal and subscriptions resolve `@key` the same way
+        let mut counter = grandchild_bb.entry::<u32>("@counter");
+        assert_eq!(counter.update(|v| v.unwrap_or(1) + 1).await, 2);
+        assert_eq!(root_bb.get::<u32>("counter").await, Some(2));
+        assert!(grandchild_bb.is_empty().await);
+
+        let mut changes = grandchild_bb.subscribe("@battery").await;
+        root_bb.set("battery", 70u32).await;
+        grandchild_bb.set("@battery", 60u32).await;
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.key, "@battery");
+        assert_eq!(change.sequence_id, 2);
+        assert_eq!(changes.try_recv().unwrap().sequence_id, 3);
+
+        assert!(grandchild_bb.remove("@battery").await);
+        assert_eq!(root_bb.get::<u32>("battery").await, None);
+        assert!(!grandchild_bb.remove("@battery").await);
+    }
+
+    #[tokio::test]
+    async fn typed_entry() {
+        let mut root_bb = Blackboard::create();
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb.enable_auto_remapping(true).await;
+
+        let mut counter = child_bb.entry::<u32>("counter");
+
+        assert_eq!(counter.get().await, None);
+        assert_eq!(counter.update(|v| v.unwrap_or(10) + 1).await, 11);
+        assert_eq!(counter.update(|v| v.unwrap_or(10) + 1).await, 12);
+
+        // Writes go through the parent chain
+        assert_eq!(root_bb.get::<u32>("counter").await, Some(12));
+
+        root_bb.set("counter", "20").await;
+        assert_eq!(counter.get_exact().await, None);
+        assert_eq!(counter.get().await, Some(20));
+        assert_eq!(counter.get_exact().await, Some(20));
+
+        // Updates notify the subscribers under either name
+        let mut changes = root_bb.subscribe("counter").await;
+        let mut child_changes = child_bb.subscribe("counter").await;
+        assert_eq!(counter.update(|v| v.unwrap_or_default() * 2).await, 40);
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.type_name, std::any::type_name::<u32>());
+        assert_eq!(change.sequence_id, 4);
+        assert_eq!(child_changes.try_recv().unwrap().sequence_id, 4);
+        assert!(changes.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn type_matching() {
         let mut bb = Blackboard::create();
@@ -661,6 +2296,49 @@ mod tests {
         assert!(bb.get::<f32>("foo").await.is_none());
     }
 
+    #[tokio::test]
+    async fn entry_type() {
+        #[derive(Clone, Debug)]
+        struct Pose {
+            _x: f64,
+        }
+
+        let mut root_bb = Blackboard::create();
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb.enable_auto_remapping(true).await;
+
+        root_bb.set("pose", Pose { _x: 1.0 }).await;
+        root_bb.set("count", 3u8).await;
+        root_bb.set("text", String::from("12")).await;
+
+        assert_eq!(
+            root_bb.entry_type("pose").await,
+            Some(std::any::type_name::<Pose>())
+        );
+        assert_eq!(
+            child_bb.entry_type("count").await,
+            Some(std::any::type_name::<u8>())
+        );
+        assert_eq!(
+            root_bb.entry_type("text").await,
+            Some(std::any::type_name::<String>())
+        );
+        assert_eq!(root_bb.entry_type("missing").await, None);
+
+        // Overwriting with another type updates the name
+        child_bb.set("count", 3i64).await;
+        assert_eq!(root_bb.entry_type("count").await, Some("i64"));
+
+        // Parsing a string stores the parsed value
+        assert_eq!(root_bb.get::<u32>("text").await, Some(12));
+        assert_eq!(root_bb.entry_type("text").await, Some("u32"));
+
+        // Imported entries keep their type
+        let mut other = Blackboard::create();
+        other.import_from(&root_bb, true).await;
+        assert_eq!(other.entry_type("count").await, Some("i64"));
+    }
+
     #[tokio::test]
     async fn custom_type() {
         #[derive(Clone, Debug, PartialEq)]
@@ -714,4 +2392,397 @@ mod tests {
             None
         );
     }
+
+    #[tokio::test]
+    async fn namespaces() {
+        let mut blackboard = Blackboard::create();
+        let mut nav = blackboard.namespace("nav/");
+        let mut perception = blackboard.namespace("perception");
+
+        nav.set("goal", 1u32).await;
+        perception.set("goal", 2u32).await;
+
+        // Same key in different namespaces doesn't collide
+        assert_eq!(nav.prefix(), "nav");
+        assert_eq!(blackboard.get_exact::<u32>("nav/goal").await, Some(1));
+        assert_eq!(
+            blackboard.get_exact::<u32>("perception/goal").await,
+            Some(2)
+        );
+        assert_eq!(blackboard.get_exact::<u32>("goal").await, None);
+
+        blackboard.set("nav/speed", "1.5").await;
+        assert_eq!(nav.get::<f64>("speed").await, Some(1.5));
+        assert_eq!(nav.get_exact::<u32>("goal").await, Some(1));
+
+        // Nested namespaces and entry handles
+        let local = nav.namespace("local");
+        local.entry::<u32>("goal").set(3).await;
+        assert_eq!(blackboard.get_exact::<u32>("nav/local/goal").await, Some(3));
+
+        // Global keys aren't namespaced
+        nav.set("@mode", String::from("auto")).await;
+        assert_eq!(nav.key("@mode"), "@mode");
+        assert_eq!(
+            blackboard.get_exact::<String>("@mode").await,
+            Some(String::from("auto"))
+        );
+        assert_eq!(blackboard.get_exact::<String>("nav/@mode").await, None);
+    }
+
+    #[tokio::test]
+    async fn import_from() {
+        let mut source = Blackboard::create();
+        source.set("goal", String::from("kitchen")).await;
+        source.set("speed", 1.5f64).await;
+        source.set("attempts", 3u32).await;
+        source.set("status", NodeStatus::Running).await;
+        source.set("path", vec![1, 2, 3]).await;
+
+        // Into a fresh blackboard
+        let mut target = Blackboard::create();
+        let skipped = target.import_from(&source, false).await;
+
+        assert_eq!(skipped, vec![String::from("path")]);
+        assert_eq!(
+            target.get_exact::<String>("goal").await.as_deref(),
+            Some("kitchen")
+        );
+        assert_eq!(target.get_exact::<f64>("speed").await, Some(1.5));
+        assert_eq!(target.get_exact::<u32>("attempts").await, Some(3));
+        assert_eq!(
+            target.get_exact::<NodeStatus>("status").await,
+            Some(NodeStatus::Running)
+        );
+        assert!(target.get_exact::<Vec<i32>>("path").await.is_none());
+
+        // Values are copied, not shared
+        source.set("attempts", 4u32).await;
+        assert_eq!(target.get_exact::<u32>("attempts").await, Some(3));
+
+        // Existing keys are kept unless `overwrite` is set
+        target.import_from(&source, false).await;
+        assert_eq!(target.get_exact::<u32>("attempts").await, Some(3));
+        target.import_from(&source, true).await;
+        assert_eq!(target.get_exact::<u32>("attempts").await, Some(4));
+
+        // Importing from itself changes nothing
+        let mut copy = target.clone();
+        target.import_from(&copy, true).await;
+        assert_eq!(copy.get_exact::<u32>("attempts").await, Some(4));
+    }
+
+    #[tokio::test]
+    async fn max_entries() {
+        // Rejecting new keys
+        let mut blackboard = Blackboard::create();
+        blackboard
+            .set_max_entries(Some(3), EvictionPolicy::Reject)
+            .await;
+
+        for i in 0..10u32 {
+            blackboard.set(format!("key{i}"), i).await;
+        }
+        assert_eq!(blackboard.len().await, 3);
+        assert_eq!(blackboard.get::<u32>("key2").await, Some(2));
+        assert_eq!(blackboard.get::<u32>("key3").await, None);
+
+        let err = blackboard.try_set("key10", 10u32).await.unwrap_err();
+        assert_eq!(err.key, "key10");
+        assert_eq!(err.max_entries, 3);
+        // Existing keys can still be written
+        assert!(blackboard.try_set("key0", 100u32).await.is_ok());
+        assert_eq!(blackboard.get::<u32>("key0").await, Some(100));
+
+        // Removing the limit
+        blackboard
+            .set_max_entries(None, EvictionPolicy::Reject)
+            .await;
+        assert!(blackboard.try_set("key10", 10u32).await.is_ok());
+        assert_eq!(blackboard.len().await, 4);
+
+        // Evicting the least recently used entry
+        let mut blackboard = Blackboard::create();
+        blackboard
+            .set_max_entries(Some(3), EvictionPolicy::LeastRecentlyUsed)
+            .await;
+
+        for i in 0..10u32 {
+            blackboard.set(format!("key{i}"), i).await;
+        }
+        assert_eq!(blackboard.len().await, 3);
+        assert_eq!(blackboard.get::<u32>("key6").await, None);
+
+        // Reading key7 makes key8 the least recently used
+        assert_eq!(blackboard.get::<u32>("key7").await, Some(7));
+        blackboard.set("key10", 10u32).await;
+        assert_eq!(blackboard.get::<u32>("key8").await, None);
+        assert_eq!(blackboard.get::<u32>("key7").await, Some(7));
+        assert_eq!(blackboard.get::<u32>("key9").await, Some(9));
+        assert_eq!(blackboard.get::<u32>("key10").await, Some(10));
+
+        // A limit of zero can't evict anything to make room
+        blackboard
+            .set_max_entries(Some(0), EvictionPolicy::LeastRecentlyUsed)
+            .await;
+        assert_eq!(blackboard.len().await, 3);
+        assert!(blackboard.try_set("key11", 11u32).await.is_err());
+        assert_eq!(blackboard.len().await, 3);
+    }
+
+    #[tokio::test]
+    async fn max_entries_remapped() {
+        let mut root_bb = Blackboard::create();
+        root_bb
+            .set_max_entries(Some(2), EvictionPolicy::LeastRecentlyUsed)
+            .await;
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb.enable_auto_remapping(true).await;
+        child_bb
+            .set_max_entries(Some(1), EvictionPolicy::Reject)
+            .await;
+
+        root_bb.set("a", 1u32).await;
+        root_bb.set("b", 2u32).await;
+
+        // Entries of the parent don't count toward the child's limit
+        assert_eq!(child_bb.get::<u32>("a").await, Some(1));
+        assert_eq!(child_bb.get::<u32>("b").await, Some(2));
+        assert_eq!(child_bb.len().await, 0);
+
+        // Evicting "a" from the parent drops the child's cached entry, so
+        // writing "a" again through the child reaches the parent
+        root_bb.set("c", 3u32).await;
+        assert_eq!(root_bb.get::<u32>("a").await, None);
+        assert_eq!(child_bb.get::<u32>("a").await, None);
+        child_bb.set("a", 10u32).await;
+        assert_eq!(root_bb.get::<u32>("a").await, Some(10));
+        assert_eq!(child_bb.get::<u32>("a").await, Some(10));
+
+        // Removing an entry of the parent does the same
+        root_bb.remove("a").await;
+        assert_eq!(child_bb.get::<u32>("a").await, None);
+    }
+
+    #[tokio::test]
+    async fn set_after_parent_remove() {
+        let mut root_bb = Blackboard::create();
+        root_bb.set("x", 1u32).await;
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb.enable_auto_remapping(true).await;
+        assert_eq!(child_bb.get::<u32>("x").await, Some(1));
+
+        // The child's cached entry is stale, so the write creates a new
+        // entry in the parent instead
+        assert!(root_bb.remove("x").await);
+        child_bb.set("x", 2u32).await;
+        assert_eq!(root_bb.get::<u32>("x").await, Some(2));
+        assert_eq!(child_bb.get::<u32>("x").await, Some(2));
+
+        // Same for an explicit remapping
+        let mut remapped_bb = Blackboard::with_parent(&root_bb).await;
+        remapped_bb
+            .add_subtree_remapping(String::from("y"), String::from("x"))
+            .await;
+        assert_eq!(remapped_bb.get::<u32>("y").await, Some(2));
+        root_bb.clear().await;
+        remapped_bb.set("y", 3u32).await;
+        assert_eq!(root_bb.get::<u32>("x").await, Some(3));
+        assert_eq!(remapped_bb.get::<u32>("y").await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn get_mut() {
+        let mut root_bb = Blackboard::create();
+        root_bb.set("cloud", vec![0.5f32]).await;
+        root_bb.set("count", String::from("1")).await;
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb
+            .add_subtree_remapping(String::from("points"), String::from("cloud"))
+            .await;
+
+        // Changes through a remapped key are made to the parent's entry
+        child_bb
+            .get_mut::<Vec<f32>>("points")
+            .await
+            .unwrap()
+            .extend([1.5, 2.5]);
+        assert_eq!(
+            root_bb.get_exact::<Vec<f32>>("cloud").await,
+            Some(vec![0.5, 1.5, 2.5])
+        );
+        assert_eq!(root_bb.sequence_id("cloud").await, Some(2));
+
+        // Other types and strings that would parse aren't returned
+        assert!(root_bb.get_mut::<Vec<f64>>("cloud").await.is_none());
+        assert!(root_bb.get_mut::<u32>("count").await.is_none());
+        assert!(root_bb.get_mut::<u32>("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn subscribe() {
+        let mut root_bb = Blackboard::create();
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb
+            .add_subtree_remapping(String::from("goal"), String::from("target"))
+            .await;
+
+        let mut root_changes = root_bb.subscribe("target").await;
+        let mut child_changes = child_bb.subscribe("goal").await;
+        let other_changes = root_bb.subscribe("other").await;
+
+        // Both names are notified, whichever is set
+        child_bb.set("goal", 1u32).await;
+        root_bb.set("target", String::from("2")).await;
+
+        for (changes, key) in [(&mut root_changes, "target"), (&mut child_changes, "goal")] {
+            let first = changes.try_recv().unwrap();
+            assert_eq!((first.key.as_str(), first.sequence_id), (key, 1));
+            assert_eq!(first.type_name, std::any::type_name::<u32>());
+
+            let second = changes.try_recv().unwrap();
+            assert_eq!((second.key.as_str(), second.sequence_id), (key, 2));
+            assert_eq!(second.type_name, std::any::type_name::<String>());
+
+            assert!(changes.try_recv().is_err());
+        }
+
+        // Dropped receivers are removed on the next change
+        drop(other_changes);
+        root_bb.set("other", 3u32).await;
+        assert!(root_bb.data.read().await.subscribers["other"].is_empty());
+
+        // A key that isn't remapped is the child's own
+        let mut local_changes = child_bb.subscribe("target").await;
+        root_bb.set("target", 4u32).await;
+        assert!(local_changes.try_recv().is_err());
+        child_bb.set("target", 5u32).await;
+        assert_eq!(local_changes.try_recv().unwrap().sequence_id, 1);
+
+        // With auto-remapping, every key is the parent's
+        let mut auto_bb = Blackboard::with_parent(&root_bb).await;
+        auto_bb.enable_auto_remapping(true).await;
+        let mut auto_changes = auto_bb.subscribe("target").await;
+        let mut planning = Blackboard::create();
+        planning.set("target", 6u32).await;
+        root_bb.import_from(&planning, true).await;
+        assert_eq!(auto_changes.try_recv().unwrap().sequence_id, 4);
+    }
+
+    #[tokio::test]
+    async fn subscribe_with() {
+        let root_bb = Blackboard::create();
+
+        // A channel is subscribed once per key
+        let (sender, receiver) = mpsc::unbounded_channel();
+        for _ in 0..3 {
+            root_bb.subscribe_with("target", sender.clone()).await;
+        }
+        root_bb.subscribe_with("other", sender.clone()).await;
+        assert_eq!(root_bb.data.read().await.subscribers["target"].len(), 1);
+
+        // Dropped receivers are removed when subscribing again
+        drop(receiver);
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        root_bb.subscribe_with("target", sender).await;
+        assert_eq!(root_bb.data.read().await.subscribers["target"].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn keys_and_entries() {
+        let mut root_bb = Blackboard::create();
+        root_bb.set("target", 1u32).await;
+        root_bb.set("path", vec![1, 2]).await;
+        root_bb.set("hidden", true).await;
+
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb
+            .add_subtree_remapping(String::from("goal"), String::from("target"))
+            .await;
+        child_bb
+            .add_subtree_remapping(String::from("missing"), String::from("nothing"))
+            .await;
+        child_bb.set("speed", "fast").await;
+
+        // Remapped keys are listed by their local name
+        assert_eq!(child_bb.keys().await, vec!["goal", "speed"]);
+        assert_eq!(
+            child_bb.entries().await,
+            vec![
+                (String::from("goal"), String::from("1")),
+                (String::from("speed"), String::from("fast")),
+            ]
+        );
+
+        // Reading a key caches the parent's entry, which isn't listed twice
+        assert_eq!(child_bb.get::<u32>("goal").await, Some(1));
+        assert_eq!(child_bb.keys().await, vec!["goal", "speed"]);
+
+        // With auto-remapping, all keys of the parent are visible, unless
+        // they're shadowed
+        let mut auto_bb = Blackboard::with_parent(&child_bb).await;
+        auto_bb.enable_auto_remapping(true).await;
+        auto_bb.set("speed", 2.5).await;
+        assert_eq!(
+            auto_bb.entries().await,
+            vec![
+                (String::from("goal"), String::from("1")),
+                (String::from("speed"), String::from("2.5")),
+            ]
+        );
+
+        // Values that can't be shown as text show their type
+        let entries = root_bb.entries().await;
+        assert_eq!(entries[1].0, "path");
+        assert_eq!(
+            entries[1].1,
+            format!("<{}>", std::any::type_name::<Vec<i32>>())
+        );
+
+        // Entries that were created but never set aren't listed
+        let _ = root_bb.create_entry(&"empty").await;
+        assert_eq!(root_bb.keys().await, vec!["hidden", "path", "target"]);
+    }
+
+    #[tokio::test]
+    async fn remove_and_clear() {
+        let mut root_bb = Blackboard::create();
+        root_bb.set("target", 1u32).await;
+
+        let mut child_bb = Blackboard::with_parent(&root_bb).await;
+        child_bb
+            .add_subtree_remapping(String::from("goal"), String::from("target"))
+            .await;
+        child_bb.set("local", 2u32).await;
+        child_bb.set("goal", 3u32).await;
+        let _ = child_bb.create_entry(&"empty").await;
+
+        assert!(child_bb.remove("local").await);
+        assert!(!child_bb.remove("local").await);
+        assert!(!child_bb.remove("empty").await);
+        assert!(!child_bb.remove("missing").await);
+        assert_eq!(child_bb.get::<u32>("local").await, None);
+
+        // The parent keeps remapped values, and the remapping stays
+        assert!(!child_bb.remove("goal").await);
+        assert_eq!(root_bb.get::<u32>("target").await, Some(3));
+        assert_eq!(child_bb.get::<u32>("goal").await, Some(3));
+
+        let mut auto_bb = Blackboard::with_parent(&root_bb).await;
+        auto_bb.enable_auto_remapping(true).await;
+        assert_eq!(auto_bb.get::<u32>("target").await, Some(3));
+        assert!(!auto_bb.remove("target").await);
+        assert_eq!(root_bb.get::<u32>("target").await, Some(3));
+
+        // Clearing keeps remappings and subscriptions
+        let mut changes = child_bb.subscribe("local").await;
+        child_bb.set("local", 4u32).await;
+        child_bb.clear().await;
+        assert_eq!(child_bb.keys().await, vec!["goal"]);
+        assert_eq!(child_bb.get::<u32>("goal").await, Some(3));
+
+        child_bb.set("local", 5u32).await;
+        assert_eq!(changes.try_recv().unwrap().sequence_id, 1);
+        assert_eq!(changes.try_recv().unwrap().sequence_id, 1);
+    }
 }
@@ -0,0 +1,780 @@
+//! Small expression language for port values, e.g. `timeout="eval: base * 2"`.
+//!
+//! Supported are integer, float, boolean and string (`'text'`) literals,
+//! the operators `||`, `&&`, `==`, `!=`, `<`, `<=`, `>`, `>=`, `+`, `-`, `*`,
+//! `/`, `%`, unary `-` and `!`, and parentheses, with the usual precedence.
+//!
+//! Every other identifier (`base`) is a variable that's read from the
+//! blackboard, with the usual remapping rules. Keys that aren't identifiers,
+//! such as `nav/goal`, can be written in braces: `{nav/goal} + 1`. Numbers,
+//! booleans and strings stored in the blackboard can be used; strings that
+//! hold a number or boolean are read as such, like `Blackboard::get<T>()`.
+//! `@key` reads `key` from the root blackboard, which holds the tree's global
+//! entries, without any remapping.
+//!
+//! A [`Script`] is a list of assignments such as `A := 5; B := A * 2`, which
+//! write their results to the blackboard. The `Script` node runs one.
+
+use std::{collections::HashMap, fmt};
+
+use thiserror::Error;
+
+use crate::{basic_types::FromString, blackboard::Blackboard};
+
+/// Port values starting with this prefix are evaluated as an expression.
+pub const EXPRESSION_PREFIX: &str = "eval:";
+
+#[derive(Debug, Error)]
+pub enum ExpressionError {
+    #[error("Unexpected character '{0}' at position {1}")]
+    UnexpectedChar(char, usize),
+    #[error("Unexpected end of expression")]
+    UnexpectedEnd,
+    #[error("Unexpected token [{0}]")]
+    UnexpectedToken(String),
+    #[error("Variable [{0}] isn't in the blackboard or has an unsupported type")]
+    UnknownVariable(String),
+    #[error("Operator [{0}] can't be applied to {1}")]
+    /// `(operator, operands)`
+    TypeMismatch(String, String),
+    #[error("Integer overflow or division by zero in [{0}]")]
+    ArithmeticError(String),
+    #[error("Expected an assignment such as `A := 5`, found [{0}]")]
+    InvalidAssignment(String),
+    #[error("Expected the expression to be a bool, found {0}")]
+    /// Type and value of the result, e.g. `int [3]`
+    ExpectedBool(String),
+    #[error("Value {1} doesn't fit the type of blackboard entry [{0}]")]
+    /// `(key, value)`
+    ValueOutOfRange(String, String),
+}
+
+impl ExpressionError {
+    /// `ExpectedBool` for an expression that evaluated to `value`.
+    pub(crate) fn expected_bool(value: &Value) -> ExpressionError {
+        ExpressionError::ExpectedBool(format!("{} [{value}]", value.type_name()))
+    }
+}
+
+/// Value of an expression or a variable.
+///
+/// Integers are kept as `i128` so that every `i64` and `u64` value, such as
+/// IDs or timestamps above `i64::MAX`, round-trips without loss.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i128),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Value {
+    /// Reads a string the way the blackboard converts strings: as a number or
+    /// boolean if possible.
+    fn from_text(text: &str) -> Value {
+        if let Ok(value) = text.parse::<i128>() {
+            Value::Int(value)
+        } else if let Ok(value) = text.parse::<f64>() {
+            Value::Float(value)
+        } else if let Ok(value) = <bool as FromString>::from_string(text) {
+            Value::Bool(value)
+        } else {
+            Value::String(text.to_string())
+        }
+    }
+
+    fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Int(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "int",
+            Self::Float(_) => "float",
+            Self::Bool(_) => "bool",
+            Self::String(_) => "string",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Value(Value),
+    Variable(String),
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(Value),
+    Variable(String),
+    Unary(&'static str, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+/// Operators by precedence, from lowest to highest
+const BINARY_OPERATORS: [&[&str]; 5] = [
+    &["||"],
+    &["&&"],
+    &["==", "!=", "<=", ">=", "<", ">"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+/// A parsed expression.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    root: Expr,
+}
+
+impl Expression {
+    pub fn parse(text: &str) -> Result<Expression, ExpressionError> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let root = parser.binary(0)?;
+        match parser.tokens.get(parser.pos) {
+            Some(token) => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+            None => Ok(Expression { root }),
+        }
+    }
+
+    /// Names of the variables used in the expression, without duplicates.
+    pub fn variables(&self) -> Vec<&str> {
+        fn collect<'a>(expr: &'a Expr, names: &mut Vec<&'a str>) {
+            match expr {
+                Expr::Value(_) => {}
+                Expr::Variable(name) => {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name);
+                    }
+                }
+                Expr::Unary(_, expr) => collect(expr, names),
+                Expr::Binary(_, left, right) => {
+                    collect(left, names);
+                    collect(right, names);
+                }
+            }
+        }
+
+        let mut names = Vec::new();
+        collect(&self.root, &mut names);
+
+        names
+    }
+
+    /// Evaluates the expression with the given variable values.
+    pub fn evaluate(&self, variables: &HashMap<String, Value>) -> Result<Value, ExpressionError> {
+        eval(&self.root, variables)
+    }
+
+    /// Evaluates the expression, reading its variables from `blackboard`.
+    pub async fn evaluate_with(&self, blackboard: &Blackboard) -> Result<Value, ExpressionError> {
+        let mut variables = HashMap::new();
+
+        for name in self.variables() {
+            let (mut blackboard, key) = resolve(blackboard, name);
+            match read_variable(&mut blackboard, key).await {
+                Some(value) => variables.insert(name.to_string(), value),
+                None => return Err(ExpressionError::UnknownVariable(name.to_string())),
+            };
+        }
+
+        self.evaluate(&variables)
+    }
+}
+
+/// Parses `text` and evaluates it, reading variables from `blackboard`.
+pub async fn evaluate(text: &str, blackboard: &Blackboard) -> Result<Value, ExpressionError> {
+    Expression::parse(text)?.evaluate_with(blackboard).await
+}
+
+/// Assignments separated by `;`, e.g. `A := 5; B := A * 2`.
+///
+/// The target of an assignment is a name, `@name` or a key in braces, like
+/// variables in an expression.
+#[derive(Debug, Clone)]
+pub struct Script {
+    assignments: Vec<(String, Expression)>,
+}
+
+impl Script {
+    pub fn parse(text: &str) -> Result<Script, ExpressionError> {
+        let mut assignments = Vec::new();
+
+        for statement in split_statements(text) {
+            let statement = statement.trim();
+            if statement.is_empty() {
+                continue;
+            }
+
+            let invalid = || ExpressionError::InvalidAssignment(statement.to_string());
+            let (target, expression) = statement.split_once(":=").ok_or_else(invalid)?;
+            let target = target.trim();
+            let key = match target.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+                Some(key) if !key.trim().is_empty() => key.trim(),
+                None if is_identifier(target) => target,
+                _ => return Err(invalid()),
+            };
+
+            assignments.push((key.to_string(), Expression::parse(expression)?));
+        }
+
+        Ok(Script { assignments })
+    }
+
+    /// Keys the script assigns to, in order and without duplicates. A key of
+    /// the root blackboard keeps its `@`.
+    pub fn targets(&self) -> Vec<&str> {
+        let mut keys = Vec::new();
+        for (key, _) in &self.assignments {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key.as_str());
+            }
+        }
+
+        keys
+    }
+
+    /// Runs the assignments in order, so each one sees the entries written by
+    /// the ones before it.
+    ///
+    /// Existing numeric entries keep their type. New entries get an `i64`
+    /// (`u64` or `i128` if it doesn't fit), `f64`, `bool` or `String`.
+    pub async fn execute(&self, blackboard: &Blackboard) -> Result<(), ExpressionError> {
+        for (key, expression) in &self.assignments {
+            let value = expression.evaluate_with(blackboard).await?;
+            write_variable(blackboard, key, value).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits `text` at every `;` that isn't in a string or braces.
+fn split_statements(text: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut closing = None;
+
+    for (pos, c) in text.char_indices() {
+        match (closing, c) {
+            (Some(end), c) if c == end => closing = None,
+            (Some(_), _) => {}
+            (None, '\'') => closing = Some('\''),
+            (None, '{') => closing = Some('}'),
+            (None, ';') => {
+                statements.push(&text[start..pos]);
+                start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&text[start..]);
+
+    statements
+}
+
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let name = name.strip_prefix('@').unwrap_or(name);
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns the blackboard that holds `key` and the key in it: the root
+/// blackboard for `@key`.
+fn resolve<'a>(blackboard: &Blackboard, key: &'a str) -> (Blackboard, &'a str) {
+    match key.strip_prefix('@') {
+        Some(key) => (blackboard.root(), key),
+        None => (blackboard.clone(), key),
+    }
+}
+
+async fn write_variable(
+    blackboard: &Blackboard,
+    key: &str,
+    value: Value,
+) -> Result<(), ExpressionError> {
+    let (mut blackboard, key) = resolve(blackboard, key);
+
+    match value {
+        Value::Int(value) => {
+            macro_rules! try_int {
+                ($($t:ty),*) => {
+                    $(
+                        if blackboard.get_exact::<$t>(key).await.is_some() {
+                            let value = <$t>::try_from(value).map_err(|_| {
+                                ExpressionError::ValueOutOfRange(key.to_string(), value.to_string())
+                            })?;
+                            blackboard.set(key, value).await;
+                            return Ok(());
+                        }
+                    )*
+                };
+            }
+
+            try_int!(i64, i32, i16, i8, i128, u64, u32, u16, u8, u128, isize, usize);
+
+            if let Ok(value) = i64::try_from(value) {
+                blackboard.set(key, value).await;
+            } else if let Ok(value) = u64::try_from(value) {
+                blackboard.set(key, value).await;
+            } else {
+                blackboard.set(key, value).await;
+            }
+        }
+        Value::Float(value) => match blackboard.get_exact::<f32>(key).await {
+            Some(_) => blackboard.set(key, value as f32).await,
+            None => blackboard.set(key, value).await,
+        },
+        Value::Bool(value) => blackboard.set(key, value).await,
+        Value::String(value) => blackboard.set(key, value).await,
+    }
+
+    Ok(())
+}
+
+async fn read_variable(blackboard: &mut Blackboard, key: &str) -> Option<Value> {
+    macro_rules! try_int {
+        ($($t:ty),*) => {
+            $(
+                if let Some(value) = blackboard.get_exact::<$t>(key).await {
+                    return i128::try_from(value).ok().map(Value::Int);
+                }
+            )*
+        };
+    }
+
+    try_int!(i64, i32, i16, i8, i128, u64, u32, u16, u8, u128, isize, usize);
+
+    if let Some(value) = blackboard.get_exact::<f64>(key).await {
+        return Some(Value::Float(value));
+    }
+    if let Some(value) = blackboard.get_exact::<f32>(key).await {
+        return Some(Value::Float(value as f64));
+    }
+    if let Some(value) = blackboard.get_exact::<bool>(key).await {
+        return Some(Value::Bool(value));
+    }
+    if let Some(value) = blackboard.get_exact::<String>(key).await {
+        return Some(Value::from_text(&value));
+    }
+    if let Some(value) = blackboard.get_exact::<&'static str>(key).await {
+        return Some(Value::from_text(value));
+    }
+
+    None
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ExpressionError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        if c.is_whitespace() {
+            pos += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+                pos += 1;
+            }
+            let number: String = chars[start..pos].iter().collect();
+
+            let value = match number.parse::<i128>() {
+                Ok(value) => Value::Int(value),
+                Err(_) => match number.parse::<f64>() {
+                    Ok(value) => Value::Float(value),
+                    Err(_) => return Err(ExpressionError::UnexpectedToken(number)),
+                },
+            };
+            tokens.push(Token::Value(value));
+        } else if c.is_alphabetic() || c == '_' || c == '@' {
+            let start = pos;
+            pos += 1;
+            while pos < chars.len() && (chars[pos].is_alphanumeric() || chars[pos] == '_') {
+                pos += 1;
+            }
+            let name: String = chars[start..pos].iter().collect();
+
+            tokens.push(match name.as_str() {
+                "true" => Token::Value(Value::Bool(true)),
+                "false" => Token::Value(Value::Bool(false)),
+                _ => Token::Variable(name),
+            });
+        } else if c == '\'' || c == '{' {
+            let end = if c == '\'' { '\'' } else { '}' };
+            let start = pos + 1;
+            pos = start;
+            while pos < chars.len() && chars[pos] != end {
+                pos += 1;
+            }
+            if pos == chars.len() {
+                return Err(ExpressionError::UnexpectedEnd);
+            }
+            let content: String = chars[start..pos].iter().collect();
+            pos += 1;
+
+            tokens.push(match c {
+                '\'' => Token::Value(Value::String(content)),
+                _ => Token::Variable(content.trim().to_string()),
+            });
+        } else if c == '(' {
+            tokens.push(Token::LeftParen);
+            pos += 1;
+        } else if c == ')' {
+            tokens.push(Token::RightParen);
+            pos += 1;
+        } else {
+            let rest: String = chars[pos..chars.len().min(pos + 2)].iter().collect();
+            let operator = ["||", "&&", "==", "!=", "<=", ">="]
+                .into_iter()
+                .chain(["<", ">", "+", "-", "*", "/", "%", "!"])
+                .find(|op| rest.starts_with(op))
+                .ok_or(ExpressionError::UnexpectedChar(c, pos))?;
+
+            tokens.push(Token::Operator(operator));
+            pos += operator.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Result<Token, ExpressionError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or(ExpressionError::UnexpectedEnd)?;
+        self.pos += 1;
+
+        Ok(token)
+    }
+
+    /// Parses operators of precedence `level` and higher
+    fn binary(&mut self, level: usize) -> Result<Expr, ExpressionError> {
+        if level == BINARY_OPERATORS.len() {
+            return self.unary();
+        }
+
+        let mut left = self.binary(level + 1)?;
+
+        while let Some(Token::Operator(op)) = self.tokens.get(self.pos) {
+            if !BINARY_OPERATORS[level].contains(op) {
+                break;
+            }
+            let op = *op;
+            self.pos += 1;
+
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExpressionError> {
+        match self.next()? {
+            Token::Operator(op @ ("-" | "!")) => Ok(Expr::Unary(op, Box::new(self.unary()?))),
+            Token::Value(value) => Ok(Expr::Value(value)),
+            Token::Variable(name) => Ok(Expr::Variable(name)),
+            Token::LeftParen => {
+                let expr = self.binary(0)?;
+                match self.next()? {
+                    Token::RightParen => Ok(expr),
+                    token => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+                }
+            }
+            token => Err(ExpressionError::UnexpectedToken(format!("{token:?}"))),
+        }
+    }
+}
+
+fn eval(expr: &Expr, variables: &HashMap<String, Value>) -> Result<Value, ExpressionError> {
+    match expr {
+        Expr::Value(value) => Ok(value.clone()),
+        Expr::Variable(name) => variables
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ExpressionError::UnknownVariable(name.clone())),
+        Expr::Unary(op, expr) => match (*op, eval(expr, variables)?) {
+            ("-", Value::Int(value)) => value
+                .checked_neg()
+                .map(Value::Int)
+                .ok_or_else(|| ExpressionError::ArithmeticError(op.to_string())),
+            ("-", Value::Float(value)) => Ok(Value::Float(-value)),
+            ("!", Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (op, value) => Err(ExpressionError::TypeMismatch(
+                op.to_string(),
+                value.type_name().to_string(),
+            )),
+        },
+        // Short-circuit logical operators
+        Expr::Binary(op @ ("&&" | "||"), left, right) => {
+            let left = as_bool(op, eval(left, variables)?)?;
+            match (*op, left) {
+                ("&&", false) => Ok(Value::Bool(false)),
+                ("||", true) => Ok(Value::Bool(true)),
+                _ => Ok(Value::Bool(as_bool(op, eval(right, variables)?)?)),
+            }
+        }
+        Expr::Binary(op, left, right) => {
+            binary(op, eval(left, variables)?, eval(right, variables)?)
+        }
+    }
+}
+
+fn as_bool(op: &str, value: Value) -> Result<bool, ExpressionError> {
+    match value {
+        Value::Bool(value) => Ok(value),
+        value => Err(ExpressionError::TypeMismatch(
+            op.to_string(),
+            value.type_name().to_string(),
+        )),
+    }
+}
+
+fn binary(op: &str, left: Value, right: Value) -> Result<Value, ExpressionError> {
+    let mismatch = |left: &Value, right: &Value| {
+        ExpressionError::TypeMismatch(
+            op.to_string(),
+            format!("{} and {}", left.type_name(), right.type_name()),
+        )
+    };
+    let overflow = || ExpressionError::ArithmeticError(op.to_string());
+
+    match (&left, &right) {
+        (Value::Int(l), Value::Int(r)) => {
+            let (l, r) = (*l, *r);
+            let value = match op {
+                "+" => Value::Int(l.checked_add(r).ok_or_else(overflow)?),
+                "-" => Value::Int(l.checked_sub(r).ok_or_else(overflow)?),
+                "*" => Value::Int(l.checked_mul(r).ok_or_else(overflow)?),
+                "/" => Value::Int(l.checked_div(r).ok_or_else(overflow)?),
+                "%" => Value::Int(l.checked_rem(r).ok_or_else(overflow)?),
+                _ => compare(op, l.partial_cmp(&r)),
+            };
+            Ok(value)
+        }
+        (Value::String(l), Value::String(r)) => match op {
+            "+" => Ok(Value::String(format!("{l}{r}"))),
+            "-" | "*" | "/" | "%" => Err(mismatch(&left, &right)),
+            _ => Ok(compare(op, l.partial_cmp(r))),
+        },
+        (Value::Bool(l), Value::Bool(r)) if matches!(op, "==" | "!=") => {
+            Ok(compare(op, l.partial_cmp(r)))
+        }
+        _ => match (left.as_float(), right.as_float()) {
+            (Some(l), Some(r)) => Ok(match op {
+                "+" => Value::Float(l + r),
+                "-" => Value::Float(l - r),
+                "*" => Value::Float(l * r),
+                "/" => Value::Float(l / r),
+                "%" => Value::Float(l % r),
+                _ => compare(op, l.partial_cmp(&r)),
+            }),
+            _ => Err(mismatch(&left, &right)),
+        },
+    }
+}
+
+fn compare(op: &str, ordering: Option<std::cmp::Ordering>) -> Value {
+    use std::cmp::Ordering::{Equal, Greater, Less};
+
+    let result = match op {
+        "==" => ordering == Some(Equal),
+        "!=" => ordering != Some(Equal),
+        "<" => ordering == Some(Less),
+        "<=" => matches!(ordering, Some(Less | Equal)),
+        ">" => ordering == Some(Greater),
+        ">=" => matches!(ordering, Some(Greater | Equal)),
+        _ => unreachable!("{op} isn't a comparison"),
+    };
+
+    Value::Bool(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_str(text: &str) -> Result<Value, ExpressionError> {
+        Expression::parse(text)?.evaluate(&HashMap::new())
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval_str("1 + 2 * 3").unwrap(), Value::Int(7));
+        assert_eq!(eval_str("(1 + 2) * 3").unwrap(), Value::Int(9));
+        assert_eq!(eval_str("7 / 2").unwrap(), Value::Int(3));
+        assert_eq!(eval_str("7 % 4 - -1").unwrap(), Value::Int(4));
+        assert_eq!(eval_str("7 / 2.0").unwrap(), Value::Float(3.5));
+        assert_eq!(
+            eval_str("'a' + 'b'").unwrap(),
+            Value::String(String::from("ab"))
+        );
+
+        assert!(matches!(
+            eval_str("1 / 0"),
+            Err(ExpressionError::ArithmeticError(_))
+        ));
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(eval_str("1 < 2 && 2 <= 2").unwrap(), Value::Bool(true));
+        assert_eq!(eval_str("!(1 == 1) || 3 > 4").unwrap(), Value::Bool(false));
+        assert_eq!(eval_str("1 == 1.0").unwrap(), Value::Bool(true));
+        assert_eq!(eval_str("'a' != 'b'").unwrap(), Value::Bool(true));
+        // The right side isn't evaluated
+        assert_eq!(eval_str("false && missing").unwrap(), Value::Bool(false));
+
+        assert!(matches!(
+            eval_str("1 && true"),
+            Err(ExpressionError::TypeMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert!(matches!(
+            eval_str("1 +"),
+            Err(ExpressionError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            eval_str("(1 + 2"),
+            Err(ExpressionError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            eval_str("1 2"),
+            Err(ExpressionError::UnexpectedToken(_))
+        ));
+        assert!(matches!(
+            eval_str("1 # 2"),
+            Err(ExpressionError::UnexpectedChar('#', 2))
+        ));
+    }
+
+    #[tokio::test]
+    async fn blackboard_variables() {
+        let mut blackboard = Blackboard::create();
+        blackboard.set("base", 21u32).await;
+        blackboard.set("scale", String::from("0.5")).await;
+        blackboard.set("nav/ready", true).await;
+
+        let expression = Expression::parse("base * 2 + base").unwrap();
+        assert_eq!(expression.variables(), vec!["base"]);
+
+        assert_eq!(
+            evaluate("base * scale", &blackboard).await.unwrap(),
+            Value::Float(10.5)
+        );
+        assert_eq!(
+            evaluate("{nav/ready} && base > 20", &blackboard)
+                .await
+                .unwrap(),
+            Value::Bool(true)
+        );
+        assert!(matches!(
+            evaluate("missing + 1", &blackboard).await,
+            Err(ExpressionError::UnknownVariable(name)) if name == "missing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn unsigned_values() {
+        let mut blackboard = Blackboard::create();
+        blackboard.set("id", u64::MAX).await;
+        blackboard
+            .set("stamp", String::from("18446744073709551615"))
+            .await;
+
+        assert_eq!(
+            evaluate("id", &blackboard).await.unwrap(),
+            Value::Int(u64::MAX as i128)
+        );
+        assert_eq!(
+            evaluate("id == stamp", &blackboard).await.unwrap(),
+            Value::Bool(true)
+        );
+        assert_eq!(
+            evaluate("id - 1 < 18446744073709551615", &blackboard)
+                .await
+                .unwrap(),
+            Value::Bool(true)
+        );
+
+        // The result converts back to the original type without loss
+        let value = evaluate("id", &blackboard).await.unwrap();
+        blackboard.set("copy", value.to_string()).await;
+        assert_eq!(blackboard.get::<u64>("copy").await, Some(u64::MAX));
+    }
+
+    #[tokio::test]
+    async fn script() {
+        let mut root = Blackboard::create();
+        root.set("count", 1u8).await;
+        let mut blackboard = Blackboard::with_parent(&root).await;
+
+        Script::parse("A := 5; B := A * 2;; {nav/goal} := 'a;b'; @count := @count + B")
+            .unwrap()
+            .execute(&blackboard)
+            .await
+            .unwrap();
+
+        assert_eq!(blackboard.get_exact::<i64>("A").await, Some(5));
+        assert_eq!(blackboard.get_exact::<i64>("B").await, Some(10));
+        assert_eq!(
+            blackboard.get_exact::<String>("nav/goal").await.as_deref(),
+            Some("a;b")
+        );
+        // Existing entries keep their type
+        assert_eq!(root.get_exact::<u8>("count").await, Some(11));
+        assert_eq!(root.get_exact::<i64>("A").await, None);
+
+        assert!(matches!(
+            Script::parse("A = 5"),
+            Err(ExpressionError::InvalidAssignment(_))
+        ));
+        assert!(matches!(
+            Script::parse("A + 1 := 5"),
+            Err(ExpressionError::InvalidAssignment(_))
+        ));
+        assert!(matches!(
+            Script::parse("A := 5 +"),
+            Err(ExpressionError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            Script::parse("@count := 256").unwrap().execute(&blackboard).await,
+            Err(ExpressionError::ValueOutOfRange(key, _)) if key == "count"
+        ));
+    }
+}
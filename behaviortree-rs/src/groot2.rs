@@ -0,0 +1,633 @@
+//! Live monitoring with Groot2, enabled by the `groot2` feature. See
+//! `AsyncTree::enable_groot2_publisher()`.
+//!
+//! Groot2 talks to BehaviorTree.CPP over two ZeroMQ sockets: a REP socket that
+//! answers its requests, and a PUB socket on the next port. The parts of ZMTP
+//! 3.0 these need are implemented in `zmtp`, so the feature doesn't add any
+//! dependencies.
+
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::{BuildHasher, Hasher},
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use quick_xml::Writer;
+use thiserror::Error;
+
+use crate::{
+    basic_types::{NodeStatus, TreeNodeManifest},
+    nodes::{decorator::SubTreeNode, TreeNodePtr},
+    tree::write_node_model,
+};
+
+/// Version of the Groot2 protocol, sent in every header
+const PROTOCOL_ID: u8 = 2;
+/// Request for the tree as XML
+const REQUEST_FULLTREE: u8 = b'T';
+/// Request for the status of every node
+const REQUEST_STATUS: u8 = b'S';
+/// Length of a request header: protocol, request type and a `u32` id
+const REQUEST_HEADER_LEN: usize = 6;
+
+/// How long a publish waits for a subscriber that doesn't read
+const PUBLISH_TIMEOUT: Duration = Duration::from_millis(100);
+/// How many pairs of free ports are tried when the port is 0
+const BIND_ATTEMPTS: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum Groot2Error {
+    #[error("Couldn't open a socket for Groot2: {0}")]
+    Io(#[from] io::Error),
+    #[error("Groot2 needs two ports, but [{0}] is the last one")]
+    /// `(port)`
+    InvalidPort(u16),
+    #[error("The Groot2 publisher is already enabled for this tree")]
+    AlreadyEnabled,
+}
+
+/// Status of a node as Groot2 shows it. A node that went back to IDLE is
+/// shown with the status it had before, as `10 + status`.
+fn encode_status(status: &NodeStatus, previous: u8) -> u8 {
+    match status {
+        NodeStatus::Idle => match previous {
+            1..=4 => 10 + previous,
+            previous => previous,
+        },
+        NodeStatus::Running => 1,
+        NodeStatus::Success => 2,
+        NodeStatus::Failure => 3,
+        NodeStatus::Skipped => 4,
+    }
+}
+
+#[derive(Debug, Default)]
+struct Statuses {
+    /// Encoded status of every node, by uid
+    current: BTreeMap<u16, u8>,
+    /// Changes that weren't published yet
+    changed: BTreeMap<u16, u8>,
+}
+
+impl Statuses {
+    fn record(&mut self, uid: u16, status: &NodeStatus) {
+        let previous = self.current.get(&uid).copied().unwrap_or_default();
+        let status = encode_status(status, previous);
+
+        if status != previous {
+            self.current.insert(uid, status);
+            self.changed.insert(uid, status);
+        }
+    }
+}
+
+/// Serializes statuses as Groot2 expects them: the uid as a little-endian
+/// `u16`, followed by the status as a `u8`.
+fn status_buffer<'a>(statuses: impl IntoIterator<Item = (&'a u16, &'a u8)>) -> Vec<u8> {
+    statuses
+        .into_iter()
+        .flat_map(|(uid, status)| {
+            let [low, high] = uid.to_le_bytes();
+            [low, high, *status]
+        })
+        .collect()
+}
+
+/// State shared by the tree and the threads serving Groot2.
+#[derive(Debug)]
+struct Shared {
+    /// Identifies the tree in every reply, so Groot2 notices a new tree
+    tree_id: [u8; 16],
+    tree_xml: String,
+    statuses: Mutex<Statuses>,
+    /// Open connections of both sockets, to close them on drop
+    connections: Mutex<HashMap<u64, TcpStream>>,
+    subscribers: Mutex<HashMap<u64, TcpStream>>,
+    next_connection: AtomicU64,
+    next_message: AtomicU32,
+    stop: AtomicBool,
+}
+
+impl Shared {
+    /// Answers the request in `frames`, which comes from a REQ socket.
+    fn answer(&self, frames: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        // REQ sockets put an empty delimiter frame before the request, which
+        // must be put before the reply too
+        let header = match frames {
+            [delimiter, header, ..] if delimiter.is_empty() => header,
+            _ => return error_reply("Request not recognized"),
+        };
+        if header.len() != REQUEST_HEADER_LEN {
+            return error_reply("Request not recognized");
+        }
+        if header[0] != PROTOCOL_ID {
+            return error_reply("wrong protocol");
+        }
+
+        let payload = match header[1] {
+            REQUEST_FULLTREE => self.tree_xml.as_bytes().to_vec(),
+            REQUEST_STATUS => status_buffer(&self.statuses.lock().unwrap().current),
+            _ => return error_reply("Request not recognized"),
+        };
+
+        vec![Vec::new(), self.reply_header(header), payload]
+    }
+
+    /// The request header, followed by the tree id.
+    fn reply_header(&self, request_header: &[u8]) -> Vec<u8> {
+        [request_header, &self.tree_id].concat()
+    }
+
+    /// Returns a message with the status changes since the last one, with
+    /// the header of a status request, or `None` if nothing changed.
+    fn take_changes(&self) -> Option<[Vec<u8>; 2]> {
+        let changed = std::mem::take(&mut self.statuses.lock().unwrap().changed);
+        if changed.is_empty() {
+            return None;
+        }
+
+        let id = self.next_message.fetch_add(1, Ordering::Relaxed);
+        let mut header = vec![PROTOCOL_ID, REQUEST_STATUS];
+        header.extend(id.to_le_bytes());
+
+        Some([self.reply_header(&header), status_buffer(&changed)])
+    }
+
+    /// Sends `message` to every subscriber.
+    fn publish(&self, message: &[Vec<u8>]) {
+        // Subscribers that can't keep up are dropped
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|_, stream| zmtp::write_message(stream, message).is_ok());
+    }
+
+    fn add_connection(&self, stream: &TcpStream) -> io::Result<u64> {
+        let id = self.next_connection.fetch_add(1, Ordering::Relaxed);
+        self.connections
+            .lock()
+            .unwrap()
+            .insert(id, stream.try_clone()?);
+
+        Ok(id)
+    }
+}
+
+fn error_reply(message: &str) -> Vec<Vec<u8>> {
+    vec![Vec::new(), b"error".to_vec(), message.as_bytes().to_vec()]
+}
+
+/// Reports the status a node returned from its tick to the Groot2 publisher
+/// of its tree. Given to every node by `AsyncTree::enable_groot2_publisher()`.
+#[derive(Clone)]
+pub(crate) struct StatusHook(Arc<Shared>);
+
+impl std::fmt::Debug for StatusHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusHook").finish_non_exhaustive()
+    }
+}
+
+impl StatusHook {
+    pub(crate) fn report(&self, uid: u16, status: &NodeStatus) {
+        self.0.statuses.lock().unwrap().record(uid, status);
+    }
+}
+
+/// Serves Groot2 for one tree until it's dropped.
+#[derive(Debug)]
+pub(crate) struct Groot2Publisher {
+    shared: Arc<Shared>,
+    /// Local addresses of the REP and the PUB socket
+    addresses: [SocketAddr; 2],
+    /// Status messages for the thread that sends them to the subscribers, so
+    /// the tick never waits for the network
+    messages: Sender<[Vec<u8>; 2]>,
+}
+
+impl Groot2Publisher {
+    /// Opens the REP socket on `port` of `ip` and the PUB socket on
+    /// `port + 1`. If `port` is 0, a pair of free ports is picked.
+    pub(crate) fn new(
+        root: &TreeNodePtr,
+        ip: IpAddr,
+        port: u16,
+    ) -> Result<Groot2Publisher, Groot2Error> {
+        let (server, publisher) = bind_ports(ip, port)?;
+        let addresses = [server.local_addr()?, publisher.local_addr()?];
+
+        let shared = Arc::new(Shared {
+            tree_id: tree_id(),
+            tree_xml: write_tree_xml(root),
+            statuses: Mutex::default(),
+            connections: Mutex::default(),
+            subscribers: Mutex::default(),
+            next_connection: AtomicU64::new(0),
+            next_message: AtomicU32::new(0),
+            stop: AtomicBool::new(false),
+        });
+
+        let state = Arc::clone(&shared);
+        thread::spawn(move || accept(server, state, serve_requests));
+        let state = Arc::clone(&shared);
+        thread::spawn(move || accept(publisher, state, add_subscriber));
+
+        // Ends when the publisher is dropped, with the sender
+        let (messages, receiver) = mpsc::channel::<[Vec<u8>; 2]>();
+        let state = Arc::clone(&shared);
+        thread::spawn(move || {
+            for message in receiver {
+                state.publish(&message);
+            }
+        });
+
+        Ok(Groot2Publisher {
+            shared,
+            addresses,
+            messages,
+        })
+    }
+
+    pub(crate) fn hook(&self) -> StatusHook {
+        StatusHook(Arc::clone(&self.shared))
+    }
+
+    /// The port of the REP socket. The PUB socket is on the next one.
+    pub(crate) fn port(&self) -> u16 {
+        self.addresses[0].port()
+    }
+
+    /// Records the statuses of all nodes after a tick, and publishes what
+    /// changed during the tick in the background.
+    pub(crate) fn update(&self, report: &HashMap<u16, NodeStatus>) {
+        {
+            let mut statuses = self.shared.statuses.lock().unwrap();
+            for (uid, status) in report {
+                statuses.record(*uid, status);
+            }
+        }
+
+        if let Some(message) = self.shared.take_changes() {
+            let _ = self.messages.send(message);
+        }
+    }
+}
+
+/// Binds the REP socket to `port` and the PUB socket to `port + 1`. If
+/// `port` is 0, tries a few free ports until the next one is free as well.
+fn bind_ports(ip: IpAddr, port: u16) -> Result<(TcpListener, TcpListener), Groot2Error> {
+    if port != 0 {
+        let publisher_port = port.checked_add(1).ok_or(Groot2Error::InvalidPort(port))?;
+        let server = TcpListener::bind((ip, port))?;
+        let publisher = TcpListener::bind((ip, publisher_port))?;
+
+        return Ok((server, publisher));
+    }
+
+    let mut attempts = 1;
+    loop {
+        let server = TcpListener::bind((ip, 0))?;
+        let port = server.local_addr()?.port();
+
+        let error = match port.checked_add(1) {
+            Some(publisher_port) => match TcpListener::bind((ip, publisher_port)) {
+                Ok(publisher) => return Ok((server, publisher)),
+                Err(e) => Groot2Error::Io(e),
+            },
+            None => Groot2Error::InvalidPort(port),
+        };
+        if attempts == BIND_ATTEMPTS {
+            return Err(error);
+        }
+        attempts += 1;
+    }
+}
+
+impl Drop for Groot2Publisher {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+
+        // Wake up the threads waiting for connections, so they see `stop`
+        for mut address in self.addresses {
+            if address.ip().is_unspecified() {
+                address.set_ip(match address {
+                    SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+                });
+            }
+            let _ = TcpStream::connect(address);
+        }
+
+        let connections = self
+            .shared
+            .connections
+            .lock()
+            .unwrap()
+            .drain()
+            .collect::<Vec<_>>();
+        let subscribers = self
+            .shared
+            .subscribers
+            .lock()
+            .unwrap()
+            .drain()
+            .collect::<Vec<_>>();
+        for (_, stream) in connections.into_iter().chain(subscribers) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Hands every connection to `listener` to `handle` in a thread of its own,
+/// until the publisher is dropped.
+fn accept(
+    listener: TcpListener,
+    shared: Arc<Shared>,
+    handle: fn(TcpStream, &Shared) -> io::Result<()>,
+) {
+    for stream in listener.incoming() {
+        if shared.stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || handle(stream, &shared));
+    }
+}
+
+fn serve_requests(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    zmtp::handshake(&mut stream, "REP")?;
+    let id = shared.add_connection(&stream)?;
+
+    let result = loop {
+        let reply = match zmtp::read_message(&mut stream) {
+            Ok(request) => shared.answer(&request),
+            Err(e) => break Err(e),
+        };
+        if let Err(e) = zmtp::write_message(&mut stream, &reply) {
+            break Err(e);
+        }
+    };
+
+    shared.connections.lock().unwrap().remove(&id);
+    result
+}
+
+fn add_subscriber(mut stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    zmtp::handshake(&mut stream, "PUB")?;
+    // A subscriber that doesn't read mustn't hold up the others for long
+    stream.set_write_timeout(Some(PUBLISH_TIMEOUT))?;
+
+    // Subscriptions aren't read: every subscriber gets every message
+    let id = shared.next_connection.fetch_add(1, Ordering::Relaxed);
+    shared.subscribers.lock().unwrap().insert(id, stream);
+
+    Ok(())
+}
+
+/// Returns a random id, without a dependency on a random number generator.
+fn tree_id() -> [u8; 16] {
+    let state = RandomState::new();
+    let mut id = [0; 16];
+
+    for (i, chunk) in id.chunks_mut(8).enumerate() {
+        let mut hasher = state.build_hasher();
+        hasher.write_usize(i);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+
+    id
+}
+
+/// Writes the tree under `root` as XML, the way BehaviorTree.CPP sends it to
+/// Groot2: every node has its `_uid` and `_fullpath`, and the
+/// `<TreeNodesModel>` describes the types of the nodes in the tree.
+///
+/// Each `<SubTree>` is written as a `<BehaviorTree>` of its own, with the
+/// subtree's path as its ID, since a subtree can be used more than once.
+fn write_tree_xml(root: &TreeNodePtr) -> String {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 4);
+    let mut trees = vec![(String::from("MainTree"), root)];
+    let mut models = BTreeMap::new();
+
+    writer
+        .create_element("root")
+        .with_attribute(("BTCPP_format", "4"))
+        .with_attribute(("main_tree_to_execute", "MainTree"))
+        .write_inner_content(|writer| {
+            // Subtrees are added to `trees` as they're found
+            let mut next = 0;
+            while let Some((id, root)) = trees.get(next).cloned() {
+                writer
+                    .create_element("BehaviorTree")
+                    .with_attribute(("ID", id.as_str()))
+                    .write_inner_content(|writer| {
+                        write_node(writer, root, &mut trees, &mut models)
+                    })?;
+                next += 1;
+            }
+
+            writer
+                .create_element("TreeNodesModel")
+                .write_inner_content(|writer| {
+                    for manifest in models.values() {
+                        write_node_model(writer, manifest)?;
+                    }
+                    Ok(())
+                })?;
+            Ok(())
+        })
+        .expect("Writing to a Vec can't fail");
+
+    String::from_utf8(writer.into_inner()).expect("The tree is written from strings")
+}
+
+fn write_node<'a>(
+    writer: &mut Writer<Vec<u8>>,
+    node: &'a TreeNodePtr,
+    trees: &mut Vec<(String, &'a TreeNodePtr)>,
+    models: &mut BTreeMap<String, TreeNodeManifest>,
+) -> Result<(), quick_xml::Error> {
+    let config = node.config();
+    let uid = node.uid().to_string();
+    let is_subtree = node.as_any().is::<SubTreeNode>();
+
+    let tag = match (&config.manifest, is_subtree) {
+        (_, true) => String::from("SubTree"),
+        (Some(manifest), false) => {
+            models
+                .entry(manifest.registration_id.clone())
+                .or_insert_with(|| manifest.as_ref().clone());
+            manifest.registration_id.clone()
+        }
+        (None, false) => node.name().clone(),
+    };
+
+    let mut element = writer.create_element(&tag);
+    if is_subtree {
+        element = element.with_attribute(("ID", config.path.as_str()));
+    }
+    element = element
+        .with_attribute(("_uid", uid.as_str()))
+        .with_attribute(("_fullpath", config.path.as_str()));
+
+    let mut ports: Vec<_> = config
+        .input_ports
+        .iter()
+        .chain(config.output_ports.iter())
+        .collect();
+    ports.sort();
+    for (name, value) in ports {
+        element = element.with_attribute((name.as_str(), value.as_str()));
+    }
+
+    let children = node.child_nodes();
+    if is_subtree {
+        trees.extend(
+            children
+                .into_iter()
+                .map(|child| (config.path.clone(), child)),
+        );
+        element.write_empty()?;
+    } else if children.is_empty() {
+        element.write_empty()?;
+    } else {
+        element.write_inner_content(|writer| {
+            for child in children {
+                write_node(writer, child, trees, models)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+/// The parts of ZMTP 3.0 (<https://rfc.zeromq.org/spec/23/>) that Groot2
+/// needs: the NULL security mechanism, and multipart messages.
+mod zmtp {
+    use std::{
+        io::{self, Read, Write},
+        net::TcpStream,
+    };
+
+    const MORE: u8 = 0x01;
+    const LONG: u8 = 0x02;
+    const COMMAND: u8 = 0x04;
+
+    /// Frames larger than this are treated as a broken connection
+    const MAX_FRAME_LEN: u64 = 64 * 1024 * 1024;
+
+    fn invalid_data(message: &str) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, message)
+    }
+
+    /// Exchanges greetings and READY commands with the peer. `socket_type`
+    /// is this end's type, e.g. `"REP"`.
+    pub(super) fn handshake(stream: &mut TcpStream, socket_type: &str) -> io::Result<()> {
+        // Signature, version 3.0, mechanism and as-server, padded to 64 bytes
+        let mut greeting = [0; 64];
+        greeting[0] = 0xFF;
+        greeting[9] = 0x7F;
+        greeting[10] = 3;
+        greeting[12..16].copy_from_slice(b"NULL");
+        stream.write_all(&greeting)?;
+
+        let mut peer = [0; 64];
+        stream.read_exact(&mut peer)?;
+        if peer[0] != 0xFF || peer[9] != 0x7F || peer[10] < 3 || &peer[12..17] != b"NULL\0" {
+            return Err(invalid_data(
+                "Peer isn't a ZMTP 3 socket with NULL security",
+            ));
+        }
+
+        let mut ready = b"\x05READY\x0bSocket-Type".to_vec();
+        ready.extend((socket_type.len() as u32).to_be_bytes());
+        ready.extend(socket_type.as_bytes());
+        write_frame(stream, COMMAND, &ready)?;
+
+        match read_frame(stream)? {
+            (flags, body) if flags & COMMAND != 0 && body.starts_with(b"\x05READY") => Ok(()),
+            _ => Err(invalid_data("Peer didn't send READY")),
+        }
+    }
+
+    fn write_frame(stream: &mut impl Write, flags: u8, body: &[u8]) -> io::Result<()> {
+        match u8::try_from(body.len()) {
+            Ok(len) => stream.write_all(&[flags, len])?,
+            Err(_) => {
+                stream.write_all(&[flags | LONG])?;
+                stream.write_all(&(body.len() as u64).to_be_bytes())?;
+            }
+        }
+
+        stream.write_all(body)
+    }
+
+    fn read_frame(stream: &mut TcpStream) -> io::Result<(u8, Vec<u8>)> {
+        let mut flags = [0];
+        stream.read_exact(&mut flags)?;
+        let flags = flags[0];
+
+        let len = match flags & LONG {
+            0 => {
+                let mut len = [0];
+                stream.read_exact(&mut len)?;
+                u64::from(len[0])
+            }
+            _ => {
+                let mut len = [0; 8];
+                stream.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            }
+        };
+        if len > MAX_FRAME_LEN {
+            return Err(invalid_data("Frame is too large"));
+        }
+
+        let mut body = vec![0; len as usize];
+        stream.read_exact(&mut body)?;
+
+        Ok((flags, body))
+    }
+
+    /// Reads the frames of the next message. Commands such as PING aren't
+    /// part of a message and are skipped.
+    pub(super) fn read_message(stream: &mut TcpStream) -> io::Result<Vec<Vec<u8>>> {
+        let mut frames = Vec::new();
+
+        loop {
+            let (flags, body) = read_frame(stream)?;
+            if flags & COMMAND != 0 {
+                continue;
+            }
+
+            frames.push(body);
+            if flags & MORE == 0 {
+                return Ok(frames);
+            }
+        }
+    }
+
+    /// Writes a message with the given frames, all at once.
+    pub(super) fn write_message(stream: &mut TcpStream, frames: &[Vec<u8>]) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            let flags = if i + 1 < frames.len() { MORE } else { 0 };
+            write_frame(&mut buffer, flags, frame)?;
+        }
+
+        stream.write_all(&buffer)
+    }
+}
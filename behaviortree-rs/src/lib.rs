@@ -225,8 +225,13 @@ extern crate self as behaviortree_rs;
 
 pub mod basic_types;
 pub mod blackboard;
+pub mod expression;
+pub mod loggers;
+#[cfg(feature = "groot2")]
+pub mod groot2;
 
 pub mod nodes;
+pub mod observer;
 
 pub mod macros;
 pub mod tree;
@@ -249,4 +254,24 @@ pub mod sync {
 
     pub use tokio::sync::Mutex;
     pub use tokio::task::spawn_blocking;
+
+    use tokio::runtime::{Handle, RuntimeFlavor};
+
+    /// Runs the blocking `f` on the current thread, e.g. a sync callback of a
+    /// node ticked by an async tree. On a multi-threaded Tokio runtime, the
+    /// worker first hands its other tasks to another thread with
+    /// `tokio::task::block_in_place()`, so `f` doesn't stall them. Anywhere
+    /// else, i.e. on a current-thread runtime or outside of Tokio, `f` is
+    /// just called.
+    pub fn block_in_place<F, R>(f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(f)
+            }
+            _ => f(),
+        }
+    }
 }
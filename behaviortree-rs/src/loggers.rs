@@ -0,0 +1,304 @@
+//! Loggers that show the status changes of a tree's nodes, like
+//! BehaviorTree.CPP's `StdCoutLogger` and `FileLogger2`.
+//!
+//! A logger adds a status callback to the tree, see
+//! `AsyncTree::add_status_callback()`, so several loggers and a
+//! `TreeObserver` can watch the same tree. It stops logging when it's
+//! dropped. Nodes grafted after the logger was created aren't logged.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    basic_types::{NodeStatus, NodeType},
+    nodes::TreeNodePtr,
+    tree::{AsyncTree, StatusCallbackHandle, SyncTree},
+};
+
+/// A node as it's shown in the logs
+#[derive(Debug)]
+struct LoggedNode {
+    uid: u16,
+    name: String,
+    path: String,
+    node_type: NodeType,
+}
+
+impl LoggedNode {
+    fn new(node: &TreeNodePtr) -> LoggedNode {
+        // The manifest tells conditions apart from actions
+        let node_type = match &node.config().manifest {
+            Some(manifest) => manifest.node_type.clone(),
+            None => node.node_type(),
+        };
+
+        Self {
+            uid: node.uid(),
+            name: node.name().clone(),
+            path: node.path().clone(),
+            node_type,
+        }
+    }
+}
+
+/// Writes a status change of a node, with the seconds since the Unix epoch
+type LogFn = Box<dyn FnMut(f64, &LoggedNode, &NodeStatus, &NodeStatus) + Send>;
+
+struct LoggerState {
+    nodes: HashMap<u16, LoggedNode>,
+    /// Only nodes of these types are logged, if set
+    node_types: Option<Vec<NodeType>>,
+    /// Taken when the logger is dropped
+    log: Option<LogFn>,
+}
+
+/// The part shared by all loggers. Adds the status callback, and removes it
+/// when dropped.
+struct Logger {
+    state: Arc<Mutex<LoggerState>>,
+    callback: Option<StatusCallbackHandle>,
+}
+
+impl std::fmt::Debug for Logger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Logger").finish_non_exhaustive()
+    }
+}
+
+impl Logger {
+    fn new(log: LogFn) -> Logger {
+        Self {
+            state: Arc::new(Mutex::new(LoggerState {
+                nodes: HashMap::new(),
+                node_types: None,
+                log: Some(log),
+            })),
+            callback: None,
+        }
+    }
+
+    fn attach(tree: &mut AsyncTree, log: LogFn) -> Logger {
+        let mut logger = Logger::new(log);
+        tree.visit_nodes(|node| logger.add_node(node));
+        logger.callback = Some(tree.add_status_callback(logger.callback()));
+
+        logger
+    }
+
+    fn attach_sync(tree: &mut SyncTree, log: LogFn) -> Logger {
+        let mut logger = Logger::new(log);
+        tree.visit_nodes(|node| logger.add_node(node));
+        logger.callback = Some(tree.add_status_callback(logger.callback()));
+
+        logger
+    }
+
+    fn add_node(&self, node: &TreeNodePtr) {
+        let node = LoggedNode::new(node);
+        self.state.lock().unwrap().nodes.insert(node.uid, node);
+    }
+
+    fn set_node_types(&self, node_types: impl IntoIterator<Item = NodeType>) {
+        self.state.lock().unwrap().node_types = Some(node_types.into_iter().collect());
+    }
+
+    fn callback(&self) -> impl FnMut(u16, NodeStatus, NodeStatus) + Send + 'static {
+        let state = self.state.clone();
+
+        move |uid, previous, status| {
+            let mut state = state.lock().unwrap();
+            let LoggerState {
+                nodes,
+                node_types,
+                log,
+            } = &mut *state;
+
+            let (Some(log), Some(node)) = (log.as_mut(), nodes.get(&uid)) else {
+                return;
+            };
+            if let Some(node_types) = node_types {
+                if !node_types.contains(&node.node_type) {
+                    return;
+                }
+            }
+
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs_f64();
+            log(timestamp, node, &previous, &status);
+        }
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        if let Some(callback) = self.callback.take() {
+            callback.remove();
+        }
+
+        // Dropping the log function also closes the file of a `FileLogger`,
+        // even if the callback is still running
+        if let Ok(mut state) = self.state.lock() {
+            state.log = None;
+        }
+    }
+}
+
+/// Prints every status change of the tree's nodes to stdout, or to another
+/// writer, e.g. `[1697461234.123]: [MoveBase] IDLE -> RUNNING`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let _logger = StdoutLogger::new(&mut tree).with_node_types([NodeType::Action]);
+/// tree.tick_while_running().await?;
+/// ```
+#[derive(Debug)]
+pub struct StdoutLogger(Logger);
+
+impl StdoutLogger {
+    /// Starts logging the status changes of `tree`, until the logger is
+    /// dropped.
+    pub fn new(tree: &mut AsyncTree) -> StdoutLogger {
+        Self(Logger::attach(tree, Box::new(print_status_change)))
+    }
+
+    /// Sync version of `new()`
+    pub fn new_sync(tree: &mut SyncTree) -> StdoutLogger {
+        Self(Logger::attach_sync(tree, Box::new(print_status_change)))
+    }
+
+    /// Like `new()`, but writes the lines to `writer` instead of stdout.
+    pub fn to_writer(tree: &mut AsyncTree, writer: impl Write + Send + 'static) -> StdoutLogger {
+        Self(Logger::attach(tree, write_status_changes(writer)))
+    }
+
+    /// Sync version of `to_writer()`
+    pub fn to_writer_sync(
+        tree: &mut SyncTree,
+        writer: impl Write + Send + 'static,
+    ) -> StdoutLogger {
+        Self(Logger::attach_sync(tree, write_status_changes(writer)))
+    }
+
+    /// Only logs the nodes of the given types, e.g. `NodeType::Action`.
+    pub fn with_node_types(self, node_types: impl IntoIterator<Item = NodeType>) -> StdoutLogger {
+        self.0.set_node_types(node_types);
+        self
+    }
+}
+
+fn format_status_change(
+    timestamp: f64,
+    node: &LoggedNode,
+    previous: &NodeStatus,
+    status: &NodeStatus,
+) -> String {
+    format!(
+        "[{timestamp:.3}]: [{}] {} -> {}",
+        node.name,
+        previous.into_string_color(),
+        status.into_string_color()
+    )
+}
+
+fn print_status_change(
+    timestamp: f64,
+    node: &LoggedNode,
+    previous: &NodeStatus,
+    status: &NodeStatus,
+) {
+    println!(
+        "{}",
+        format_status_change(timestamp, node, previous, status)
+    );
+}
+
+fn write_status_changes(mut writer: impl Write + Send + 'static) -> LogFn {
+    Box::new(move |timestamp, node, previous, status| {
+        let line = format_status_change(timestamp, node, previous, status);
+
+        if let Err(e) = writeln!(writer, "{line}") {
+            log::warn!("[behaviortree_rs]: Couldn't write the log: {e}");
+        }
+    })
+}
+
+/// Writes every status change of the tree's nodes to a file, one JSON object
+/// per line:
+///
+/// ```json
+/// {"timestamp":1697461234.123456,"uid":3,"name":"MoveBase","path":"Sequence/MoveBase","type":"Action","previous":"IDLE","status":"RUNNING"}
+/// ```
+///
+/// The file is written through a buffer, which is flushed when the logger is
+/// dropped.
+#[derive(Debug)]
+pub struct FileLogger(Logger);
+
+impl FileLogger {
+    /// Creates the file at `path`, or truncates it, and starts logging the
+    /// status changes of `tree` until the logger is dropped.
+    pub fn new(tree: &mut AsyncTree, path: impl AsRef<Path>) -> io::Result<FileLogger> {
+        let log = json_lines(File::create(path)?);
+        Ok(Self(Logger::attach(tree, log)))
+    }
+
+    /// Sync version of `new()`
+    pub fn new_sync(tree: &mut SyncTree, path: impl AsRef<Path>) -> io::Result<FileLogger> {
+        let log = json_lines(File::create(path)?);
+        Ok(Self(Logger::attach_sync(tree, log)))
+    }
+
+    /// Only logs the nodes of the given types, e.g. `NodeType::Action`.
+    pub fn with_node_types(self, node_types: impl IntoIterator<Item = NodeType>) -> FileLogger {
+        self.0.set_node_types(node_types);
+        self
+    }
+}
+
+fn json_lines(file: File) -> LogFn {
+    let mut writer = BufWriter::new(file);
+
+    Box::new(move |timestamp, node, previous, status| {
+        let result = writeln!(
+            writer,
+            "{{\"timestamp\":{timestamp:.6},\"uid\":{},\"name\":{},\"path\":{},\"type\":\"{}\",\"previous\":\"{previous}\",\"status\":\"{status}\"}}",
+            node.uid,
+            json_string(&node.name),
+            json_string(&node.path),
+            node.node_type,
+        );
+
+        if let Err(e) = result {
+            log::warn!("[behaviortree_rs]: Couldn't write to the log file: {e}");
+        }
+    })
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
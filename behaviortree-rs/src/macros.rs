@@ -1,5 +1,5 @@
 pub use behaviortree_rs_derive::{
-    register_action_node, register_control_node, register_decorator_node,
+    register_action_node, register_condition_node, register_control_node, register_decorator_node,
 };
 
 /// Macro for simplifying implementation of `FromString` for any type that implements `FromStr`.
@@ -74,9 +74,23 @@ macro_rules! __define_ports {
 #[doc(inline)]
 pub use __define_ports as define_ports;
 
+/// Declares an input port, optionally with a default value and a type that's
+/// recorded in the node's manifest: `input_port!("speed", type: f64, 1.5)`.
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __input_port {
+    ($n:tt, type: $t:ty) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n);
+        port_info.set_type::<$t>();
+
+        (name, port_info)
+    }};
+    ($n:tt, type: $t:ty, $d:expr) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n, $d);
+        port_info.set_type::<$t>();
+
+        (name, port_info)
+    }};
     ($n:tt) => {{
         use $crate::basic_types::{PortDirection, PortInfo};
         let port_info = PortInfo::new(PortDirection::Input);
@@ -95,6 +109,40 @@ macro_rules! __input_port {
 #[doc(inline)]
 pub use __input_port as input_port;
 
+/// Like `input_port!`, but the value keeps its leading and trailing whitespace.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __raw_input_port {
+    ($n:tt, type: $t:ty) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n, type: $t);
+        port_info.set_raw(true);
+
+        (name, port_info)
+    }};
+    ($n:tt, type: $t:ty, $d:expr) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n, type: $t, $d);
+        port_info.set_raw(true);
+
+        (name, port_info)
+    }};
+    ($n:tt) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n);
+        port_info.set_raw(true);
+
+        (name, port_info)
+    }};
+    ($n:tt, $d:expr) => {{
+        let (name, mut port_info) = $crate::macros::input_port!($n, $d);
+        port_info.set_raw(true);
+
+        (name, port_info)
+    }};
+}
+#[doc(inline)]
+pub use __raw_input_port as raw_input_port;
+
+/// Declares an output port, optionally with a type that's recorded in the
+/// node's manifest: `output_port!("reached", type: bool)`.
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __output_port {
@@ -104,14 +152,134 @@ macro_rules! __output_port {
 
         ($n, port_info)
     }};
+    ($n:tt, type: $t:ty) => {{
+        let (name, mut port_info) = $crate::macros::output_port!($n);
+        port_info.set_type::<$t>();
+
+        (name, port_info)
+    }};
 }
 #[doc(inline)]
 pub use __output_port as output_port;
 
+/// Registers an action node that runs an async block, without writing a node
+/// type for it: `register_simple_action!(factory, "Name", |config| async move
+/// { ... })`. The block gets the node's `NodeConfig` and returns a
+/// `NodeResult`. A `PortsList` for the node can follow as a fourth argument,
+/// and a function with the signature of `Factory::register_simple_action()`
+/// can be given instead of the block. See `SimpleActionNode`.
+///
+/// ```ignore
+/// register_simple_action!(
+///     factory,
+///     "SaySomething",
+///     |config| async move {
+///         let message: String = config.get_input("message").await?;
+///         println!("{message}");
+///         Ok(NodeStatus::Success)
+///     },
+///     define_ports!(input_port!("message"))
+/// );
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __register_simple_action {
+    ($factory:expr, $name:expr, |$config:ident| async move $body:block $(,)?) => {
+        $crate::macros::register_simple_action!(
+            $factory,
+            $name,
+            |$config| async move $body,
+            $crate::basic_types::PortsList::new()
+        )
+    };
+    ($factory:expr, $name:expr, |$config:ident| async move $body:block, $ports:expr $(,)?) => {
+        $factory.register_simple_action(
+            $name,
+            |$config: &mut $crate::nodes::NodeConfig| ::std::boxed::Box::pin(async move $body),
+            $ports,
+        )
+    };
+    ($factory:expr, $name:expr, $tick_fn:expr $(,)?) => {
+        $factory.register_simple_action($name, $tick_fn, $crate::basic_types::PortsList::new())
+    };
+    ($factory:expr, $name:expr, $tick_fn:expr, $ports:expr $(,)?) => {
+        $factory.register_simple_action($name, $tick_fn, $ports)
+    };
+}
+#[doc(inline)]
+pub use __register_simple_action as register_simple_action;
+
+/// Registers a condition node that runs an async block, without writing a
+/// node type for it: `register_simple_condition!(factory, "Name", |config|
+/// async move { ... })`. The block gets the node's `NodeConfig` and returns
+/// a `Result<bool, NodeError>`; `true` is SUCCESS and `false` is FAILURE.
+/// Like `register_simple_action!`, a `PortsList` can follow, and a function
+/// can be given instead of the block. See `SimpleConditionNode`.
+///
+/// ```ignore
+/// register_simple_condition!(
+///     factory,
+///     "BatteryOK",
+///     |config| async move {
+///         let level: f64 = config.get_input("level").await?;
+///         Ok(level > 20.0)
+///     },
+///     define_ports!(input_port!("level"))
+/// );
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __register_simple_condition {
+    ($factory:expr, $name:expr, |$config:ident| async move $body:block $(,)?) => {
+        $crate::macros::register_simple_condition!(
+            $factory,
+            $name,
+            |$config| async move $body,
+            $crate::basic_types::PortsList::new()
+        )
+    };
+    ($factory:expr, $name:expr, |$config:ident| async move $body:block, $ports:expr $(,)?) => {
+        $factory.register_simple_condition(
+            $name,
+            |$config: &mut $crate::nodes::NodeConfig| ::std::boxed::Box::pin(async move $body),
+            $ports,
+        )
+    };
+    ($factory:expr, $name:expr, $tick_fn:expr $(,)?) => {
+        $factory.register_simple_condition($name, $tick_fn, $crate::basic_types::PortsList::new())
+    };
+    ($factory:expr, $name:expr, $tick_fn:expr, $ports:expr $(,)?) => {
+        $factory.register_simple_condition($name, $tick_fn, $ports)
+    };
+}
+#[doc(inline)]
+pub use __register_simple_condition as register_simple_condition;
+
+/// Registers a decorator node that maps the status of its child with a
+/// closure, without writing a node type for it:
+/// `register_simple_decorator!(factory, "Name", |status| ...)`. See
+/// `SimpleDecoratorNode`.
+///
+/// ```ignore
+/// register_simple_decorator!(factory, "FailIfSkipped", |status| match status {
+///     NodeStatus::Skipped => NodeStatus::Failure,
+///     status => status,
+/// });
+/// ```
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __register_simple_decorator {
+    ($factory:expr, $name:expr, $tick_fn:expr $(,)?) => {
+        $factory.register_simple_decorator($name, $tick_fn)
+    };
+}
+#[doc(inline)]
+pub use __register_simple_decorator as register_simple_decorator;
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __build_node_ptr {
-    ($conf:expr, $n:expr, $t:ty $(,$x:expr),* $(,)?) => {
+    ($conf:expr, $n:expr, $t:ty $(, $x:expr)* $(,)?) => {
         {
             use $crate::nodes::{GetNodeType, NodePorts, TreeNodeDefaults};
 
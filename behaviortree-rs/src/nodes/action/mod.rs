@@ -2,7 +2,23 @@ use std::{cell::RefCell, rc::Rc};
 
 use futures::future::BoxFuture;
 
-use crate::nodes::{NodeResult, TreeNodeBase};
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{NodeResult, TreeNodeBase},
+};
+
+mod placeholder;
+pub use placeholder::*;
+mod script;
+pub use script::*;
+mod set_blackboard;
+pub use set_blackboard::*;
+mod simple_action;
+pub use simple_action::*;
+mod state;
+pub use state::*;
+mod test_node;
+pub use test_node::*;
 
 pub trait ActionNodeBase: TreeNodeBase + ActionNode {}
 
@@ -14,6 +30,19 @@ pub trait SyncActionNode {}
 
 pub type ActionNodePtr = Rc<RefCell<dyn ActionNodeBase>>;
 
+/// Callbacks of a `#[bt_node(StatefulActionNode)]`. The node calls
+/// `on_start()` when it's ticked while IDLE, and `on_running()` on the
+/// following ticks while it returns RUNNING.
+///
+/// When a running node is halted, `on_halted()` is called to cancel the work
+/// in flight, e.g. the goal sent by `on_start()`, and the node is reset to
+/// IDLE. The next tick starts over with `on_start()`, so `on_running()` must
+/// not rely on state from before the halt that `on_start()` doesn't set up
+/// again. A node that isn't running is reset without calling `on_halted()`.
+///
+/// Don't implement `AsyncHalt` for a stateful action; it's generated to call
+/// `on_halted()`. With the `Sync` runtime, implement
+/// `SyncStatefulActionNode` instead.
 pub trait AsyncStatefulActionNode {
     fn on_start(&mut self) -> BoxFuture<NodeResult>;
     fn on_running(&mut self) -> BoxFuture<NodeResult>;
@@ -22,8 +51,55 @@ pub trait AsyncStatefulActionNode {
     }
 }
 
+/// Sync version of `AsyncStatefulActionNode`, for a
+/// `#[bt_node(StatefulActionNode, Sync)]`.
+///
+/// The callbacks run on the thread that ticks the tree, through
+/// `sync::block_in_place()`. On a multi-threaded Tokio runtime, a callback
+/// that blocks doesn't hold up the runtime's other tasks. On a current-thread
+/// runtime it does, including other trees and the Groot2 publisher, so
+/// callbacks must return quickly there; use `AsyncStatefulActionNode` for
+/// work that waits.
 pub trait SyncStatefulActionNode {
     fn on_start(&mut self) -> NodeResult;
     fn on_running(&mut self) -> NodeResult;
     fn on_halted(&mut self) {}
 }
+
+/// Lifecycle step of a stateful action node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatefulTransition {
+    Start,
+    Running,
+    Halted,
+}
+
+impl std::fmt::Display for StatefulTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::Start => "on_start",
+            Self::Running => "on_running",
+            Self::Halted => "on_halted",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+/// Reports a lifecycle step of the stateful action at `path`, with the status
+/// it returned if any. Called by the code generated for stateful action nodes.
+///
+/// With the `tracing` feature enabled, this logs at `trace` level with the
+/// `behaviortree_rs::lifecycle` target. Otherwise it does nothing.
+pub fn trace_transition(path: &str, transition: StatefulTransition, status: Option<&NodeStatus>) {
+    #[cfg(feature = "tracing")]
+    match status {
+        Some(status) => {
+            log::trace!(target: "behaviortree_rs::lifecycle", "{path}: {transition}() -> {status}")
+        }
+        None => log::trace!(target: "behaviortree_rs::lifecycle", "{path}: {transition}()"),
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = (path, transition, status);
+}
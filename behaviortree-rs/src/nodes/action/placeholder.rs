@@ -0,0 +1,36 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+use log::warn;
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{AsyncHalt, AsyncTick, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The PlaceholderNode stands in for a leaf node that isn't registered, when
+/// the `Factory` allows unknown nodes. It logs a warning on every tick and
+/// returns a fixed status, SUCCESS by default.
+///
+/// The node has the name of the unknown node, and ignores its attributes.
+#[bt_node(SyncActionNode)]
+pub struct PlaceholderNode {
+    result: NodeStatus,
+}
+
+impl AsyncTick for PlaceholderNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            warn!(
+                "[{}]: placeholder for an unknown node returns {}",
+                self.path(),
+                self.result
+            );
+
+            Ok(self.result.clone())
+        })
+    }
+}
+
+impl NodePorts for PlaceholderNode {}
+
+impl AsyncHalt for PlaceholderNode {}
@@ -0,0 +1,108 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{NodeStatus, PortsList},
+    expression::{self, ExpressionError, Script, Value},
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts, NodeResult},
+};
+
+/// Returns the value of the "code" port.
+async fn code(config: &NodeConfig) -> Result<String, NodeError> {
+    match config.get_input_str("code").await {
+        Some(code) => Ok(code.to_string()),
+        None => Err(NodeError::PortError(String::from("code"))),
+    }
+}
+
+fn code_error(error: ExpressionError) -> NodeError {
+    NodeError::ExpressionError(String::from("code"), error)
+}
+
+/// The ScriptNode runs the assignments in "code" and returns SUCCESS, or an
+/// error if the script can't be parsed or evaluated.
+///
+/// Each assignment evaluates an expression (see `crate::expression`) and
+/// writes the result to the blackboard, where the next assignments can read
+/// it. Plain names are entries of the node's blackboard, with the usual
+/// remapping, while `@name` is an entry of the root blackboard.
+///
+/// Example:
+///
+/// ```xml
+/// <Script code="A := 5; B := A * 2; @done := B > 8" />
+/// ```
+#[bt_node(SyncActionNode)]
+pub struct ScriptNode {}
+
+impl AsyncTick for ScriptNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let code = code(&self.config).await?;
+
+            Script::parse(&code)
+                .map_err(code_error)?
+                .execute(self.config.blackboard())
+                .await
+                .map_err(code_error)?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for ScriptNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("code"))
+    }
+
+    fn written_keys(&self) -> Vec<String> {
+        let code = self.config.input_ports.get("code");
+        match code.map(|code| Script::parse(code)) {
+            Some(Ok(script)) => script.targets().into_iter().map(String::from).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl AsyncHalt for ScriptNode {}
+
+/// The ScriptConditionNode evaluates the expression in "code" and returns
+/// SUCCESS if it's `true` and FAILURE if it's `false`. Any other value, or an
+/// expression that can't be evaluated, is an error.
+///
+/// Variables are read like in the `Script` node, see `crate::expression`.
+///
+/// Example:
+///
+/// ```xml
+/// <ScriptCondition code="battery > 20 || @mode == 'manual'" />
+/// ```
+#[bt_node(ConditionNode)]
+pub struct ScriptConditionNode {}
+
+impl AsyncTick for ScriptConditionNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let code = code(&self.config).await?;
+
+            match expression::evaluate(&code, self.config.blackboard())
+                .await
+                .map_err(code_error)?
+            {
+                Value::Bool(true) => Ok(NodeStatus::Success),
+                Value::Bool(false) => Ok(NodeStatus::Failure),
+                value => Err(code_error(ExpressionError::expected_bool(&value))),
+            }
+        })
+    }
+}
+
+impl NodePorts for ScriptConditionNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("code"))
+    }
+}
+
+impl AsyncHalt for ScriptConditionNode {}
@@ -0,0 +1,143 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{FromString, NodeStatus, PortsList},
+    blackboard::{Blackboard, BlackboardString},
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, NodeError, NodePorts, NodeResult},
+};
+
+/// The SetBlackboardNode writes "value" to the blackboard entry "output_key"
+/// and returns SUCCESS.
+///
+/// The value is stored with the type of the existing entry, so it doesn't
+/// turn an integer entry into a string. Supported are the numeric types,
+/// `bool`, `String` and `NodeStatus`. A new key (or an entry of another type)
+/// gets a `String`, unless the optional "type" port selects one of `int`
+/// (`i64`), `uint` (`u64`), `float` (`f64`), `bool` or `string`.
+///
+/// Example:
+///
+/// ```xml
+/// <SetBlackboard output_key="count" value="5" />
+/// <SetBlackboard output_key="speed" value="1.5" type="float" />
+/// ```
+#[bt_node(SyncActionNode)]
+pub struct SetBlackboardNode {}
+
+impl AsyncTick for SetBlackboardNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            // The port names the entry, so it isn't resolved. Also accept
+            // `output_key="{key}"`
+            let key = match self
+                .config
+                .input_ports
+                .get("output_key")
+                .map(|key| key.trim())
+            {
+                Some(key) => key.strip_bb_pointer().unwrap_or_else(|| key.to_string()),
+                None => return Err(NodeError::PortError(String::from("output_key"))),
+            };
+            let value: String = self.config.get_input("value").await?;
+            let value_type = self
+                .config
+                .get_input_str("type")
+                .await
+                .map(|t| t.to_string());
+
+            let blackboard = self.config.blackboard_mut();
+
+            match value_type.as_deref() {
+                Some(value_type) => set_as_type(blackboard, &key, value, value_type).await?,
+                None => set_as_existing_type(blackboard, &key, value).await?,
+            }
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for SetBlackboardNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("value"),
+            input_port!("output_key"),
+            input_port!("type")
+        )
+    }
+
+    fn written_keys(&self) -> Vec<String> {
+        self.config
+            .input_ports
+            .get("output_key")
+            .map(|key| key.trim())
+            .map(|key| key.strip_bb_pointer().unwrap_or_else(|| key.to_string()))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl AsyncHalt for SetBlackboardNode {}
+
+async fn set_parsed<T>(blackboard: &mut Blackboard, key: &str, value: &str) -> Result<(), NodeError>
+where
+    T: FromString + Send + 'static,
+{
+    match <T as FromString>::from_string(value) {
+        Ok(value) => {
+            blackboard.set(key, value).await;
+            Ok(())
+        }
+        Err(_) => Err(NodeError::parse_error::<T>("value", value)),
+    }
+}
+
+async fn set_as_type(
+    blackboard: &mut Blackboard,
+    key: &str,
+    value: String,
+    value_type: &str,
+) -> Result<(), NodeError> {
+    match value_type {
+        "int" => set_parsed::<i64>(blackboard, key, &value).await,
+        "uint" => set_parsed::<u64>(blackboard, key, &value).await,
+        "float" => set_parsed::<f64>(blackboard, key, &value).await,
+        "bool" => set_parsed::<bool>(blackboard, key, &value).await,
+        "string" => {
+            blackboard.set(key, value).await;
+            Ok(())
+        }
+        _ => Err(NodeError::InvalidPortValue(
+            String::from("type"),
+            String::from(value_type),
+            String::from("int, uint, float, bool or string"),
+        )),
+    }
+}
+
+/// Stores `value` with the type of the entry at `key`, or as a `String`.
+async fn set_as_existing_type(
+    blackboard: &mut Blackboard,
+    key: &str,
+    value: String,
+) -> Result<(), NodeError> {
+    macro_rules! try_types {
+        ($($t:ty),*) => {
+            $(
+                if blackboard.get_exact::<$t>(key).await.is_some() {
+                    return set_parsed::<$t>(blackboard, key, &value).await;
+                }
+            )*
+        };
+    }
+
+    try_types!(
+        u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, NodeStatus
+    );
+
+    blackboard.set(key, value).await;
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{NodeStatus, PortsList},
+    nodes::{AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts, NodeResult},
+};
+
+type SimpleActionFnDyn =
+    dyn for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, NodeResult> + Send + Sync;
+
+/// Function ticked by a `SimpleActionNode`, shared by all the nodes
+/// registered with it.
+#[derive(Clone)]
+pub struct SimpleActionFn(Arc<SimpleActionFnDyn>);
+
+impl SimpleActionFn {
+    pub fn new<F>(tick_fn: F) -> SimpleActionFn
+    where
+        F: for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, NodeResult> + Send + Sync + 'static,
+    {
+        Self(Arc::new(tick_fn))
+    }
+}
+
+impl std::fmt::Debug for SimpleActionFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpleActionFn").finish_non_exhaustive()
+    }
+}
+
+/// The SimpleActionNode calls a function with its `NodeConfig` and returns
+/// the status the function returns, like BehaviorTree.CPP's
+/// `SimpleActionNode`. It saves writing a node type for a trivial action.
+///
+/// It's registered with `Factory::register_simple_action()` or the
+/// `register_simple_action!` macro, with the ports the function uses. Like
+/// other synchronous actions, the function must not return RUNNING.
+#[bt_node(SyncActionNode)]
+pub struct SimpleActionNode {
+    tick_fn: SimpleActionFn,
+    ports: PortsList,
+}
+
+impl AsyncTick for SimpleActionNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { (self.tick_fn.0)(&mut self.config).await })
+    }
+}
+
+impl NodePorts for SimpleActionNode {
+    fn provided_ports(&self) -> PortsList {
+        self.ports.clone()
+    }
+}
+
+impl AsyncHalt for SimpleActionNode {}
+
+type SimpleConditionFnDyn =
+    dyn for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, Result<bool, NodeError>> + Send + Sync;
+
+/// Function ticked by a `SimpleConditionNode`, shared by all the nodes
+/// registered with it.
+#[derive(Clone)]
+pub struct SimpleConditionFn(Arc<SimpleConditionFnDyn>);
+
+impl SimpleConditionFn {
+    pub fn new<F>(tick_fn: F) -> SimpleConditionFn
+    where
+        F: for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, Result<bool, NodeError>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self(Arc::new(tick_fn))
+    }
+}
+
+impl std::fmt::Debug for SimpleConditionFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpleConditionFn").finish_non_exhaustive()
+    }
+}
+
+/// The SimpleConditionNode calls a function with its `NodeConfig` and
+/// returns SUCCESS if the function returns `true`, FAILURE if it returns
+/// `false`, like BehaviorTree.CPP's `SimpleConditionNode`.
+///
+/// It's registered as a condition with `Factory::register_simple_condition()`
+/// or the `register_simple_condition!` macro, with the ports the function
+/// uses.
+#[bt_node(ConditionNode)]
+pub struct SimpleConditionNode {
+    tick_fn: SimpleConditionFn,
+    ports: PortsList,
+}
+
+impl AsyncTick for SimpleConditionNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            match (self.tick_fn.0)(&mut self.config).await? {
+                true => Ok(NodeStatus::Success),
+                false => Ok(NodeStatus::Failure),
+            }
+        })
+    }
+}
+
+impl NodePorts for SimpleConditionNode {
+    fn provided_ports(&self) -> PortsList {
+        self.ports.clone()
+    }
+}
+
+impl AsyncHalt for SimpleConditionNode {}
@@ -0,0 +1,151 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{get_remapped_key, NodeStatus, PortsList},
+    macros::{define_ports, input_port},
+    nodes::{control::RegisteredEnum, AsyncHalt, AsyncTick, NodeError, NodePorts, NodeResult},
+};
+
+fn registered<'a>(
+    registered: &'a Option<RegisteredEnum>,
+    node: &str,
+) -> Result<&'a RegisteredEnum, NodeError> {
+    registered.as_ref().ok_or_else(|| {
+        NodeError::NodeStructureError(format!(
+            "{node} must be built by a Factory that knows its enum."
+        ))
+    })
+}
+
+/// The SetStateNode writes the variant "state" of the enum "enum" to the
+/// blackboard entry "variable", `{state}` by default, and returns SUCCESS.
+///
+/// Together with `InState` and `Match`, it models a state machine whose state
+/// is a single blackboard entry: `SetState` makes the transitions, `InState`
+/// guards nodes that only apply in some states, and `Match` dispatches to the
+/// subtree of the current state.
+///
+/// The entry holds the enum's type, as registered with
+/// `Factory::register_enum()`. A literal "state" is checked against the enum
+/// when the tree is built, a blackboard pointer when ticking.
+///
+/// Example:
+///
+/// ```xml
+/// <SetState enum="RobotState" state="Moving" />
+/// ```
+#[bt_node(SyncActionNode)]
+pub struct SetStateNode {
+    /// Set up by the factory when the tree is built
+    #[bt(default)]
+    pub(crate) registered: Option<RegisteredEnum>,
+}
+
+impl AsyncTick for SetStateNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let registered = registered(&self.registered, "SetStateNode")?;
+            let key = match self.config.input_ports.get("variable") {
+                Some(variable) => get_remapped_key("variable", variable.trim()),
+                None => None,
+            }
+            .ok_or_else(|| NodeError::PortError(String::from("variable")))?;
+
+            let state: String = self.config.get_input("state").await?;
+            let index = registered.index_of(state.trim()).ok_or_else(|| {
+                let enum_name = self.config.input_ports.get("enum").cloned();
+                NodeError::InvalidPortValue(
+                    String::from("state"),
+                    state.clone(),
+                    enum_name.unwrap_or_default(),
+                )
+            })?;
+
+            (registered.write)(self.config.blackboard().clone(), key, index).await;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for SetStateNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("state"),
+            input_port!("enum"),
+            input_port!("variable", "{state}")
+        )
+    }
+
+    fn written_keys(&self) -> Vec<String> {
+        self.config
+            .input_ports
+            .get("variable")
+            .and_then(|variable| get_remapped_key("variable", variable.trim()))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl AsyncHalt for SetStateNode {}
+
+/// The InStateNode returns SUCCESS if the enum in the blackboard entry
+/// "variable", `{state}` by default, is one of the variants in "state", and
+/// FAILURE otherwise. Several variants are separated by `;`.
+///
+/// Like the condition of a state machine's transition, it guards the nodes
+/// that only apply in some states. See `SetState` for the whole pattern.
+///
+/// Example:
+///
+/// ```xml
+/// <Sequence>
+///     <InState enum="RobotState" state="Idle;Charging" />
+///     <SetState enum="RobotState" state="Moving" />
+/// </Sequence>
+/// ```
+#[bt_node(ConditionNode)]
+pub struct InStateNode {
+    /// Set up by the factory when the tree is built
+    #[bt(default)]
+    pub(crate) registered: Option<RegisteredEnum>,
+}
+
+impl AsyncTick for InStateNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let registered = registered(&self.registered, "InStateNode")?;
+            let variable = match self.config.input_ports.get("variable") {
+                Some(variable) => variable.trim().to_string(),
+                None => return Err(NodeError::PortError(String::from("variable"))),
+            };
+
+            let current = match get_remapped_key("variable", &variable) {
+                Some(key) => (registered.read)(self.config.blackboard().clone(), key.clone())
+                    .await
+                    .ok_or(NodeError::BlackboardError(key))?,
+                None => variable,
+            };
+
+            let states: String = self.config.get_input("state").await?;
+
+            match states.split(';').any(|state| state.trim() == current) {
+                true => Ok(NodeStatus::Success),
+                false => Ok(NodeStatus::Failure),
+            }
+        })
+    }
+}
+
+impl NodePorts for InStateNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("state"),
+            input_port!("enum"),
+            input_port!("variable", "{state}")
+        )
+    }
+}
+
+impl AsyncHalt for InStateNode {}
@@ -0,0 +1,175 @@
+use std::{pin::Pin, time::Duration};
+
+use behaviortree_rs_derive::bt_node;
+use futures::{future::BoxFuture, FutureExt};
+use tokio::time::Sleep;
+
+use crate::{
+    basic_types::{NodeStatus, PortsList},
+    expression::Script,
+    macros::{define_ports, input_port},
+    nodes::{AsyncStatefulActionNode, AsyncTick, NodeError, NodePorts, NodeResult},
+};
+
+/// Configures the behavior of a `TestNode`.
+///
+/// The default node completes on its first tick with SUCCESS.
+#[derive(Clone, Debug)]
+pub struct TestNodeConfig {
+    /// Status returned by each completion, in order. After the last one,
+    /// the last status is returned again. SUCCESS if empty.
+    pub statuses: Vec<NodeStatus>,
+    /// Ticks that return RUNNING before the node completes
+    pub running_ticks: usize,
+    /// Time that must pass after the first tick before the node completes.
+    /// It's checked on every tick, the node returns RUNNING until then.
+    /// A delay needs a Tokio runtime with the time driver enabled.
+    pub async_delay: Duration,
+    /// Script run when the node completes with SUCCESS
+    pub success_script: Option<String>,
+    /// Script run when the node completes with FAILURE
+    pub failure_script: Option<String>,
+}
+
+impl Default for TestNodeConfig {
+    fn default() -> Self {
+        Self {
+            statuses: vec![NodeStatus::Success],
+            running_ticks: 0,
+            async_delay: Duration::ZERO,
+            success_script: None,
+            failure_script: None,
+        }
+    }
+}
+
+/// The TestNode stands in for another node, to test a tree without the side
+/// effects of its real nodes. It does nothing but return the statuses from
+/// its `TestNodeConfig`, and run a script when it completes.
+///
+/// In XML, the ports set the config: the node waits "async_delay"
+/// milliseconds (0 by default) and returns "return_status" (SUCCESS by
+/// default). "success_script" or "failure_script" runs when it completes
+/// with that status. While waiting, the node returns RUNNING without
+/// blocking the tree, and halting it cancels the wait. The wait is a
+/// `tokio::time::sleep()`, so a delay needs a Tokio runtime with the time
+/// driver enabled.
+///
+/// `Factory::add_substitution_rule()` creates TestNodes from a config
+/// instead.
+///
+/// Example:
+///
+/// ```xml
+/// <TestNode return_status="FAILURE" async_delay="100" failure_script="tries := tries + 1" />
+/// ```
+#[bt_node(StatefulActionNode)]
+pub struct TestNode {
+    test_config: TestNodeConfig,
+    /// Set on the first tick if there's a delay, dropped when halted
+    #[bt(default)]
+    delay: Option<Pin<Box<Sleep>>>,
+    #[bt(default)]
+    ticks: usize,
+    #[bt(default)]
+    completions: usize,
+}
+
+impl TestNode {
+    /// Applies the ports that are set to the config. A node created by a
+    /// substitution rule has none of them, only the ports of the node it
+    /// replaces.
+    async fn read_ports(&mut self) -> Result<(), NodeError> {
+        if self.config.input_ports.contains_key("return_status") {
+            self.test_config.statuses = vec![self.config.get_input("return_status").await?];
+        }
+        if self.config.input_ports.contains_key("async_delay") {
+            let delay: u64 = self.config.get_input("async_delay").await?;
+            self.test_config.async_delay = Duration::from_millis(delay);
+        }
+        if self.config.input_ports.contains_key("success_script") {
+            self.test_config.success_script = Some(self.config.get_input("success_script").await?);
+        }
+        if self.config.input_ports.contains_key("failure_script") {
+            self.test_config.failure_script = Some(self.config.get_input("failure_script").await?);
+        }
+
+        Ok(())
+    }
+
+    /// Returns RUNNING until the configured ticks and delay have passed,
+    /// then the next status, after running its script.
+    async fn step(&mut self) -> NodeResult {
+        // Polled once per tick, so waiting doesn't block the tree
+        let waiting = match self.delay.as_mut() {
+            Some(delay) => delay.as_mut().now_or_never().is_none(),
+            None => false,
+        };
+        if self.ticks < self.test_config.running_ticks || waiting {
+            self.ticks += 1;
+            return Ok(NodeStatus::Running);
+        }
+        self.delay = None;
+
+        let statuses = &self.test_config.statuses;
+        let status = statuses
+            .get(self.completions)
+            .or(statuses.last())
+            .cloned()
+            .unwrap_or(NodeStatus::Success);
+        self.completions += 1;
+
+        let (port, script) = match status {
+            NodeStatus::Success => ("success_script", &self.test_config.success_script),
+            NodeStatus::Failure => ("failure_script", &self.test_config.failure_script),
+            _ => return Ok(status),
+        };
+        if let Some(code) = script {
+            let script_error = |e| NodeError::ExpressionError(String::from(port), e);
+
+            Script::parse(code)
+                .map_err(script_error)?
+                .execute(self.config.blackboard())
+                .await
+                .map_err(script_error)?;
+        }
+
+        Ok(status)
+    }
+}
+
+impl AsyncStatefulActionNode for TestNode {
+    fn on_start(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.read_ports().await?;
+            let delay = self.test_config.async_delay;
+            self.delay = (!delay.is_zero()).then(|| Box::pin(tokio::time::sleep(delay)));
+            self.ticks = 0;
+
+            self.step().await
+        })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { self.step().await })
+    }
+
+    fn on_halted(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.delay = None;
+            self.ticks = 0;
+        })
+    }
+}
+
+impl NodePorts for TestNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            // No defaults, so the config is kept unless a port is set
+            input_port!("return_status", type: NodeStatus),
+            input_port!("async_delay", type: u64),
+            input_port!("success_script"),
+            input_port!("failure_script")
+        )
+    }
+}
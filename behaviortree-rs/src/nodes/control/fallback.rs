@@ -15,6 +15,8 @@ use crate::{
 /// - If a child returns RUNNING, this node returns RUNNING.
 ///
 /// - If a child returns SUCCESS, stop the loop and return SUCCESS.
+///
+/// - If all the children return SKIPPED, this node returns SKIPPED.
 // #[derive(TreeNodeDefaults, ControlNode, Debug, Clone)]
 #[bt_node(ControlNode)]
 pub struct FallbackNode {
@@ -58,7 +60,7 @@ impl AsyncTick for FallbackNode {
                     }
                     NodeStatus::Idle => {
                         return Err(NodeError::StatusError(
-                            "Name here".to_string(),
+                            "FallbackNode".to_string(),
                             "Idle".to_string(),
                         ));
                     }
@@ -1,10 +1,11 @@
 use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
-use log::warn;
 
 use crate::{
     basic_types::NodeStatus,
-    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+    nodes::{
+        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
 };
 
 /// IfThenElseNode must have exactly 2 or 3 children. This node is NOT reactive.
@@ -19,6 +20,10 @@ use crate::{
 /// statement returns FAILURE.
 ///
 /// This is equivalent to add AlwaysFailure as 3rd child.
+///
+/// While the statement or the chosen branch returns RUNNING, this node returns
+/// RUNNING. The statement isn't ticked again until the branch has completed or
+/// the node was halted. If the statement is skipped, the whole node is skipped.
 #[bt_node(ControlNode)]
 pub struct IfThenElseNode {
     #[bt(default = "0")]
@@ -47,16 +52,20 @@ impl AsyncTick for IfThenElseNode {
                         if children_count == 3 {
                             self.child_idx = 2;
                         } else {
+                            self.reset_children().await;
                             return Ok(NodeStatus::Failure);
                         }
                     }
+                    NodeStatus::Skipped => {
+                        self.reset_children().await;
+                        return Ok(NodeStatus::Skipped);
+                    }
                     NodeStatus::Idle => {
                         return Err(NodeError::StatusError(
-                            "Node name here".to_string(),
+                            self.path().clone(),
                             "Idle".to_string(),
                         ))
                     }
-                    _ => warn!("Condition node of IfThenElseNode returned Skipped"),
                 }
             }
 
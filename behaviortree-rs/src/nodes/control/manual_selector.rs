@@ -0,0 +1,137 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{NodeStatus, PortsList},
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+};
+
+/// The ManualSelectorNode lets a person choose which child to tick, which is
+/// useful while developing a large tree.
+///
+/// With the `cli` feature, it lists its children on stdout and reads the
+/// number of the child to tick from stdin. An empty line makes it return
+/// FAILURE. Without the feature, it returns FAILURE unless a child was
+/// chosen with `select()`.
+///
+/// The chosen child is ticked until it completes, and this node returns its
+/// status. If "repeat_last_selection" is true, the same child is chosen
+/// again the next time, without asking.
+///
+/// Example:
+///
+/// ```xml
+/// <ManualSelector repeat_last_selection="false">
+///     <GoToKitchen />
+///     <GoToBedroom />
+/// </ManualSelector>
+/// ```
+#[bt_node(ControlNode)]
+pub struct ManualSelectorNode {
+    /// Set by `select()` for the next time a child is chosen
+    #[bt(default)]
+    pending_selection: Option<usize>,
+    #[bt(default)]
+    last_selection: Option<usize>,
+    /// Child that returned RUNNING in the previous tick
+    #[bt(default)]
+    running_child: Option<usize>,
+}
+
+impl ManualSelectorNode {
+    /// Chooses the child at `index` to tick the next time a child is chosen,
+    /// instead of asking on stdin. Takes effect once the running child, if
+    /// any, has completed.
+    pub fn select(&mut self, index: usize) {
+        self.pending_selection = Some(index);
+    }
+
+    /// Asks on stdin which child to tick. Returns `None` if no child was
+    /// chosen.
+    #[cfg(feature = "cli")]
+    fn prompt(&self) -> Option<usize> {
+        use std::io::{BufRead, Write};
+
+        println!("Children of {}:", self.config.path);
+        for (index, child) in self.children.iter().enumerate() {
+            println!("  {index}: {}", child.name());
+        }
+
+        let mut stdin = std::io::stdin().lock();
+        loop {
+            print!("Child to tick (empty for FAILURE): ");
+            let _ = std::io::stdout().flush();
+
+            let mut line = String::new();
+            if stdin.read_line(&mut line).unwrap_or(0) == 0 || line.trim().is_empty() {
+                return None;
+            }
+            match line.trim().parse() {
+                Ok(index) if index < self.children.len() => return Some(index),
+                _ => println!("Enter a number from 0 to {}", self.children.len() - 1),
+            }
+        }
+    }
+
+    #[cfg(not(feature = "cli"))]
+    fn prompt(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl AsyncTick for ManualSelectorNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let index = match self.running_child {
+                Some(running) => running,
+                None => {
+                    let repeat: bool = self.config.get_input("repeat_last_selection").await?;
+                    let repeated = self.last_selection.filter(|_| repeat);
+
+                    match self.pending_selection.take().or(repeated) {
+                        Some(index) => index,
+                        None => match self.prompt() {
+                            Some(index) => index,
+                            None => return Ok(NodeStatus::Failure),
+                        },
+                    }
+                }
+            };
+
+            if index >= self.children.len() {
+                return Err(NodeError::IndexError);
+            }
+            self.last_selection = Some(index);
+
+            self.status = NodeStatus::Running;
+
+            match self.children[index].execute_tick().await? {
+                NodeStatus::Running => {
+                    self.running_child = Some(index);
+                    Ok(NodeStatus::Running)
+                }
+                status => {
+                    self.running_child = None;
+                    self.reset_children().await;
+                    Ok(status)
+                }
+            }
+        })
+    }
+}
+
+impl NodePorts for ManualSelectorNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("repeat_last_selection", false))
+    }
+}
+
+impl AsyncHalt for ManualSelectorNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.running_child = None;
+            self.reset_children().await;
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use std::{any::Any, fmt::Display, sync::Arc};
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{get_remapped_key, NodeStatus, PortsList},
+    blackboard::Blackboard,
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+};
+
+/// Reads the blackboard entry at the key as the name of an enum variant.
+pub(crate) type EnumReadFn = fn(Blackboard, String) -> BoxFuture<'static, Option<String>>;
+
+/// Writes the variant with the index to the blackboard entry at the key.
+pub(crate) type EnumWriteFn =
+    Arc<dyn Fn(Blackboard, String, usize) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// An enum registered with `Factory::register_enum()`.
+#[derive(Clone)]
+pub(crate) struct RegisteredEnum {
+    pub(crate) variants: Vec<String>,
+    pub(crate) read: EnumReadFn,
+    pub(crate) write: EnumWriteFn,
+}
+
+impl std::fmt::Debug for RegisteredEnum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredEnum")
+            .field("variants", &self.variants)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RegisteredEnum {
+    pub(crate) fn index_of(&self, variant: &str) -> Option<usize> {
+        self.variants.iter().position(|v| v == variant)
+    }
+}
+
+/// Reads an entry stored as `T`, or as a `String` holding the variant name.
+pub(crate) fn read_enum<T>(
+    mut blackboard: Blackboard,
+    key: String,
+) -> BoxFuture<'static, Option<String>>
+where
+    T: Any + Clone + Display + Send,
+{
+    Box::pin(async move {
+        match blackboard.get_exact::<T>(&key).await {
+            Some(value) => Some(value.to_string()),
+            None => blackboard.get::<String>(&key).await,
+        }
+    })
+}
+
+/// Returns a writer that stores the values of `variants` as `T`.
+pub(crate) fn enum_writer<T>(variants: Vec<T>) -> EnumWriteFn
+where
+    T: Any + Clone + Send + Sync,
+{
+    Arc::new(move |mut blackboard, key, index| {
+        let value = variants[index].clone();
+        Box::pin(async move { blackboard.set(key, value).await })
+    })
+}
+
+/// The MatchNode ticks the child for the current value of the enum in
+/// "variable", like a `switch` statement.
+///
+/// "enum" is the name of an enum registered with `Factory::register_enum()`
+/// and "cases" lists variant names separated by `;`. The n-th child belongs
+/// to the n-th case. An optional extra child at the end is the default, which
+/// is ticked for any other value; without it, the node returns FAILURE then.
+///
+/// The cases, and "variable" if it isn't a blackboard pointer, are checked
+/// against the enum when the tree is built, so a misspelled case is an error.
+///
+/// If a child is RUNNING and the value changes, that child is halted before
+/// the child for the new value is ticked.
+///
+/// Example:
+///
+/// ```xml
+/// <Match variable="{state}" enum="RobotState" cases="Idle;Moving">
+///     <Wait />
+///     <FollowPath />
+///     <Recover />
+/// </Match>
+/// ```
+#[bt_node(ControlNode)]
+pub struct MatchNode {
+    /// Set up by the factory when the tree is built
+    #[bt(default)]
+    pub(crate) cases: Vec<String>,
+    #[bt(default)]
+    pub(crate) read: Option<EnumReadFn>,
+    /// Child that returned RUNNING in the previous tick
+    #[bt(default)]
+    running_child: Option<usize>,
+}
+
+impl AsyncTick for MatchNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let read = self.read.ok_or_else(|| {
+                NodeError::NodeStructureError(String::from(
+                    "MatchNode must be built by a Factory that knows its enum.",
+                ))
+            })?;
+            let variable = match self.config.input_ports.get("variable") {
+                Some(variable) => variable.trim().to_string(),
+                None => return Err(NodeError::PortError(String::from("variable"))),
+            };
+
+            let value = match get_remapped_key("variable", &variable) {
+                Some(key) => read(self.config.blackboard().clone(), key.clone())
+                    .await
+                    .ok_or(NodeError::BlackboardError(key))?,
+                None => variable,
+            };
+
+            let index = match self.cases.iter().position(|case| *case == value) {
+                Some(index) => Some(index),
+                None if self.children.len() > self.cases.len() => Some(self.cases.len()),
+                None => None,
+            };
+
+            // The value changed while a child was running
+            if let Some(running) = self.running_child {
+                if Some(running) != index {
+                    self.halt_child(running).await?;
+                    self.running_child = None;
+                }
+            }
+
+            let Some(index) = index else {
+                return Ok(NodeStatus::Failure);
+            };
+
+            self.status = NodeStatus::Running;
+
+            match self.children[index].execute_tick().await? {
+                NodeStatus::Running => {
+                    self.running_child = Some(index);
+                    Ok(NodeStatus::Running)
+                }
+                status => {
+                    self.running_child = None;
+                    self.reset_children().await;
+                    Ok(status)
+                }
+            }
+        })
+    }
+}
+
+impl NodePorts for MatchNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("variable"),
+            input_port!("enum"),
+            input_port!("cases")
+        )
+    }
+}
+
+impl AsyncHalt for MatchNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.running_child = None;
+            self.reset_children().await;
+        })
+    }
+}
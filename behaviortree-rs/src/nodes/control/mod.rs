@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::nodes::{NodeError, TreeNodeBase, TreeNodePtr};
+use crate::nodes::{NodeError, NodeResult, TreeNodeBase, TreeNodePtr};
 
 mod if_then_else;
 use futures::future::BoxFuture;
@@ -21,6 +21,12 @@ mod reactive_sequence;
 pub use reactive_sequence::*;
 mod while_do_else;
 pub use while_do_else::*;
+mod manual_selector;
+pub use manual_selector::*;
+mod match_node;
+pub use match_node::*;
+mod switch;
+pub use switch::*;
 
 pub trait ControlNodeBase: TreeNodeBase + ControlNode {}
 
@@ -31,10 +37,15 @@ pub trait ControlNode: TreeNodeBase {
     fn add_child(&mut self, child: TreeNodePtr);
     /// Return reference to `Vec` of children nodes
     fn children(&self) -> &Vec<TreeNodePtr>;
-    /// Call `halt()` on child at index
+    /// Call `halt()` on child at index if it's running, and reset its status.
+    /// Completes only once the child's `halt()` has completed.
     fn halt_child(&mut self, index: usize) -> BoxFuture<Result<(), NodeError>>;
-    /// Halt all children at and after index
+    /// Halt all children at and after index, one after the other
     fn halt_children(&mut self, start: usize) -> BoxFuture<Result<(), NodeError>>;
-    /// Reset status of all child nodes
+    /// Halt all running children and reset the status of all child nodes
     fn reset_children(&mut self) -> BoxFuture<()>;
+    /// Tick the child at index and return its status. Like a decorator's
+    /// `tick_child()`, a child that completed must be reset (`halt_child()`)
+    /// before it's ticked again.
+    fn tick_child(&mut self, index: usize) -> BoxFuture<NodeResult>;
 }
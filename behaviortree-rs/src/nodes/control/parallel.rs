@@ -1,14 +1,12 @@
-use std::collections::HashSet;
-
 use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
 
 use crate::{
-    basic_types::NodeStatus,
-    macros::{define_ports, input_port},
-    nodes::{
-        AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    basic_types::{
+        aggregate_parallel, aggregate_parallel_required, resolve_parallel_threshold, NodeStatus,
     },
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
 };
 
 /// The ParallelNode execute all its children
@@ -19,7 +17,10 @@ use crate::{
 /// multiple children RUNNING at the same time.
 ///
 /// The Node is completed either when the THRESHOLD_SUCCESS
-/// or THRESHOLD_FAILURE number is reached (both configured using ports).
+/// or THRESHOLD_FAILURE number is reached, configured with the ports
+/// "success_count" (all children by default) and "failure_count" (1 by
+/// default). It also fails once too few children are left to reach
+/// THRESHOLD_SUCCESS.
 ///
 /// If any of the thresholds is reached, and other children are still running,
 /// they will be halted.
@@ -28,6 +29,14 @@ use crate::{
 /// https://www.i2tutorials.com/what-are-negative-indexes-and-why-are-they-used/
 ///
 /// Therefore -1 is equivalent to the number of children.
+///
+/// Alternatively, the `required_children` port takes a `;` separated list of
+/// child indexes, e.g. `"0;1"`. The node then fails as soon as one of those
+/// children fails and succeeds once all of them succeeded, ignoring the
+/// other children's results. The thresholds are not used in that case.
+///
+/// It can be written without children (`<Parallel />`), in which case it
+/// returns SUCCESS.
 #[bt_node(ControlNode)]
 pub struct ParallelNode {
     #[bt(default = "-1")]
@@ -35,102 +44,92 @@ pub struct ParallelNode {
     #[bt(default = "-1")]
     failure_threshold: i32,
     #[bt(default)]
-    completed_list: HashSet<usize>,
-    #[bt(default = "0")]
-    success_count: usize,
-    #[bt(default = "0")]
-    failure_count: usize,
+    required_children: Option<Vec<usize>>,
+    #[bt(default)]
+    child_statuses: Vec<NodeStatus>,
 }
 
 impl ParallelNode {
-    fn success_threshold(&self) -> usize {
-        if self.success_threshold < 0 {
-            ((self.children.len() as i32) + self.success_threshold + 1).max(0) as usize
-        } else {
-            self.success_threshold as usize
-        }
-    }
-
-    fn failure_threshold(&self) -> usize {
-        if self.failure_threshold < 0 {
-            ((self.children.len() as i32) + self.failure_threshold + 1).max(0) as usize
-        } else {
-            self.failure_threshold as usize
-        }
-    }
-
     fn clear(&mut self) {
-        self.completed_list.clear();
-        self.success_count = 0;
-        self.failure_count = 0;
+        self.child_statuses.clear();
     }
 }
 
 impl AsyncTick for ParallelNode {
     fn tick(&mut self) -> BoxFuture<NodeResult> {
         Box::pin(async move {
-            self.success_threshold = self.config_mut().get_input("success_count").await.unwrap();
-            self.failure_threshold = self.config_mut().get_input("failure_count").await.unwrap();
+            self.success_threshold = self.config.get_input("success_count").await?;
+            self.failure_threshold = self.config.get_input("failure_count").await?;
+            self.required_children = self.config.get_input_opt("required_children").await?;
 
             let children_count = self.children.len();
 
-            if children_count < self.success_threshold() {
+            // Only possible if the node was allowed to have no children
+            if children_count == 0 {
+                return Ok(NodeStatus::Success);
+            }
+
+            if children_count < resolve_parallel_threshold(self.success_threshold, children_count) {
                 return Err(NodeError::NodeStructureError(
                     "Number of children is less than the threshold. Can never succeed.".to_string(),
                 ));
             }
 
-            if children_count < self.failure_threshold() {
+            if children_count < resolve_parallel_threshold(self.failure_threshold, children_count) {
                 return Err(NodeError::NodeStructureError(
                     "Number of children is less than the threshold. Can never fail.".to_string(),
                 ));
             }
 
-            let mut skipped_count = 0;
-
-            for i in 0..children_count {
-                if !self.completed_list.contains(&i) {
-                    let child = &mut self.children[i];
-                    match child.execute_tick().await? {
-                        NodeStatus::Skipped => skipped_count += 1,
-                        NodeStatus::Success => {
-                            self.completed_list.insert(i);
-                            self.success_count += 1;
-                        }
-                        NodeStatus::Failure => {
-                            self.completed_list.insert(i);
-                            self.failure_count += 1;
-                        }
-                        NodeStatus::Running => {}
-                        // Throw error, should never happen
-                        NodeStatus::Idle => {}
-                    }
+            if let Some(required) = &self.required_children {
+                if let Some(index) = required.iter().find(|i| **i >= children_count) {
+                    return Err(NodeError::NodeStructureError(format!(
+                        "Required child index {index} is out of range for {children_count} children."
+                    )));
                 }
+            }
 
-                let required_success_count = self.success_threshold();
+            self.status = NodeStatus::Running;
 
-                // Check if success condition has been met
-                if self.success_count >= required_success_count
-                    || (self.success_threshold < 0
-                        && (self.success_count + skipped_count) >= required_success_count)
-                {
-                    self.clear();
-                    self.reset_children().await;
-                    return Ok(NodeStatus::Success);
+            if self.child_statuses.len() != children_count {
+                self.child_statuses = vec![NodeStatus::Idle; children_count];
+            }
+
+            // Skipped children are ticked again, so they only count for this tick
+            for status in self.child_statuses.iter_mut() {
+                if *status == NodeStatus::Skipped {
+                    *status = NodeStatus::Idle;
+                }
+            }
+
+            for i in 0..children_count {
+                if !self.child_statuses[i].is_completed() {
+                    self.child_statuses[i] = self.children[i].execute_tick().await?;
                 }
 
-                if (children_count - self.failure_count) < required_success_count
-                    || self.failure_count == self.failure_threshold()
-                {
+                let status = match &self.required_children {
+                    Some(required) => aggregate_parallel_required(&self.child_statuses, required),
+                    None => aggregate_parallel(
+                        &self.child_statuses,
+                        self.success_threshold,
+                        self.failure_threshold,
+                    ),
+                };
+
+                if status.is_completed() {
                     self.clear();
                     self.reset_children().await;
-                    return Ok(NodeStatus::Failure);
+                    return Ok(status);
                 }
             }
 
             // If all children were skipped, return Skipped
             // Otherwise return Running
-            match skipped_count == children_count {
+            match self
+                .child_statuses
+                .iter()
+                .all(|status| *status == NodeStatus::Skipped)
+            {
                 true => Ok(NodeStatus::Skipped),
                 false => Ok(NodeStatus::Running),
             }
@@ -142,7 +141,8 @@ impl NodePorts for ParallelNode {
     fn provided_ports(&self) -> crate::basic_types::PortsList {
         define_ports!(
             input_port!("success_count", -1),
-            input_port!("failure_count", 1)
+            input_port!("failure_count", 1),
+            input_port!("required_children")
         )
     }
 }
@@ -150,6 +150,7 @@ impl NodePorts for ParallelNode {
 impl AsyncHalt for ParallelNode {
     fn halt(&mut self) -> BoxFuture<()> {
         Box::pin(async move {
+            self.clear();
             self.reset_children().await;
         })
     }
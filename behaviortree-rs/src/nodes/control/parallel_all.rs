@@ -1,10 +1,8 @@
-use std::collections::HashSet;
-
 use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
 
 use crate::{
-    basic_types::NodeStatus,
+    basic_types::{aggregate_parallel, resolve_parallel_threshold, NodeStatus},
     macros::{define_ports, input_port},
     nodes::{
         AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
@@ -22,24 +20,15 @@ use crate::{
 /// https://www.i2tutorials.com/what-are-negative-indexes-and-why-are-they-used/
 ///
 /// Therefore -1 is equivalent to the number of children.
+///
+/// It can be written without children (`<ParallelAll />`), in which case it
+/// returns SUCCESS.
 #[bt_node(ControlNode)]
 pub struct ParallelAllNode {
     #[bt(default = "-1")]
     failure_threshold: i32,
     #[bt(default)]
-    completed_list: HashSet<usize>,
-    #[bt(default = "0")]
-    failure_count: usize,
-}
-
-impl ParallelAllNode {
-    fn failure_threshold(&self) -> usize {
-        if self.failure_threshold < 0 {
-            ((self.children.len() as i32) + self.failure_threshold + 1).max(0) as usize
-        } else {
-            self.failure_threshold as usize
-        }
-    }
+    child_statuses: Vec<NodeStatus>,
 }
 
 impl AsyncTick for ParallelAllNode {
@@ -49,63 +38,77 @@ impl AsyncTick for ParallelAllNode {
 
             let children_count = self.children.len();
 
+            // Only possible if the node was allowed to have no children
+            if children_count == 0 {
+                return Ok(NodeStatus::Success);
+            }
+
+            self.status = NodeStatus::Running;
+
             if (children_count as i32) < self.failure_threshold {
                 return Err(NodeError::NodeStructureError(
                     "Number of children is less than the threshold. Can never fail.".to_string(),
                 ));
             }
 
-            let mut skipped_count = 0;
+            if self.child_statuses.len() != children_count {
+                self.child_statuses = vec![NodeStatus::Idle; children_count];
+            }
+
+            // Skipped children are ticked again, so they only count for this tick
+            for status in self.child_statuses.iter_mut() {
+                if *status == NodeStatus::Skipped {
+                    *status = NodeStatus::Idle;
+                }
+            }
 
             for i in 0..children_count {
                 // Skip completed node
-                if self.completed_list.contains(&i) {
+                if self.child_statuses[i].is_completed() {
                     continue;
                 }
 
                 let status = self.children[i].execute_tick().await?;
-                match status {
-                    NodeStatus::Success => {
-                        self.completed_list.insert(i);
-                    }
-                    NodeStatus::Failure => {
-                        self.completed_list.insert(i);
-                        self.failure_count += 1;
-                    }
-                    NodeStatus::Skipped => skipped_count += 1,
-                    NodeStatus::Running => {}
+                if status == NodeStatus::Idle {
                     // Throw error, should never happen
-                    NodeStatus::Idle => {
-                        return Err(NodeError::StatusError(
-                            "ParallelAllNode".to_string(),
-                            "Idle".to_string(),
-                        ))
-                    }
+                    return Err(NodeError::StatusError(
+                        "ParallelAllNode".to_string(),
+                        "Idle".to_string(),
+                    ));
                 }
+
+                self.child_statuses[i] = status;
             }
 
-            if skipped_count == children_count {
+            if self
+                .child_statuses
+                .iter()
+                .all(|status| *status == NodeStatus::Skipped)
+            {
                 return Ok(NodeStatus::Skipped);
             }
 
-            if skipped_count + self.completed_list.len() >= children_count {
-                // Done!
-                self.reset_children().await;
-                self.completed_list.clear();
-
-                let status = if self.failure_count >= self.failure_threshold() {
-                    NodeStatus::Failure
-                } else {
-                    NodeStatus::Success
-                };
+            if self.child_statuses.contains(&NodeStatus::Running) {
+                return Ok(NodeStatus::Running);
+            }
 
-                // Reset failure_count after using it
-                self.failure_count = 0;
+            // Done! Every child has finished, so this succeeds unless `max_failures`
+            // was reached. That's a relative success threshold, so skipped children
+            // count as successful.
+            let max_failures = resolve_parallel_threshold(self.failure_threshold, children_count);
+            let status = match max_failures {
+                0 => NodeStatus::Failure,
+                max_failures => aggregate_parallel(
+                    &self.child_statuses,
+                    -(max_failures as i32),
+                    max_failures as i32,
+                ),
+            };
 
-                return Ok(status);
-            }
+            self.reset_children().await;
+            self.child_statuses.clear();
 
-            Ok(NodeStatus::Running)
+            Ok(status)
         })
     }
 }
@@ -119,6 +122,7 @@ impl NodePorts for ParallelAllNode {
 impl AsyncHalt for ParallelAllNode {
     fn halt(&mut self) -> BoxFuture<()> {
         Box::pin(async move {
+            self.child_statuses.clear();
             self.reset_children().await;
         })
     }
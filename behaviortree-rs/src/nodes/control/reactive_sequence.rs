@@ -2,7 +2,8 @@ use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
 
 use crate::{
-    basic_types::NodeStatus,
+    basic_types::{NodeStatus, PortsList},
+    macros::{define_ports, input_port},
     nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
 };
 
@@ -17,19 +18,118 @@ use crate::{
 ///
 /// IMPORTANT: to work properly, this node should not have more than a single
 ///            asynchronous child.
+///
+/// With `_allow_multiple_running="true"`, several children may be RUNNING at
+/// the same time instead:
+///
+/// - A child returning RUNNING doesn't stop the sequence, the next sibling is
+///   ticked as well.
+/// - A child that was RUNNING and then returns SUCCESS isn't ticked again until
+///   this node completes. Children that succeed right away (e.g. conditions)
+///   are still ticked on every tick.
+/// - The node returns RUNNING as long as any child is RUNNING, and SUCCESS
+///   once none is.
+/// - If a child returns FAILURE, all running children are halted and the node
+///   returns FAILURE.
+///
+/// The trade-off is that siblings after a RUNNING child start before it has
+/// finished, so they must not depend on its result.
 #[bt_node(ControlNode)]
 pub struct ReactiveSequenceNode {
     #[bt(default = "-1")]
     running_child: i32,
+    /// Only used with `_allow_multiple_running`: `Running` for children that
+    /// are running, `Success` for children that were running and succeeded
+    #[bt(default)]
+    async_statuses: Vec<NodeStatus>,
+}
+
+impl ReactiveSequenceNode {
+    async fn clear(&mut self) {
+        self.running_child = -1;
+        self.async_statuses.clear();
+        self.reset_children().await;
+    }
+
+    async fn tick_multiple_running(&mut self) -> NodeResult {
+        let children_count = self.children.len();
+        if self.async_statuses.len() != children_count {
+            self.async_statuses = vec![NodeStatus::Idle; children_count];
+        }
+
+        let mut any_running = false;
+        let mut all_skipped = true;
+
+        for i in 0..children_count {
+            // Finished earlier in this run
+            if self.async_statuses[i] == NodeStatus::Success {
+                all_skipped = false;
+                continue;
+            }
+
+            let child = &mut self.children[i];
+            let child_status = child.execute_tick().await?;
+
+            all_skipped &= child_status == NodeStatus::Skipped;
+
+            match child_status {
+                NodeStatus::Running => {
+                    self.async_statuses[i] = NodeStatus::Running;
+                    any_running = true;
+                }
+                NodeStatus::Failure => {
+                    self.clear().await;
+                    return Ok(NodeStatus::Failure);
+                }
+                NodeStatus::Success => {
+                    if self.async_statuses[i] == NodeStatus::Running {
+                        self.async_statuses[i] = NodeStatus::Success;
+                    }
+                }
+                NodeStatus::Skipped => {
+                    self.halt_child(i).await?;
+                }
+                NodeStatus::Idle => {
+                    return Err(NodeError::StatusError(
+                        child.config().path.clone(),
+                        "Idle".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if any_running {
+            // Reset the children that are ticked again, so they start over
+            for i in 0..children_count {
+                if self.async_statuses[i] == NodeStatus::Idle {
+                    self.halt_child(i).await?;
+                }
+            }
+            return Ok(NodeStatus::Running);
+        }
+
+        self.clear().await;
+
+        match all_skipped {
+            true => Ok(NodeStatus::Skipped),
+            false => Ok(NodeStatus::Success),
+        }
+    }
 }
 
 impl AsyncTick for ReactiveSequenceNode {
     fn tick(&mut self) -> BoxFuture<NodeResult> {
         Box::pin(async move {
-            let mut all_skipped = true;
-
             self.status = NodeStatus::Running;
 
+            let allow_multiple_running: bool =
+                self.config.get_input("_allow_multiple_running").await?;
+            if allow_multiple_running {
+                return self.tick_multiple_running().await;
+            }
+
+            let mut all_skipped = true;
+
             for counter in 0..self.children.len() {
                 let child = &mut self.children[counter];
                 let child_status = child.execute_tick().await?;
@@ -53,7 +153,7 @@ impl AsyncTick for ReactiveSequenceNode {
                         return Ok(NodeStatus::Running);
                     }
                     NodeStatus::Failure => {
-                        self.reset_children().await;
+                        self.clear().await;
                         return Ok(NodeStatus::Failure);
                     }
                     // Do nothing on Success
@@ -71,7 +171,7 @@ impl AsyncTick for ReactiveSequenceNode {
                 }
             }
 
-            self.reset_children().await;
+            self.clear().await;
 
             match all_skipped {
                 true => Ok(NodeStatus::Skipped),
@@ -81,12 +181,16 @@ impl AsyncTick for ReactiveSequenceNode {
     }
 }
 
-impl NodePorts for ReactiveSequenceNode {}
+impl NodePorts for ReactiveSequenceNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("_allow_multiple_running", false))
+    }
+}
 
 impl AsyncHalt for ReactiveSequenceNode {
     fn halt(&mut self) -> BoxFuture<()> {
         Box::pin(async move {
-            self.reset_children().await;
+            self.clear().await;
         })
     }
 }
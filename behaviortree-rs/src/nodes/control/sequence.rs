@@ -15,6 +15,10 @@ use crate::{
 ///   Loop is NOT restarted, the same running child will be ticked again.
 ///
 /// - If a child returns FAILURE, stop the loop and return FAILURE.
+///
+/// - If all the children return SKIPPED, this node returns SKIPPED.
+///
+/// - If it has no children (written as `<Sequence />`), this node returns SUCCESS.
 #[bt_node(ControlNode)]
 pub struct SequenceNode {
     #[bt(default = "0")]
@@ -26,6 +30,11 @@ pub struct SequenceNode {
 impl AsyncTick for SequenceNode {
     fn tick(&mut self) -> BoxFuture<NodeResult> {
         Box::pin(async move {
+            // Only possible if the node was allowed to have no children
+            if self.children.is_empty() {
+                return Ok(NodeStatus::Success);
+            }
+
             if self.status == NodeStatus::Idle {
                 self.all_skipped = true;
             }
@@ -41,6 +50,8 @@ impl AsyncTick for SequenceNode {
                 self.all_skipped &= child_status == NodeStatus::Skipped;
 
                 match &child_status {
+                    // Return so the running child can be preempted and halted
+                    NodeStatus::Running => return Ok(NodeStatus::Running),
                     NodeStatus::Failure => {
                         self.reset_children().await;
                         self.child_idx = 0;
@@ -51,11 +62,10 @@ impl AsyncTick for SequenceNode {
                     }
                     NodeStatus::Idle => {
                         return Err(NodeError::StatusError(
-                            "ParallelAllNode".to_string(),
+                            "SequenceNode".to_string(),
                             "Idle".to_string(),
                         ))
                     }
-                    _ => {}
                 };
             }
 
@@ -64,7 +74,11 @@ impl AsyncTick for SequenceNode {
                 self.child_idx = 0;
             }
 
-            Ok(NodeStatus::Success)
+            // If all children were skipped, return Skipped
+            match self.all_skipped {
+                true => Ok(NodeStatus::Skipped),
+                false => Ok(NodeStatus::Success),
+            }
         })
     }
 }
@@ -15,6 +15,8 @@ use crate::{
 ///
 /// - If a child returns FAILURE, stop the loop and return FAILURE.
 ///   Loop is NOT restarted, the same running child will be ticked again.
+///
+/// - If all the children return SKIPPED, this node returns SKIPPED.
 #[bt_node(ControlNode)]
 pub struct SequenceWithMemoryNode {
     #[bt(default = "0")]
@@ -69,7 +71,7 @@ impl AsyncTick for SequenceWithMemoryNode {
 
             match self.all_skipped {
                 true => Ok(NodeStatus::Skipped),
-                false => Ok(NodeStatus::Failure),
+                false => Ok(NodeStatus::Success),
             }
         })
     }
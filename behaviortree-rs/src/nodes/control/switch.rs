@@ -0,0 +1,120 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{NodeStatus, PortsList},
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
+};
+
+/// The SwitchNode ticks the child whose case matches "variable", like a
+/// `switch` statement. It's registered as `Switch2` to `Switch6`, for 2 to 6
+/// cases.
+///
+/// The n-th child belongs to the port "case_n", and the value of "variable"
+/// is compared to the value of each case as a string. The node must have one
+/// more child than cases, the default, which is ticked when no case matches;
+/// otherwise building the tree fails. A case whose port isn't set never
+/// matches.
+///
+/// If a child is RUNNING and the value changes, that child is halted before
+/// the child for the new value is ticked.
+///
+/// Example:
+///
+/// ```xml
+/// <Switch2 variable="{color}" case_1="red" case_2="green">
+///     <Stop />
+///     <Go />
+///     <SlowDown />
+/// </Switch2>
+/// ```
+#[bt_node(ControlNode)]
+pub struct SwitchNode {
+    /// Number of cases, without the default
+    pub(crate) cases: usize,
+    /// Child that returned RUNNING in the previous tick
+    #[bt(default)]
+    running_child: Option<usize>,
+}
+
+impl SwitchNode {
+    /// Returns the index of the first case matching the variable, or of the
+    /// default child.
+    async fn matching_child(&mut self) -> Result<usize, NodeError> {
+        // Entries of any type are compared as text, e.g. an i64 with "3"
+        let value = match self.config.get_input_str("variable").await {
+            Some(value) => value.trim().to_string(),
+            None => return Err(NodeError::PortError(String::from("variable"))),
+        };
+
+        for case in 1..=self.cases {
+            let port = format!("case_{case}");
+            match self.config.get_input_str(&port).await {
+                Some(case_value) if case_value.trim() == value => return Ok(case - 1),
+                _ => {}
+            }
+        }
+
+        Ok(self.cases)
+    }
+}
+
+impl AsyncTick for SwitchNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            if self.children.len() != self.cases + 1 {
+                return Err(NodeError::NodeStructureError(format!(
+                    "SwitchNode with {} cases must have {} children.",
+                    self.cases,
+                    self.cases + 1
+                )));
+            }
+
+            let index = self.matching_child().await?;
+
+            // The value changed while a child was running
+            if let Some(running) = self.running_child {
+                if running != index {
+                    self.halt_child(running).await?;
+                    self.running_child = None;
+                }
+            }
+
+            self.status = NodeStatus::Running;
+
+            match self.children[index].execute_tick().await? {
+                NodeStatus::Running => {
+                    self.running_child = Some(index);
+                    Ok(NodeStatus::Running)
+                }
+                status => {
+                    self.running_child = None;
+                    self.reset_children().await;
+                    Ok(status)
+                }
+            }
+        })
+    }
+}
+
+impl NodePorts for SwitchNode {
+    fn provided_ports(&self) -> PortsList {
+        let mut ports = define_ports!(input_port!("variable"));
+        for case in 1..=self.cases {
+            let (name, port_info) = input_port!((format!("case_{case}")));
+            ports.insert(name, port_info);
+        }
+
+        ports
+    }
+}
+
+impl AsyncHalt for SwitchNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.running_child = None;
+            self.reset_children().await;
+        })
+    }
+}
@@ -1,8 +1,12 @@
+use std::time::Duration;
+
 use behaviortree_rs_derive::bt_node;
 use futures::future::BoxFuture;
+use tokio::time::Instant;
 
 use crate::{
-    basic_types::NodeStatus,
+    basic_types::{NodeStatus, PortsList},
+    macros::{define_ports, input_port},
     nodes::{AsyncHalt, AsyncTick, ControlNode, NodeError, NodePorts, NodeResult},
 };
 
@@ -17,8 +21,30 @@ use crate::{
 ///
 /// If the 2nd or 3d child is RUNNING and the statement changes,
 /// the RUNNING child will be stopped before starting the sibling.
+///
+/// For expensive statements, the port "check_period_msec" limits how often
+/// the statement is re-evaluated while the 2nd or 3d child is RUNNING. Between
+/// checks, the previous result is assumed. It defaults to 0 (every tick).
+/// The period is measured with `tokio::time::Instant`, so it follows a paused
+/// tokio clock.
 #[bt_node(ControlNode)]
-pub struct WhileDoElseNode {}
+pub struct WhileDoElseNode {
+    /// Result of the last statement tick, and when it finished
+    #[bt(default)]
+    last_check: Option<(NodeStatus, Instant)>,
+}
+
+impl WhileDoElseNode {
+    /// Returns the cached statement result if it's still recent enough.
+    fn cached_condition(&self, check_period: Duration) -> Option<NodeStatus> {
+        match &self.last_check {
+            Some((status, checked_at)) if checked_at.elapsed() < check_period => {
+                Some(status.clone())
+            }
+            _ => None,
+        }
+    }
+}
 
 impl AsyncTick for WhileDoElseNode {
     fn tick(&mut self) -> BoxFuture<NodeResult> {
@@ -31,11 +57,22 @@ impl AsyncTick for WhileDoElseNode {
                 ));
             }
 
+            let check_period: u64 = self.config.get_input("check_period_msec").await?;
+            let check_period = Duration::from_millis(check_period);
+
             self.status = NodeStatus::Running;
 
-            let condition_status = self.children[0].execute_tick().await?;
+            let condition_status = match self.cached_condition(check_period) {
+                Some(status) => status,
+                None => {
+                    let status = self.children[0].execute_tick().await?;
+                    self.last_check = Some((status.clone(), Instant::now()));
+                    status
+                }
+            };
 
             if matches!(condition_status, NodeStatus::Running) {
+                self.last_check = None;
                 return Ok(NodeStatus::Running);
             }
 
@@ -65,6 +102,8 @@ impl AsyncTick for WhileDoElseNode {
             match status {
                 NodeStatus::Running => Ok(NodeStatus::Running),
                 status => {
+                    // The statement is checked again when the node restarts
+                    self.last_check = None;
                     self.reset_children().await;
                     Ok(status)
                 }
@@ -73,11 +112,16 @@ impl AsyncTick for WhileDoElseNode {
     }
 }
 
-impl NodePorts for WhileDoElseNode {}
+impl NodePorts for WhileDoElseNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("check_period_msec", 0))
+    }
+}
 
 impl AsyncHalt for WhileDoElseNode {
     fn halt(&mut self) -> BoxFuture<()> {
         Box::pin(async move {
+            self.last_check = None;
             self.reset_children().await;
         })
     }
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+use tokio::time::Instant;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The CooldownNode limits how often its child can succeed. After the child
+/// returns SUCCESS, this node returns FAILURE without ticking it until
+/// "rate_msec" milliseconds have passed.
+///
+/// Other statuses of the child are returned as they are. Halting this node
+/// halts the child, but keeps the cooldown running. The cooldown is timed
+/// with `tokio::time::Instant`.
+///
+/// Example:
+///
+/// ```xml
+/// <Cooldown rate_msec="10000">
+///   <SendStatusReport/>
+/// </Cooldown>
+/// ```
+#[bt_node(DecoratorNode)]
+pub struct CooldownNode {
+    #[bt(default)]
+    last_success: Option<Instant>,
+}
+
+impl AsyncTick for CooldownNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let rate: u64 = self.config.get_input("rate_msec").await?;
+
+            if let Some(last_success) = self.last_success {
+                if last_success.elapsed() < Duration::from_millis(rate) {
+                    return Ok(NodeStatus::Failure);
+                }
+            }
+
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            if child_status == NodeStatus::Success {
+                self.last_success = Some(Instant::now());
+            }
+            if child_status != NodeStatus::Running {
+                self.reset_child().await;
+            }
+
+            Ok(child_status)
+        })
+    }
+}
+
+impl NodePorts for CooldownNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(input_port!("rate_msec"))
+    }
+}
+
+impl AsyncHalt for CooldownNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+use tokio::time::Instant;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The DelayNode waits "delay_msec" milliseconds before it ticks its child
+/// for the first time, then returns the status of the child.
+///
+/// While waiting, it returns RUNNING without blocking the tree. Once the
+/// child completes, the next tick waits again. Halting this node cancels the
+/// wait and halts the child. The delay is measured with `tokio::time`, so
+/// it follows a paused tokio clock in tests.
+///
+/// Example:
+///
+/// ```xml
+/// <Delay delay_msec="500">
+///   <OpenGripper/>
+/// </Delay>
+/// ```
+#[bt_node(DecoratorNode)]
+pub struct DelayNode {
+    #[bt(default)]
+    deadline: Option<Instant>,
+}
+
+impl AsyncTick for DelayNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let deadline = match self.deadline {
+                Some(deadline) => deadline,
+                None => {
+                    let delay: u64 = self.config.get_input("delay_msec").await?;
+                    *self
+                        .deadline
+                        .insert(Instant::now() + Duration::from_millis(delay))
+                }
+            };
+
+            self.set_status(NodeStatus::Running);
+
+            if Instant::now() < deadline {
+                return Ok(NodeStatus::Running);
+            }
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            if child_status != NodeStatus::Running {
+                self.deadline = None;
+                self.reset_child().await;
+            }
+
+            Ok(child_status)
+        })
+    }
+}
+
+impl NodePorts for DelayNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(input_port!("delay_msec"))
+    }
+}
+
+impl AsyncHalt for DelayNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.deadline = None;
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The ForceRunningNode ticks its child, but always returns RUNNING.
+///
+/// When the child completes (or is skipped), it's reset, so the next tick
+/// starts it again. Halting this node halts the child.
+#[bt_node(DecoratorNode)]
+pub struct ForceRunningNode {}
+
+impl AsyncTick for ForceRunningNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            if child_status != NodeStatus::Running {
+                self.reset_child().await;
+            }
+
+            Ok(NodeStatus::Running)
+        })
+    }
+}
+
+impl NodePorts for ForceRunningNode {}
+
+impl AsyncHalt for ForceRunningNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult},
+};
+
+/// The ForceSkipNode returns always Skipped and never ticks its child, which
+/// disables a branch without removing it from the XML.
+///
+/// As the child never runs, halting this node has nothing to stop.
+#[bt_node(DecoratorNode)]
+pub struct ForceSkipNode {}
+
+impl AsyncTick for ForceSkipNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Skipped) })
+    }
+}
+
+impl NodePorts for ForceSkipNode {}
+
+impl AsyncHalt for ForceSkipNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use std::any::Any;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::{get_remapped_key, NodeStatus},
+    macros::{define_ports, input_port, output_port},
+    nodes::{
+        AsyncHalt, AsyncTick, DecoratorNode, NodeConfig, NodeError, NodePorts, NodeResult,
+        TreeNodeDefaults,
+    },
+};
+
+/// Result of taking the next item from the queue of a `LoopNode`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum QueuePop {
+    /// There's no queue of the expected type at the key
+    Missing,
+    Empty,
+    /// An item was written to "value", with this many items left
+    Popped(usize),
+}
+
+/// Takes the first item of the queue in the "queue" port and writes it to
+/// the "value" port.
+pub(crate) type QueuePopFn = fn(&mut NodeConfig) -> BoxFuture<'_, Result<QueuePop, NodeError>>;
+
+/// Pops the first item of a `Vec<T>` queue.
+pub(crate) fn pop_front<T>(config: &mut NodeConfig) -> BoxFuture<'_, Result<QueuePop, NodeError>>
+where
+    T: Any + Send,
+{
+    Box::pin(async move {
+        let key = config
+            .input_ports
+            .get("queue")
+            .and_then(|queue| get_remapped_key("queue", queue.trim()))
+            .ok_or_else(|| NodeError::PortError(String::from("queue")))?;
+
+        let Some(mut queue) = config.blackboard.take::<Vec<T>>(&key).await else {
+            return Ok(QueuePop::Missing);
+        };
+        if queue.is_empty() {
+            return Ok(QueuePop::Empty);
+        }
+        let item = queue.remove(0);
+        let remaining = queue.len();
+
+        // Written back with `set()`, so subscribers see the change
+        config.blackboard.set(&key, queue).await;
+        config.set_output("value", item).await?;
+
+        Ok(QueuePop::Popped(remaining))
+    })
+}
+
+/// The LoopNode takes the items of a queue one by one, and ticks its child
+/// for each of them. It's registered as `LoopInt`, `LoopDouble`,
+/// `LoopString` and `LoopBool`, for queues of `i64`, `f64`, `String` and
+/// `bool`. `Factory::register_loop_node()` adds it for other types.
+///
+/// The queue is a `Vec<T>` in the blackboard entry of the port "queue". The
+/// first item is removed from it and written to the port "value" before each
+/// run of the child. While items are left, this node returns RUNNING. Once
+/// the child succeeded for the last one, it returns SUCCESS.
+///
+/// If the child fails, the loop stops and this node returns FAILURE. If the
+/// queue is already empty, the port "if_empty" is returned (SUCCESS by
+/// default), and if there's no queue of the right type, FAILURE.
+///
+/// Halting this node halts the child. The remaining items stay in the
+/// queue, so the loop continues with them the next time.
+///
+/// Example:
+///
+/// ```xml
+/// <LoopString queue="{waypoints}" value="{waypoint}">
+///   <GoTo target="{waypoint}"/>
+/// </LoopString>
+/// ```
+#[bt_node(DecoratorNode)]
+pub struct LoopNode {
+    pop: QueuePopFn,
+    /// Items left in the queue after the one the child runs for
+    #[bt(default)]
+    remaining: Option<usize>,
+}
+
+impl AsyncTick for LoopNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            if self.remaining.is_none() {
+                match (self.pop)(&mut self.config).await? {
+                    QueuePop::Missing => return Ok(NodeStatus::Failure),
+                    QueuePop::Empty => return self.config.get_input("if_empty").await,
+                    QueuePop::Popped(remaining) => self.remaining = Some(remaining),
+                }
+            }
+
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            match child_status {
+                NodeStatus::Running => Ok(NodeStatus::Running),
+                NodeStatus::Failure => {
+                    self.remaining = None;
+                    self.reset_child().await;
+
+                    Ok(NodeStatus::Failure)
+                }
+                NodeStatus::Success | NodeStatus::Skipped => {
+                    let remaining = self.remaining.take();
+                    self.reset_child().await;
+
+                    match remaining {
+                        Some(0) => Ok(NodeStatus::Success),
+                        _ => Ok(NodeStatus::Running),
+                    }
+                }
+                NodeStatus::Idle => Err(NodeError::StatusError(
+                    self.config.path.clone(),
+                    "Idle".to_string(),
+                )),
+            }
+        })
+    }
+}
+
+impl NodePorts for LoopNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(
+            input_port!("queue"),
+            output_port!("value"),
+            input_port!("if_empty", type: NodeStatus, NodeStatus::Success)
+        )
+    }
+}
+
+impl AsyncHalt for LoopNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.remaining = None;
+            self.reset_child().await;
+        })
+    }
+}
@@ -1,9 +1,17 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::nodes::{NodeError, TreeNodeBase, TreeNodePtr};
+use crate::nodes::{NodeError, NodeResult, TreeNodeBase, TreeNodePtr};
 
+mod cooldown;
+pub use cooldown::*;
+mod delay;
+pub use delay::*;
 mod force_failure;
 pub use force_failure::*;
+mod force_running;
+pub use force_running::*;
+mod force_skip;
+pub use force_skip::*;
 mod force_success;
 pub use force_success::*;
 mod inverter;
@@ -11,12 +19,22 @@ use futures::future::BoxFuture;
 pub use inverter::*;
 mod keep_running_until_failure;
 pub use keep_running_until_failure::*;
+mod loop_node;
+pub use loop_node::*;
 mod repeat;
 pub use repeat::*;
 mod retry;
 pub use retry::*;
+mod run_n;
+pub use run_n::*;
 mod run_once;
 pub use run_once::*;
+mod simple_decorator;
+pub use simple_decorator::*;
+mod subtree;
+pub use subtree::*;
+mod timeout;
+pub use timeout::*;
 
 pub trait DecoratorNodeBase: TreeNodeBase + DecoratorNode {}
 
@@ -31,4 +49,12 @@ pub trait DecoratorNode: TreeNodeBase {
     fn halt_child(&mut self) -> BoxFuture<()>;
     /// Reset status of child and call `halt()`
     fn reset_child(&mut self) -> BoxFuture<()>;
+    /// Tick the child and return its status.
+    ///
+    /// A decorator can tick its child several times in one of its own ticks.
+    /// Once the child has completed (any status but RUNNING), call
+    /// `reset_child()` before ticking it again, so it starts over from IDLE.
+    /// If the child returns RUNNING, return RUNNING as well and tick it again
+    /// on the next tick, without resetting it. See `RunNNode` for an example.
+    fn tick_child(&mut self) -> BoxFuture<NodeResult>;
 }
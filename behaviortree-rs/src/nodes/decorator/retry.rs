@@ -3,7 +3,7 @@ use futures::future::BoxFuture;
 
 use crate::{
     basic_types::NodeStatus,
-    macros::{define_ports, input_port},
+    macros::{define_ports, input_port, output_port},
     nodes::{
         AsyncHalt, AsyncTick, DecoratorNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
     },
@@ -17,6 +17,9 @@ use crate::{
 /// If the child returns FAILURE, this node will try again up to N times
 /// (N is read from port "num_attempts").
 ///
+/// The optional output port "current_attempt" is set to the number of the
+/// current attempt (starting at 1, as `usize`) before each tick of the child.
+///
 /// Example:
 ///
 /// ```xml
@@ -50,6 +53,13 @@ impl AsyncTick for RetryNode {
             self.set_status(NodeStatus::Running);
 
             while do_loop {
+                // Only written if the port is used
+                if self.config.output_ports.contains_key("current_attempt") {
+                    self.config
+                        .set_output("current_attempt", self.try_count + 1)
+                        .await?;
+                }
+
                 let child_status = self.child.as_mut().unwrap().execute_tick().await?;
 
                 self.all_skipped &= matches!(child_status, NodeStatus::Skipped);
@@ -95,7 +105,7 @@ impl AsyncTick for RetryNode {
 
 impl NodePorts for RetryNode {
     fn provided_ports(&self) -> crate::basic_types::PortsList {
-        define_ports!(input_port!("num_attempts"))
+        define_ports!(input_port!("num_attempts"), output_port!("current_attempt"))
     }
 }
 
@@ -0,0 +1,133 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port, output_port},
+    nodes::{
+        AsyncHalt, AsyncTick, DecoratorNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
+};
+
+/// The RunNNode ticks its child N times (port "num_runs") and aggregates
+/// the results: it returns SUCCESS if at least "success_threshold" runs
+/// succeeded, FAILURE otherwise. Unlike `Repeat`, a failed run doesn't stop
+/// the loop. The threshold defaults to -1, which means every run must succeed.
+///
+/// All runs happen within one tick, unless the child returns RUNNING. Then
+/// this node returns RUNNING too and continues the same run on the next tick.
+///
+/// Skipped runs count as neither success nor failure. If every run is
+/// skipped, this node returns SKIPPED.
+///
+/// The optional output port "successes" is set to the number of successful
+/// runs (as `usize`) when the last run has completed.
+///
+/// If the child returns an error, the error is returned and the runs start
+/// over on the next tick.
+///
+/// Example:
+///
+/// ```xml
+/// <RunN num_runs="5" success_threshold="3">
+///     <DetectObject/>
+/// </RunN>
+/// ```
+#[bt_node(DecoratorNode)]
+pub struct RunNNode {
+    #[bt(default)]
+    run_count: usize,
+    #[bt(default)]
+    successes: usize,
+    #[bt(default)]
+    skipped: usize,
+}
+
+impl RunNNode {
+    fn reset_counts(&mut self) {
+        self.run_count = 0;
+        self.successes = 0;
+        self.skipped = 0;
+    }
+}
+
+impl AsyncTick for RunNNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let num_runs: usize = self.config.get_input("num_runs").await?;
+            let threshold: i32 = self.config.get_input("success_threshold").await?;
+
+            self.set_status(NodeStatus::Running);
+
+            while self.run_count < num_runs {
+                let status = match self.tick_child().await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        // The next tick starts over
+                        self.reset_counts();
+                        return Err(e);
+                    }
+                };
+
+                match status {
+                    NodeStatus::Running => return Ok(NodeStatus::Running),
+                    NodeStatus::Success => self.successes += 1,
+                    NodeStatus::Failure => {}
+                    NodeStatus::Skipped => self.skipped += 1,
+                    NodeStatus::Idle => {
+                        self.reset_counts();
+                        return Err(NodeError::StatusError(
+                            self.config.path.clone(),
+                            "Idle".to_string(),
+                        ));
+                    }
+                }
+
+                // The next run starts from IDLE
+                self.run_count += 1;
+                self.reset_child().await;
+            }
+
+            let (successes, skipped) = (self.successes, self.skipped);
+            self.reset_counts();
+
+            // Only written if the port is used
+            if self.config.output_ports.contains_key("successes") {
+                self.config.set_output("successes", successes).await?;
+            }
+
+            if num_runs > 0 && skipped == num_runs {
+                return Ok(NodeStatus::Skipped);
+            }
+
+            let required = match usize::try_from(threshold) {
+                Ok(threshold) => threshold,
+                Err(_) => num_runs - skipped,
+            };
+
+            match successes >= required {
+                true => Ok(NodeStatus::Success),
+                false => Ok(NodeStatus::Failure),
+            }
+        })
+    }
+}
+
+impl NodePorts for RunNNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(
+            input_port!("num_runs"),
+            input_port!("success_threshold", -1),
+            output_port!("successes")
+        )
+    }
+}
+
+impl AsyncHalt for RunNNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_counts();
+            self.reset_child().await;
+        })
+    }
+}
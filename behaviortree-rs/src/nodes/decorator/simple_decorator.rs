@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{
+        AsyncHalt, AsyncTick, DecoratorNode, NodeError, NodePorts, NodeResult, TreeNodeDefaults,
+    },
+};
+
+/// Function applied by a `SimpleDecoratorNode` to the status of its child,
+/// shared by all the nodes registered with it.
+#[derive(Clone)]
+pub struct SimpleDecoratorFn(Arc<dyn Fn(NodeStatus) -> NodeStatus + Send + Sync>);
+
+impl SimpleDecoratorFn {
+    pub fn new<F>(tick_fn: F) -> SimpleDecoratorFn
+    where
+        F: Fn(NodeStatus) -> NodeStatus + Send + Sync + 'static,
+    {
+        Self(Arc::new(tick_fn))
+    }
+}
+
+impl std::fmt::Debug for SimpleDecoratorFn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimpleDecoratorFn").finish_non_exhaustive()
+    }
+}
+
+/// The SimpleDecoratorNode ticks its child and returns the status a function
+/// maps the child's status to, like BehaviorTree.CPP's
+/// `SimpleDecoratorNode`. E.g. `|status| status` ticks the child unchanged,
+/// and mapping FAILURE to SUCCESS and keeping other statuses works like
+/// `ForceSuccess`.
+///
+/// It's registered with `Factory::register_simple_decorator()` or the
+/// `register_simple_decorator!` macro. The child is reset once it completes,
+/// and halted if it's RUNNING but the function maps that to another status,
+/// e.g. `|_| NodeStatus::Success`. The function must not return IDLE.
+#[bt_node(DecoratorNode)]
+pub struct SimpleDecoratorNode {
+    tick_fn: SimpleDecoratorFn,
+}
+
+impl AsyncTick for SimpleDecoratorNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+            let status = (self.tick_fn.0)(child_status.clone());
+            // A running child is halted when the decorator doesn't keep running
+            if child_status.is_completed() || status != NodeStatus::Running {
+                self.reset_child().await;
+            }
+
+            match status {
+                NodeStatus::Idle => Err(NodeError::StatusError(
+                    self.config.path.clone(),
+                    "Idle".to_string(),
+                )),
+                status => Ok(status),
+            }
+        })
+    }
+}
+
+impl NodePorts for SimpleDecoratorNode {}
+
+impl AsyncHalt for SimpleDecoratorNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+
+use crate::{
+    basic_types::NodeStatus,
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The SubTreeNode wraps the root of a `<SubTree>`. It lives in the parent
+/// tree's scope, so attributes such as pre-conditions set on the `<SubTree>`
+/// tag are evaluated against the parent blackboard, not the subtree's.
+///
+/// It returns whatever the subtree's root returns.
+#[bt_node(DecoratorNode)]
+pub struct SubTreeNode {}
+
+impl AsyncTick for SubTreeNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            if child_status.is_completed() {
+                self.reset_child().await;
+            }
+
+            Ok(child_status)
+        })
+    }
+}
+
+impl NodePorts for SubTreeNode {}
+
+impl AsyncHalt for SubTreeNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.reset_child().await;
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use behaviortree_rs_derive::bt_node;
+use futures::future::BoxFuture;
+use tokio::time::Instant;
+
+use crate::{
+    basic_types::NodeStatus,
+    macros::{define_ports, input_port},
+    nodes::{AsyncHalt, AsyncTick, DecoratorNode, NodePorts, NodeResult, TreeNodeDefaults},
+};
+
+/// The TimeoutNode halts its child if it's still RUNNING "msec"
+/// milliseconds after it was started, and returns FAILURE.
+///
+/// The time is checked before each tick of the child, so the child isn't
+/// halted until this node is ticked again. If the child completes first,
+/// its status is returned and the next tick starts a new timeout. Like
+/// `Delay`, it reads the time from `tokio::time::Instant`.
+///
+/// Example:
+///
+/// ```xml
+/// <Timeout msec="5000">
+///   <KeepYourBreath/>
+/// </Timeout>
+/// ```
+#[bt_node(DecoratorNode)]
+pub struct TimeoutNode {
+    #[bt(default)]
+    deadline: Option<Instant>,
+}
+
+impl AsyncTick for TimeoutNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            match self.deadline {
+                Some(deadline) if Instant::now() >= deadline => {
+                    self.deadline = None;
+                    self.reset_child().await;
+
+                    return Ok(NodeStatus::Failure);
+                }
+                Some(_) => {}
+                None => {
+                    let msec: u64 = self.config.get_input("msec").await?;
+                    self.deadline = Some(Instant::now() + Duration::from_millis(msec));
+                }
+            }
+
+            self.set_status(NodeStatus::Running);
+
+            let child_status = self.child.as_mut().unwrap().execute_tick().await?;
+
+            if child_status != NodeStatus::Running {
+                self.deadline = None;
+                self.reset_child().await;
+            }
+
+            Ok(child_status)
+        })
+    }
+}
+
+impl NodePorts for TimeoutNode {
+    fn provided_ports(&self) -> crate::basic_types::PortsList {
+        define_ports!(input_port!("msec"))
+    }
+}
+
+impl AsyncHalt for TimeoutNode {
+    fn halt(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            self.deadline = None;
+            self.reset_child().await;
+        })
+    }
+}
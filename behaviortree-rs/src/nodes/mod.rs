@@ -1,4 +1,11 @@
-use std::{any::TypeId, collections::HashMap, sync::Arc};
+use std::{
+    any::{self, Any},
+    borrow::Cow,
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
 
 use futures::future::BoxFuture;
 use thiserror::Error;
@@ -8,8 +15,9 @@ use crate::{
         self, get_remapped_key, FromString, ParseStr, PortDirection, PortValue,
         PortsRemapping, TreeNodeManifest,
     },
-    blackboard::BlackboardString,
-    tree::ParseError,
+    blackboard::{BlackboardError, BlackboardFullError, BlackboardString, EntryRef},
+    expression::{self, ExpressionError, Script, EXPRESSION_PREFIX},
+    tree::{BreakpointHook, BreakpointPhase, ParseError, StatusCallbacks, WakeConditionRegistry},
     Blackboard,
 };
 
@@ -34,6 +42,7 @@ pub trait TreeNodeBase:
     std::fmt::Debug
     + NodePorts
     + TreeNodeDefaults
+    + NodeChildren
     + GetNodeType
     + ExecuteTick
     + SyncHalt
@@ -50,6 +59,29 @@ pub type TreeNodePtr = Box<dyn TreeNodeBase + Send + Sync>;
 
 pub type NodeResult = Result<NodeStatus, NodeError>;
 
+/// Access to the children of any node, regardless of its type. Implemented
+/// by the derive macros: control nodes expose all their children, decorators
+/// their child (if set), and action nodes have none.
+pub trait NodeChildren {
+    fn child_nodes(&self) -> Vec<&TreeNodePtr> {
+        Vec::new()
+    }
+
+    fn child_nodes_mut(&mut self) -> Vec<&mut TreeNodePtr> {
+        Vec::new()
+    }
+
+    /// Adds `child` to the node. Control nodes append it to their children.
+    /// Decorators replace their child and return the previous one. Action
+    /// nodes can't have children and return an error.
+    fn attach_child(&mut self, child: TreeNodePtr) -> Result<Option<TreeNodePtr>, NodeError> {
+        let _ = child;
+        Err(NodeError::NodeStructureError(
+            "Action nodes can't have children".to_string(),
+        ))
+    }
+}
+
 /// The only trait from `TreeNodeBase` that _needs_ to be
 /// implemented manually, without a derive macro. This is where
 /// the `tick()` is defined as well as the ports, with
@@ -58,6 +90,21 @@ pub trait NodePorts {
     fn provided_ports(&self) -> PortsList {
         HashMap::new()
     }
+
+    /// If true, every input port must be given a value in the XML, and the
+    /// port defaults aren't used. A missing port is a
+    /// `ParseError::MissingRequiredPort` when the tree is built.
+    fn strict_ports(&self) -> bool {
+        false
+    }
+
+    /// Blackboard keys the node writes other than through its output ports,
+    /// e.g. an entry named by an input port. They're called once the ports
+    /// are set, to check the port remapping when the tree is built, see
+    /// `Factory::take_build_warnings()`.
+    fn written_keys(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// The only trait from `TreeNodeBase` that _needs_ to be
@@ -127,6 +174,25 @@ pub trait TreeNodeDefaults {
     fn config(&self) -> &NodeConfig;
     fn config_mut(&mut self) -> &mut NodeConfig;
     fn into_boxed(self) -> Box<dyn TreeNodeBase>;
+    /// Returns the node as `Any`, to downcast it to its concrete type.
+    fn as_any(&self) -> &dyn std::any::Any;
+    /// Returns the node as `Any`, to downcast it to its concrete type, e.g. to
+    /// initialize nodes in `AsyncTree::visit_nodes_mut()`.
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
+    /// Returns the node's uid, which is unique within its tree. Uids are
+    /// assigned in pre-order while the tree is built, so the same XML always
+    /// yields the same uids.
+    fn uid(&self) -> u16 {
+        self.config().uid
+    }
+    /// Returns the description given when the node type was registered, or
+    /// an empty string if there wasn't one.
+    fn description(&self) -> &str {
+        self.config()
+            .manifest
+            .as_deref()
+            .map_or("", |manifest| manifest.description.as_str())
+    }
 }
 
 /// Automatically implemented for all node types. The implementation
@@ -135,7 +201,8 @@ pub trait ExecuteTick {
     fn execute_tick(&mut self) -> BoxFuture<NodeResult>;
 }
 
-/// TODO
+/// Implemented for the nodes created with `#[bt_node(ConditionNode)]`, which
+/// tick like synchronous actions.
 pub trait ConditionNode {}
 
 /// Automatically implemented for all node types.
@@ -155,29 +222,119 @@ pub enum NodeError {
     StatusError(String, String),
     #[error("Out of bounds index")]
     IndexError,
-    #[error("Couldn't find port [{0}]")]
+    #[error(
+        "Couldn't find port [{0}]. Hint: is the port set in the XML, or given a default value in `provided_ports()`?"
+    )]
     PortError(String),
-    #[error("Couldn't parse port [{0}] value into specified type [{1}]")]
+    #[error(
+        "Couldn't parse port [{0}] value into specified type [{1}]. Hint: check the port's value in the XML, or the type stored in the blackboard entry it points to."
+    )]
     /// # Arguments
     /// * Port name
     /// * Expected type
+    #[deprecated(note = "no longer returned, see `NodeError::InvalidPortValue`")]
     PortValueParseError(String, String),
-    #[error("Couldn't find entry in blackboard [{0}]")]
+    #[error(
+        "Couldn't parse value [{1}] of port [{0}] into type [{2}]. Hint: check the port's value in the XML, or the type stored in the blackboard entry it points to."
+    )]
+    /// Replaces `PortValueParseError`, adding the value
+    ///
+    /// # Arguments
+    /// * Port name
+    /// * Value that couldn't be parsed
+    /// * Expected type
+    InvalidPortValue(String, String, String),
+    #[error(
+        "Couldn't parse element {1} [{2}] of port [{0}] into type [{3}]. Hint: elements are separated by `,` or `;`."
+    )]
+    /// Returned by `NodeConfig::get_input_vec<T>()`
+    ///
+    /// # Arguments
+    /// * Port name
+    /// * Index of the element, starting at 0
+    /// * Element that couldn't be parsed
+    /// * Expected type
+    PortElementParseError(String, usize, String, String),
+    #[error(
+        "Couldn't find entry in blackboard [{0}]. Hint: is the key set before this node runs? A subtree only sees its parent's entries if they're remapped."
+    )]
     BlackboardError(String),
+    #[error("Couldn't read input port [{0}]: {1}")]
+    /// The blackboard entry the port points to is of another type, i.e. a
+    /// `BlackboardError::TypeMismatch`
+    ///
+    /// `(port_name, error)`
+    BlackboardTypeMismatch(String, #[source] BlackboardError),
+    #[error("Couldn't write output port [{0}]: {1}")]
+    /// `(port_name, error)`
+    BlackboardFull(String, #[source] BlackboardFullError),
     #[error("{0}")]
     UserError(#[from] anyhow::Error),
     #[error("{0}")]
     NodeStructureError(String),
-    #[error("Decorator node does not have a child.")]
+    #[error("Decorator node does not have a child. Hint: add a child node to the decorator in the XML.")]
     ChildMissing,
     #[error("Blackboard lock was poisoned.")]
     LockPoisoned,
     #[error("A tick method was called that should have been unreachable. Please report this.")]
     UnreachableTick,
+    #[error("Couldn't evaluate the expression of port [{0}]: {1}")]
+    /// `(port_name, error)`
+    ExpressionError(String, #[source] crate::expression::ExpressionError),
+    #[error("Operation timed out after {0:?}")]
+    /// Returned by `with_timeout_error()`
+    Timeout(std::time::Duration),
+    #[error("{0}")]
+    /// Domain-specific error returned by a node. The original error is kept
+    /// so callers can recover it with `downcast_ref()`.
+    Custom(Box<dyn std::error::Error + Send + Sync>),
 }
 
-/// TODO: Not currently used
-#[derive(Clone, Debug)]
+impl NodeError {
+    /// Wraps any error type in `NodeError::Custom`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use behaviortree_rs::nodes::NodeError;
+    ///
+    /// let err = NodeError::custom(std::io::Error::other("service unavailable"));
+    ///
+    /// assert_eq!(err.to_string(), "service unavailable");
+    /// assert!(err.downcast_ref::<std::io::Error>().is_some());
+    /// ```
+    pub fn custom(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> NodeError {
+        Self::Custom(error.into())
+    }
+
+    /// Creates a `NodeError::InvalidPortValue` for a `value` of `port` that
+    /// couldn't be parsed as `T`.
+    pub fn parse_error<T>(port: impl Into<String>, value: impl Into<String>) -> NodeError {
+        Self::InvalidPortValue(port.into(), value.into(), any::type_name::<T>().to_string())
+    }
+
+    /// Returns a reference to the wrapped error if this is a `NodeError::Custom`
+    /// holding an error of type `E`.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: std::error::Error + 'static,
+    {
+        match self {
+            Self::Custom(error) => error.downcast_ref::<E>(),
+            _ => None,
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for NodeError {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self::Custom(error)
+    }
+}
+
+/// Conditions checked before a node is ticked, set with the `_failureIf`,
+/// `_successIf`, `_skipIf` and `_while` XML attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PreCond {
     FailureIf,
     SuccessIf,
@@ -186,8 +343,32 @@ pub enum PreCond {
     Count,
 }
 
-/// TODO: Not currently used
-#[derive(Clone, Debug)]
+impl PreCond {
+    /// Returns the `PreCond` matching the XML attribute `name`, if any.
+    pub fn from_attribute(name: &str) -> Option<PreCond> {
+        match name {
+            "_failureIf" => Some(Self::FailureIf),
+            "_successIf" => Some(Self::SuccessIf),
+            "_skipIf" => Some(Self::SkipIf),
+            "_while" => Some(Self::WhileTrue),
+            _ => None,
+        }
+    }
+
+    /// Returns the XML attribute of the `PreCond`.
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            Self::FailureIf => "_failureIf",
+            Self::SuccessIf => "_successIf",
+            Self::SkipIf => "_skipIf",
+            Self::WhileTrue | Self::Count => "_while",
+        }
+    }
+}
+
+/// Scripts run after a node's tick, set with the `_onHalted`, `_onFailure`,
+/// `_onSuccess` and `_post` XML attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PostCond {
     OnHalted,
     OnFailure,
@@ -196,6 +377,29 @@ pub enum PostCond {
     Count,
 }
 
+impl PostCond {
+    /// Returns the `PostCond` matching the XML attribute `name`, if any.
+    pub fn from_attribute(name: &str) -> Option<PostCond> {
+        match name {
+            "_onHalted" => Some(Self::OnHalted),
+            "_onFailure" => Some(Self::OnFailure),
+            "_onSuccess" => Some(Self::OnSuccess),
+            "_post" => Some(Self::Always),
+            _ => None,
+        }
+    }
+
+    /// Returns the XML attribute of the `PostCond`.
+    pub fn attribute(&self) -> &'static str {
+        match self {
+            Self::OnHalted => "_onHalted",
+            Self::OnFailure => "_onFailure",
+            Self::OnSuccess => "_onSuccess",
+            Self::Always | Self::Count => "_post",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum NodeRuntime {
     Async,
@@ -217,10 +421,19 @@ pub struct NodeConfig {
     pub uid: u16,
     /// TODO: doesn't show actual path yet
     pub path: String,
-    /// TODO: not used
-    _pre_conditions: HashMap<PreCond, String>,
-    /// TODO: not used
-    _post_conditions: HashMap<PostCond, String>,
+    pre_conditions: HashMap<PreCond, String>,
+    /// Scripts with their text, parsed once when they're set
+    post_conditions: HashMap<PostCond, (String, Script)>,
+    /// Set while the tree is ticked by `AsyncTree::tick_with_breakpoints()`
+    pub(crate) breakpoint_hook: Option<BreakpointHook>,
+    /// Set by `AsyncTree::enable_groot2_publisher()`
+    #[cfg(feature = "groot2")]
+    pub(crate) status_hook: Option<crate::groot2::StatusHook>,
+    /// Set by the `AsyncTree` the node belongs to, see
+    /// `AsyncTree::add_status_callback()`
+    pub(crate) status_callbacks: Option<StatusCallbacks>,
+    /// Set by the `AsyncTree` the node belongs to
+    pub(crate) wake_conditions: Option<WakeConditionRegistry>,
 }
 
 impl NodeConfig {
@@ -232,8 +445,13 @@ impl NodeConfig {
             manifest: None,
             uid: 1,
             path: String::from("TODO"),
-            _pre_conditions: HashMap::new(),
-            _post_conditions: HashMap::new(),
+            pre_conditions: HashMap::new(),
+            post_conditions: HashMap::new(),
+            breakpoint_hook: None,
+            #[cfg(feature = "groot2")]
+            status_hook: None,
+            status_callbacks: None,
+            wake_conditions: None,
         }
     }
 
@@ -242,6 +460,12 @@ impl NodeConfig {
         &self.blackboard
     }
 
+    /// Returns a mutable reference to the blackboard, e.g. to call `set()` or
+    /// `get()` on it directly.
+    pub fn blackboard_mut(&mut self) -> &mut Blackboard {
+        &mut self.blackboard
+    }
+
     /// Adds a port to the config based on the direction. Used during XML parsing.
     pub fn add_port(&mut self, direction: PortDirection, name: String, value: String) {
         match direction {
@@ -279,58 +503,331 @@ impl NodeConfig {
         let _ = self.manifest.insert(manifest);
     }
 
+    /// Sets the condition for `pre_cond`, replacing any previous one.
+    ///
+    /// A condition is either a literal `bool` (`"true"`/`"false"`), a
+    /// blackboard key holding a `bool` written as `"{key}"` or just `"key"`,
+    /// or an expression that evaluates to a `bool`, such as `"battery < 20"`.
+    /// See the `expression` module for the syntax.
+    pub fn set_pre_condition(&mut self, pre_cond: PreCond, condition: impl Into<String>) {
+        self.pre_conditions.insert(pre_cond, condition.into());
+    }
+
+    /// Returns the condition set for `pre_cond`, if any.
+    pub fn pre_condition(&self, pre_cond: PreCond) -> Option<&String> {
+        self.pre_conditions.get(&pre_cond)
+    }
+
+    /// Sets the script for `post_cond`, replacing any previous one. The
+    /// script is a list of assignments such as `"done := true"`, see
+    /// `expression::Script`. It's parsed here, so an invalid script returns
+    /// an error instead of failing every tick; the parsed script is returned.
+    pub fn set_post_condition(
+        &mut self,
+        post_cond: PostCond,
+        script: impl Into<String>,
+    ) -> Result<&Script, ExpressionError> {
+        let text = script.into();
+        let script = Script::parse(&text)?;
+        self.post_conditions.insert(post_cond, (text, script));
+
+        Ok(&self.post_conditions[&post_cond].1)
+    }
+
+    /// Returns the script set for `post_cond`, if any.
+    pub fn post_condition(&self, post_cond: PostCond) -> Option<&String> {
+        self.post_conditions.get(&post_cond).map(|(text, _)| text)
+    }
+
+    async fn evaluate_condition(&self, pre_cond: PreCond) -> Result<bool, NodeError> {
+        let condition = self.pre_conditions[&pre_cond].trim();
+
+        if let Ok(value) = <bool as FromString>::from_string(condition) {
+            return Ok(value);
+        }
+
+        // A bare key is read like "{key}", so that a string entry such as
+        // "true" is still converted
+        let key = condition.strip_bb_pointer().or_else(|| {
+            expression::is_identifier(condition).then(|| condition.to_string())
+        });
+        if let Some(key) = key {
+            return match self.blackboard.clone().get::<bool>(&key).await {
+                Some(value) => Ok(value),
+                None => Err(NodeError::BlackboardError(key)),
+            };
+        }
+
+        match expression::evaluate(condition, &self.blackboard).await {
+            Ok(expression::Value::Bool(value)) => Ok(value),
+            Ok(value) => Err(expression::ExpressionError::expected_bool(&value)),
+            Err(e) => Err(e),
+        }
+        .map_err(|e| NodeError::ExpressionError(pre_cond.attribute().to_string(), e))
+    }
+
+    async fn run_post_condition(&self, post_cond: PostCond) -> Result<(), NodeError> {
+        let Some((_, script)) = self.post_conditions.get(&post_cond) else {
+            return Ok(());
+        };
+
+        script
+            .execute(&self.blackboard)
+            .await
+            .map_err(|e| NodeError::ExpressionError(post_cond.attribute().to_string(), e))
+    }
+
+    /// Pauses the tick if the node matches a breakpoint of
+    /// `AsyncTree::tick_with_breakpoints()`. Called by `execute_tick()` before
+    /// the node ticks and after it returns `status`.
+    pub async fn check_breakpoint(&self, phase: BreakpointPhase, status: &NodeStatus) {
+        if let Some(hook) = &self.breakpoint_hook {
+            hook.check(self, phase, status).await;
+        }
+    }
+
+    /// Reports the status a node returned from its tick to the Groot2
+    /// publisher of the tree, if there's one, and to the callbacks of
+    /// `AsyncTree::add_status_callback()` if it differs from `previous`, the
+    /// status before the tick. Called by `execute_tick()`.
+    pub fn report_status(&self, previous: &NodeStatus, status: &NodeStatus) {
+        #[cfg(feature = "groot2")]
+        if let Some(hook) = &self.status_hook {
+            hook.report(self.uid, status);
+        }
+
+        if let Some(callbacks) = &self.status_callbacks {
+            if previous != status {
+                callbacks.call(self.uid, previous.clone(), status.clone());
+            }
+        }
+    }
+
+    /// Wakes up the tree when `key` is set in the node's blackboard, for a
+    /// node that returns RUNNING while it waits for the entry. Lasts until
+    /// the next tick starts, see `WakeConditionRegistry`. Does nothing if the
+    /// node isn't part of a tree yet.
+    pub async fn wake_up_on_change(&self, key: impl AsRef<str>) {
+        if let Some(wake_conditions) = &self.wake_conditions {
+            wake_conditions.watch(&self.blackboard, key).await;
+        }
+    }
+
+    /// Checks the pre-conditions of a node whose current status is `status`.
+    /// Returns the status the node should return instead of being ticked,
+    /// or `None` if it should be ticked normally.
+    ///
+    /// `_failureIf`, `_successIf` and `_skipIf` are only checked when the node
+    /// is `Idle`, in that order. `_while` is also checked while `Running`; if
+    /// it's false, the node returns `Skipped` and the caller should halt it.
+    pub async fn check_pre_conditions(
+        &self,
+        status: &NodeStatus,
+    ) -> Result<Option<NodeStatus>, NodeError> {
+        if self.pre_conditions.is_empty() {
+            return Ok(None);
+        }
+
+        if *status == NodeStatus::Idle {
+            for (pre_cond, result) in [
+                (PreCond::FailureIf, NodeStatus::Failure),
+                (PreCond::SuccessIf, NodeStatus::Success),
+                (PreCond::SkipIf, NodeStatus::Skipped),
+            ] {
+                if self.pre_conditions.contains_key(&pre_cond)
+                    && self.evaluate_condition(pre_cond).await?
+                {
+                    return Ok(Some(result));
+                }
+            }
+        }
+
+        if matches!(status, NodeStatus::Idle | NodeStatus::Running)
+            && self.pre_conditions.contains_key(&PreCond::WhileTrue)
+            && !self.evaluate_condition(PreCond::WhileTrue).await?
+        {
+            return Ok(Some(NodeStatus::Skipped));
+        }
+
+        Ok(None)
+    }
+
+    /// Runs the post-condition scripts for the `status` a node returned:
+    /// `_onSuccess` or `_onFailure`, then `_post`. Nothing runs for other
+    /// statuses. Called by `execute_tick()`.
+    pub async fn check_post_conditions(&self, status: &NodeStatus) -> Result<(), NodeError> {
+        if self.post_conditions.is_empty() {
+            return Ok(());
+        }
+
+        match status {
+            NodeStatus::Success => self.run_post_condition(PostCond::OnSuccess).await?,
+            NodeStatus::Failure => self.run_post_condition(PostCond::OnFailure).await?,
+            _ => return Ok(()),
+        }
+
+        self.run_post_condition(PostCond::Always).await
+    }
+
+    /// Runs the `_onHalted` script after a running node was halted. A failing
+    /// script is logged, since halting can't return an error.
+    pub async fn check_halted_condition(&self) {
+        if let Err(e) = self.run_post_condition(PostCond::OnHalted).await {
+            log::warn!("[behaviortree_rs]: {}: {e}", self.path);
+        }
+    }
+
+    /// Returns the value of the input port at `port`, with leading and trailing
+    /// whitespace removed before parsing unless the port is declared as raw.
+    /// An empty or all-whitespace value counts as unset.
+    fn input_value(&self, port: &str) -> Option<&str> {
+        let val = self.input_ports.get(port)?;
+
+        match self.is_raw_port(port) {
+            true => Some(val.as_str()),
+            false => Some(val.trim()),
+        }
+    }
+
+    fn is_raw_port(&self, port: &str) -> bool {
+        self.manifest
+            .as_ref()
+            .and_then(|manifest| manifest.ports.get(port))
+            .is_some_and(|port_info| port_info.is_raw())
+    }
+
     /// Returns the value of the input port at the `port` key as a `Result<T, NodeError>`.
+    /// Leading and trailing whitespace of the port value is ignored, unless the
+    /// port is declared with `raw_input_port!`.
+    ///
+    /// A value starting with `eval:` is an expression, which is evaluated with
+    /// blackboard entries as variables, e.g. `"eval: base * 2"`. The result is
+    /// converted to `T`. See the `expression` module for the syntax.
+    ///
     /// The value is `Err` in the following situations:
-    /// - The port wasn't found at that key
-    /// - `T` doesn't match the type of the stored value
-    /// - If a default value is needed (value is empty), couldn't parse default value
+    /// - The port wasn't found at that key (`PortError`)
+    /// - If a default value is needed (value is empty or only whitespace), there's
+    ///   no default (`PortError`) or it couldn't be parsed (`InvalidPortValue`)
     /// - If a remapped key (e.g. a port value of `"{foo}"` references the blackboard
-    /// key `"foo"`), blackboard entry wasn't found or couldn't be read as `T`
-    /// - If port value is a string, couldn't convert it to `T` using `parse_str()`.
+    /// key `"foo"`), blackboard entry wasn't found (`BlackboardError`), is of
+    ///   another type (`BlackboardTypeMismatch`), or it's a string that couldn't
+    ///   be parsed (`InvalidPortValue`)
+    /// - If port value is a string, couldn't convert it to `T` using `parse_str()`
+    ///   (`InvalidPortValue`).
+    ///
+    /// `InvalidPortValue` holds the port name, the string that couldn't be
+    /// parsed and the name of `T`.
     pub async fn get_input<T>(&mut self, port: &str) -> Result<T, NodeError>
     where
         T: FromString + Clone + Send + 'static,
     {
-        match self.input_ports.get(port) {
-            Some(val) => {
-                // Check if default is needed
-                if val.is_empty() {
-                    match self.manifest() {
-                        Ok(manifest) => {
-                            let port_info = manifest.ports.get(port).unwrap();
-                            match port_info.default_value() {
-                                Some(default) => match default.parse_str() {
-                                    Ok(value) => Ok(value),
-                                    Err(_) => Err(NodeError::PortError(String::from(port))),
-                                },
-                                None => Err(NodeError::PortError(String::from(port))),
-                            }
-                        }
-                        Err(_) => Err(NodeError::PortError(String::from(port))),
-                    }
-                } else {
-                    match get_remapped_key(port, val) {
-                        // Value is a Blackboard pointer
-                        Some(key) => match self.blackboard.get::<T>(&key).await {
-                            Some(val) => Ok(val),
-                            None => Err(NodeError::BlackboardError(key)),
-                        },
-                        // Value is just a normal string
-                        None => match <T as FromString>::from_string(val) {
-                            Ok(val) => Ok(val),
-                            Err(_) => Err(NodeError::PortValueParseError(
-                                String::from(port),
-                                format!("{:?}", TypeId::of::<T>()),
-                            )),
-                        },
-                    }
-                }
-            }
-            // Port not found
+        match self.get_input_opt(port).await? {
+            Some(value) => Ok(value),
             None => Err(NodeError::PortError(String::from(port))),
         }
     }
 
+    /// Like `get_input<T>`, but returns `Ok(None)` if the port is unset: it
+    /// wasn't found at that key, or its value is empty or only whitespace and
+    /// there's no default value.
+    pub async fn get_input_opt<T>(&mut self, port: &str) -> Result<Option<T>, NodeError>
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        let val = match self.input_value(port) {
+            Some(val) => val,
+            None => return Ok(None),
+        };
+
+        // Check if default is needed
+        if val.is_empty() {
+            let default = self
+                .manifest
+                .as_ref()
+                .and_then(|manifest| manifest.ports.get(port))
+                .and_then(|port_info| port_info.default_value());
+
+            return match default {
+                Some(default) => match default.parse_str() {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) => Err(NodeError::parse_error::<T>(port, default)),
+                },
+                None => Ok(None),
+            };
+        }
+
+        // Value is an expression, unless the port is raw
+        if let Some(expression) = val.strip_prefix(EXPRESSION_PREFIX) {
+            if !self.is_raw_port(port) {
+                let value = expression::evaluate(expression, &self.blackboard)
+                    .await
+                    .map_err(|e| NodeError::ExpressionError(String::from(port), e))?;
+
+                let value = value.to_string();
+                return match <T as FromString>::from_string(&value) {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) => Err(NodeError::parse_error::<T>(port, value)),
+                };
+            }
+        }
+
+        match get_remapped_key(port, val) {
+            // Value is a Blackboard pointer
+            Some(key) => match self.blackboard.try_get::<T>(&key).await {
+                Ok(val) => Ok(Some(val)),
+                Err(BlackboardError::Missing(key)) => Err(NodeError::BlackboardError(key)),
+                // The entry is a string, but not one that parses as `T`
+                Err(e) => match self.blackboard.get_exact::<String>(&key).await {
+                    Some(string) => Err(NodeError::parse_error::<T>(port, string)),
+                    None => Err(NodeError::BlackboardTypeMismatch(String::from(port), e)),
+                },
+            },
+            // Value is just a normal string
+            None => match <T as FromString>::from_string(val) {
+                Ok(val) => Ok(Some(val)),
+                Err(_) => Err(NodeError::parse_error::<T>(port, val)),
+            },
+        }
+    }
+
+    /// Sync version of `get_input_opt<T>`
+    pub fn get_input_opt_sync<T>(&mut self, port: &str) -> Result<Option<T>, NodeError>
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        futures::executor::block_on(self.get_input_opt(port))
+    }
+
+    /// Like `get_input<T>`, but returns `default` instead of an error. The
+    /// value is resolved the same way, so a port pointing to a blackboard
+    /// entry uses the entry if it can be read as `T`.
+    ///
+    /// `default` is returned silently if the port is unset or points to a
+    /// missing blackboard entry. If the port has a value that can't be used as
+    /// `T`, including a blackboard entry of another type, a warning is logged
+    /// as well.
+    pub async fn get_input_or<T>(&mut self, port: &str, default: T) -> T
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        match self.get_input_opt(port).await {
+            Ok(Some(value)) => value,
+            Ok(None) | Err(NodeError::BlackboardError(_)) => default,
+            Err(e) => {
+                log::warn!("[behaviortree_rs]: {}: {e}", self.path);
+                default
+            }
+        }
+    }
+
+    /// Sync version of `get_input_or<T>`
+    pub fn get_input_or_sync<T>(&mut self, port: &str, default: T) -> T
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        futures::executor::block_on(self.get_input_or(port, default))
+    }
+
     /// Sync version of `get_input<T>`
     ///
     /// Returns the value of the input port at the `port` key as a `Result<T, NodeError>`.
@@ -348,6 +845,190 @@ impl NodeConfig {
         futures::executor::block_on(self.get_input(port))
     }
 
+    /// Returns the value of the input port at `port` as a list of `T`.
+    ///
+    /// If the port points to a blackboard entry that holds a `Vec<T>`, it's
+    /// returned as is. Otherwise the value is read as a string, like with
+    /// `get_input::<String>()`, and split into elements at every `,` or `;`.
+    /// Whitespace around the elements is ignored, as are brackets around the
+    /// whole list, so `"1,2,3"`, `"1; 2; 3"` and `"[1, 2, 3]"` are the same
+    /// list. An empty list, e.g. `"[]"`, gives an empty `Vec`.
+    ///
+    /// Errors are the same as for `get_input<T>`, except that an element
+    /// that can't be parsed as `T` is a `PortElementParseError` holding its
+    /// index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::{basic_types::PortDirection, nodes::NodeConfig, Blackboard};
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// blackboard.set("ids", vec![4u32, 5]).await;
+    ///
+    /// let mut config = NodeConfig::new(blackboard);
+    /// config.add_port(PortDirection::Input, "literal".into(), "1, 2; 3".into());
+    /// config.add_port(PortDirection::Input, "remapped".into(), "{ids}".into());
+    ///
+    /// assert_eq!(config.get_input_vec::<u32>("literal").await.unwrap(), vec![1, 2, 3]);
+    /// assert_eq!(config.get_input_vec::<u32>("remapped").await.unwrap(), vec![4, 5]);
+    /// # })
+    /// ```
+    pub async fn get_input_vec<T>(&mut self, port: &str) -> Result<Vec<T>, NodeError>
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        // The blackboard entry may already hold the list
+        if let Some(key) = self
+            .input_value(port)
+            .and_then(|val| get_remapped_key(port, val))
+        {
+            if let Some(values) = self.blackboard.get_exact::<Vec<T>>(&key).await {
+                return Ok(values);
+            }
+        }
+
+        let list: String = self.get_input(port).await?;
+        let list = list.trim();
+        let list = list
+            .strip_prefix('[')
+            .and_then(|list| list.strip_suffix(']'))
+            .unwrap_or(list);
+
+        if list.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        list.split([',', ';'])
+            .map(str::trim)
+            .enumerate()
+            .map(|(index, element)| {
+                <T as FromString>::from_string(element).map_err(|_| {
+                    NodeError::PortElementParseError(
+                        String::from(port),
+                        index,
+                        String::from(element),
+                        any::type_name::<T>().to_string(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Sync version of `get_input_vec<T>`
+    pub fn get_input_vec_sync<T>(&mut self, port: &str) -> Result<Vec<T>, NodeError>
+    where
+        T: FromString + Clone + Send + 'static,
+    {
+        futures::executor::block_on(self.get_input_vec(port))
+    }
+
+    /// Returns the value of the input port at `port` as a string without
+    /// allocating when possible. Literal port values and default values are
+    /// borrowed from the config; values read through a blackboard pointer
+    /// are copied, since blackboard entries are only reachable behind a lock.
+    /// Literal values are trimmed like in `get_input<T>`. Blackboard values
+    /// that aren't strings are converted like in `Blackboard::get_text()`.
+    ///
+    /// Returns `None` if the port doesn't exist, has no value or default, or
+    /// the blackboard entry is missing or can't be converted to text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use std::borrow::Cow;
+    /// use behaviortree_rs::{basic_types::PortDirection, nodes::NodeConfig, Blackboard};
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// blackboard.set("foo", String::from("from blackboard")).await;
+    ///
+    /// let mut config = NodeConfig::new(blackboard);
+    /// config.add_port(PortDirection::Input, "literal".into(), "hello".into());
+    /// config.add_port(PortDirection::Input, "remapped".into(), "{foo}".into());
+    ///
+    /// assert!(matches!(config.get_input_str("literal").await, Some(Cow::Borrowed("hello"))));
+    /// assert_eq!(config.get_input_str("remapped").await.as_deref(), Some("from blackboard"));
+    /// assert_eq!(config.get_input_str("missing").await, None);
+    /// # })
+    /// ```
+    pub async fn get_input_str(&self, port: &str) -> Option<Cow<'_, str>> {
+        let val = self.input_value(port)?;
+
+        // Check if default is needed
+        if val.is_empty() {
+            let default = self.manifest.as_ref()?.ports.get(port)?.default_value()?;
+            return Some(Cow::Borrowed(default.as_str()));
+        }
+
+        match get_remapped_key(port, val) {
+            // Value is a Blackboard pointer
+            Some(key) => self
+                .blackboard
+                .clone()
+                .get_text(&key)
+                .await
+                .map(Cow::Owned),
+            // Value is just a normal string
+            None => Some(Cow::Borrowed(val)),
+        }
+    }
+
+    /// Sync version of `get_input_str()`
+    ///
+    /// Returns the value of the input port at `port` as a string without
+    /// allocating when possible. Literal port values and default values are
+    /// borrowed from the config; values read through a blackboard pointer
+    /// are copied, since blackboard entries are only reachable behind a lock.
+    pub fn get_input_str_sync(&self, port: &str) -> Option<Cow<'_, str>> {
+        futures::executor::block_on(self.get_input_str(port))
+    }
+
+    /// Returns a reference to the value of the blackboard entry the input port
+    /// at `port` points to, to read a large value without cloning it. See
+    /// `Blackboard::get_ref<T>()`, which this uses.
+    ///
+    /// Returns `None` if the port doesn't exist or isn't a blackboard pointer,
+    /// or the entry is missing or isn't stored as `T`. Strings aren't parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use behaviortree_rs::{basic_types::PortDirection, nodes::NodeConfig, Blackboard};
+    ///
+    /// let mut blackboard = Blackboard::create();
+    /// blackboard.set("cloud", vec![0.5f32; 1000]).await;
+    ///
+    /// let mut config = NodeConfig::new(blackboard);
+    /// config.add_port(PortDirection::Input, "points".into(), "{cloud}".into());
+    ///
+    /// let points = config.get_input_ref::<Vec<f32>>("points").await.unwrap();
+    /// assert_eq!(points.len(), 1000);
+    /// # })
+    /// ```
+    pub async fn get_input_ref<T>(&self, port: &str) -> Option<EntryRef<T>>
+    where
+        T: Any + Send,
+    {
+        let val = self.input_value(port)?;
+        let key = get_remapped_key(port, val)?;
+
+        self.blackboard.clone().get_ref::<T>(&key).await
+    }
+
+    /// Sync version of `get_input_ref<T>()`
+    ///
+    /// Returns a reference to the value of the blackboard entry the input port
+    /// at `port` points to.
+    pub fn get_input_ref_sync<T>(&self, port: &str) -> Option<EntryRef<T>>
+    where
+        T: Any + Send,
+    {
+        futures::executor::block_on(self.get_input_ref(port))
+    }
+
     /// Sets `value` into the blackboard. The key is based on the value provided
     /// to the port at `port`.
     ///
@@ -356,9 +1037,12 @@ impl NodeConfig {
     /// - Port value: `"="`: uses the port name as the blackboard key
     /// - `"foo"` uses `"foo"` as the blackboard key
     /// - `"{foo}"` uses `"foo"` as the blackboard key
+    ///
+    /// Returns `NodeError::BlackboardFull` if the key is new and the
+    /// blackboard's entry limit refuses it, see `Blackboard::set_max_entries()`.
     pub async fn set_output<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
     where
-        T: Clone + Send + 'static,
+        T: Send + 'static,
     {
         match self.output_ports.get(port) {
             Some(port_value) => {
@@ -370,9 +1054,10 @@ impl NodeConfig {
                     },
                 };
 
-                self.blackboard.set(blackboard_key, value).await;
-
-                Ok(())
+                self.blackboard
+                    .try_set(blackboard_key, value)
+                    .await
+                    .map_err(|e| NodeError::BlackboardFull(port.to_string(), e))
             }
             None => Err(NodeError::PortError(port.to_string())),
         }
@@ -390,7 +1075,7 @@ impl NodeConfig {
     /// - `"{foo}"` uses `"foo"` as the blackboard key
     pub async fn set_output_sync<T>(&mut self, port: &str, value: T) -> Result<(), NodeError>
     where
-        T: Clone + Send + 'static,
+        T: Send + 'static,
     {
         futures::executor::block_on(self.set_output(port, value))
     }
@@ -401,3 +1086,52 @@ impl Clone for Box<dyn PortValue> {
         self.clone_port()
     }
 }
+
+// =============================
+// Helper Functions
+// =============================
+
+/// Awaits `future` for at most `duration`, for use inside a node's `tick()`,
+/// e.g. to bound a call to an external service. Returns FAILURE if it doesn't
+/// resolve in time; the future is dropped then.
+///
+/// This bounds a single operation within one tick, unlike the `Timeout`
+/// decorator. Needs a Tokio runtime with the time driver enabled.
+///
+/// # Examples
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use std::time::Duration;
+/// use behaviortree_rs::nodes::{with_timeout, NodeStatus};
+///
+/// let slow = async {
+///     tokio::time::sleep(Duration::from_secs(10)).await;
+///     Ok(NodeStatus::Success)
+/// };
+///
+/// let status = with_timeout(Duration::from_millis(10), slow).await;
+/// assert_eq!(status.unwrap(), NodeStatus::Failure);
+/// # })
+/// ```
+pub async fn with_timeout<F>(duration: Duration, future: F) -> NodeResult
+where
+    F: Future<Output = NodeResult>,
+{
+    match tokio::time::timeout(duration, future).await {
+        Ok(result) => result,
+        Err(_) => Ok(NodeStatus::Failure),
+    }
+}
+
+/// Like `with_timeout()`, but returns `NodeError::Timeout` if `future`
+/// doesn't resolve within `duration`.
+pub async fn with_timeout_error<F>(duration: Duration, future: F) -> NodeResult
+where
+    F: Future<Output = NodeResult>,
+{
+    match tokio::time::timeout(duration, future).await {
+        Ok(result) => result,
+        Err(_) => Err(NodeError::Timeout(duration)),
+    }
+}
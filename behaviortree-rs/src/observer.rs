@@ -0,0 +1,124 @@
+//! Statistics about the statuses returned by the nodes of a tree, like
+//! BehaviorTree.CPP's `TreeObserver`. See `TreeObserver`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    basic_types::NodeStatus,
+    tree::{AsyncTree, SyncTree},
+};
+
+/// What a `TreeObserver` counted for a node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeStatistics {
+    /// Last SUCCESS, FAILURE or SKIPPED returned by the node, or IDLE if it
+    /// hasn't completed yet
+    pub last_result: NodeStatus,
+    /// Last status returned by the node
+    pub current_status: NodeStatus,
+    /// Number of times the node returned a status that differs from the
+    /// previous one
+    pub transitions_count: usize,
+    pub success_count: usize,
+    pub failure_count: usize,
+    pub skip_count: usize,
+}
+
+impl Default for NodeStatistics {
+    fn default() -> Self {
+        Self {
+            last_result: NodeStatus::Idle,
+            current_status: NodeStatus::Idle,
+            transitions_count: 0,
+            success_count: 0,
+            failure_count: 0,
+            skip_count: 0,
+        }
+    }
+}
+
+impl NodeStatistics {
+    fn record(&mut self, status: NodeStatus) {
+        self.transitions_count += 1;
+
+        match status {
+            NodeStatus::Success => self.success_count += 1,
+            NodeStatus::Failure => self.failure_count += 1,
+            NodeStatus::Skipped => self.skip_count += 1,
+            NodeStatus::Idle | NodeStatus::Running => {}
+        }
+        if status != NodeStatus::Running {
+            self.last_result = status.clone();
+        }
+
+        self.current_status = status;
+    }
+}
+
+/// Counts the statuses returned by every node of a tree, e.g. to check in a
+/// test that a branch was taken, or how often a node failed.
+///
+/// The observer adds a status callback to the tree, see
+/// `AsyncTree::add_status_callback()`, which counts for as long as the tree
+/// lives. Nodes are identified by their uids, see `TreeNode::uid()`.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut observer = TreeObserver::new(&mut tree);
+/// tree.tick_while_running().await?;
+///
+/// tree.visit_nodes(|node| {
+///     let statistics = observer.statistics(node.uid()).unwrap_or_default();
+///     println!("{}: {} successes", node.path(), statistics.success_count);
+/// });
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TreeObserver {
+    statistics: Arc<Mutex<HashMap<u16, NodeStatistics>>>,
+}
+
+impl TreeObserver {
+    /// Creates an observer and starts counting the statuses of `tree`.
+    pub fn new(tree: &mut AsyncTree) -> TreeObserver {
+        let observer = TreeObserver::default();
+        tree.add_status_callback(observer.callback());
+
+        observer
+    }
+
+    /// Sync version of `new()`
+    pub fn new_sync(tree: &mut SyncTree) -> TreeObserver {
+        let observer = TreeObserver::default();
+        tree.add_status_callback(observer.callback());
+
+        observer
+    }
+
+    fn callback(&self) -> impl FnMut(u16, NodeStatus, NodeStatus) + Send + 'static {
+        let statistics = self.statistics.clone();
+
+        move |uid, _, status| {
+            statistics
+                .lock()
+                .unwrap()
+                .entry(uid)
+                .or_default()
+                .record(status);
+        }
+    }
+
+    /// Returns the statistics of the node with `uid`, or `None` if it hasn't
+    /// returned a status yet.
+    pub fn statistics(&self, uid: u16) -> Option<NodeStatistics> {
+        self.statistics.lock().unwrap().get(&uid).cloned()
+    }
+
+    /// Forgets the statistics of all nodes.
+    pub fn reset(&self) {
+        self.statistics.lock().unwrap().clear();
+    }
+}
@@ -1,24 +1,44 @@
-use std::{collections::HashMap, io::Cursor, string::FromUtf8Error, sync::Arc};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::{Cursor, Read},
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use futures::future::BoxFuture;
-use log::{debug, info};
+use log::{debug, info, warn};
 use quick_xml::{
-    events::{attributes::Attributes, Event},
+    events::{attributes::Attributes, BytesText, Event},
     name::QName,
-    Reader,
+    Reader, Writer,
 };
 use thiserror::Error;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
+#[cfg(feature = "groot2")]
+use crate::groot2::{Groot2Error, Groot2Publisher};
 use crate::{
     basic_types::{
-        AttrsToMap, FromString, NodeStatus, NodeType, ParseBoolError, PortChecks, PortDirection,
-        PortsRemapping,
+        get_remapped_key, AttrsToMap, FromString, NodeStatus, NodeType, ParseBoolError,
+        PortChecks, PortDirection, PortInfo, PortsList, PortsRemapping, TreeNodeManifest,
+        RESERVED_ATTRIBUTES,
     },
-    blackboard::{Blackboard, BlackboardString},
+    blackboard::{Blackboard, BlackboardString, ValueChange},
+    expression::ExpressionError,
     macros::build_node_ptr,
     nodes::{
-        self, AsyncHalt, NodeConfig,
+        self, AsyncHalt, NodeConfig, PostCond, PreCond,
         NodeResult, TreeNodeBase, TreeNodePtr,
+        action::{
+            InStateNode, SetStateNode, SimpleActionFn, SimpleActionNode, SimpleConditionFn,
+            SimpleConditionNode, TestNodeConfig,
+        },
+        control::{enum_writer, read_enum, MatchNode, RegisteredEnum, SwitchNode},
+        decorator::{SimpleDecoratorFn, SimpleDecoratorNode},
     },
 };
 
@@ -27,6 +47,15 @@ pub enum ParseError {
     #[error("Port name [{0}] did not match Node [{1}] port list: {2:?}")]
     /// `(port_name, node_name, port_list)`
     InvalidPort(String, String, Vec<String>),
+    #[error("Node [{1}] declares port [{0}], which is a reserved attribute name. Reserved names: {2:?}")]
+    /// `(port_name, node_name, reserved_names)`
+    ReservedPortName(String, String, Vec<String>),
+    #[error("Node [{1}]: output port [{0}] must be a blackboard pointer such as {{key}}, found \"{2}\"")]
+    /// `(port_name, node_path, port_value)`
+    InvalidOutputPortValue(String, String, String),
+    #[error("Node [{1}] requires a value for port [{0}]")]
+    /// `(port_name, node_path)`
+    MissingRequiredPort(String, String),
     #[error("Error occurred parsing XML attribute: {0}")]
     AttrError(#[from] quick_xml::events::attributes::AttrError),
     #[error("Error occurred parsing XML: {0}")]
@@ -47,15 +76,202 @@ pub enum ParseError {
     MissingAttribute(String),
     #[error("Can't find tree [{0}]")]
     UnknownTree(String),
-    #[error("Node type [] didn't had invalid presence/absence of children.")]
-    NodeTypeMismatch(String),
+    #[error("Node [{0}]: expected {1} node, found {2} node")]
+    /// `(node_name, expected_type, found_type)`
+    NodeTypeMismatch(String, String, NodeType),
+    #[error("Node [{0}] must have a child")]
+    MissingChild(String),
+    #[error("Tree [{0}] has no root node")]
+    EmptyTree(String),
     #[error("No main tree was provided, either in the XML or as a function parameter.")]
     NoMainTree,
     #[error("{0}")]
     ParseStringError(#[from] ParseBoolError),
+    #[error("Error reading XML: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Couldn't include [{}]: {1}", .0.display())]
+    /// `(path, error)`
+    IncludeError(PathBuf, #[source] std::io::Error),
+    #[error("Node [{1}] uses enum [{0}], which isn't registered. Hint: register it with `Factory::register_enum()`.")]
+    /// `(enum_name, node_path)`
+    UnknownEnum(String, String),
+    #[error("Node [{2}]: [{0}] isn't a variant of enum [{1}]")]
+    /// `(value, enum_name, node_path)`
+    UnknownEnumVariant(String, String, String),
+    #[error("Node [{0}] must have {1} children, found {2}")]
+    /// `(node_path, expected, found)`
+    ChildCountMismatch(String, String, usize),
+    #[error("Port [{1}] of node [{2}] reads blackboard entry [{0}], which no node writes and isn't set. Hint: check the key for typos.")]
+    /// `(key, port_name, node_path)`. Only reported as a warning, see
+    /// `Factory::take_build_warnings()`.
+    DanglingPort(String, String, String),
+    #[error("Node [{1}]: invalid script in [{0}]: {2}")]
+    /// `(attribute, node_path, error)`
+    InvalidScript(String, String, #[source] ExpressionError),
+    #[error("Tree has more than {} nodes, which is the most that can be numbered", u16::MAX - 1)]
+    TooManyNodes,
+    #[error("Node [{2}]: attributes [{0}] and [{1}] can't be used together")]
+    /// `(attribute, other_attribute, node_name)`
+    ConflictingAttributes(String, String, String),
+}
+
+impl ParseError {
+    /// Errors about a single node or subtree. `validate_tree()` records these
+    /// and keeps going, while any other error means the XML can't be read
+    /// any further.
+    fn is_recoverable(&self) -> bool {
+        matches!(
+            self,
+            ParseError::InvalidPort(..)
+                | ParseError::ReservedPortName(..)
+                | ParseError::InvalidOutputPortValue(..)
+                | ParseError::MissingRequiredPort(..)
+                | ParseError::UnknownNode(_)
+                | ParseError::MissingAttribute(_)
+                | ParseError::UnknownTree(_)
+                | ParseError::NodeTypeMismatch(..)
+                | ParseError::MissingChild(_)
+                | ParseError::EmptyTree(_)
+                | ParseError::ParseStringError(_)
+                | ParseError::UnknownEnum(..)
+                | ParseError::UnknownEnumVariant(..)
+                | ParseError::ChildCountMismatch(..)
+                | ParseError::InvalidScript(..)
+                | ParseError::ConflictingAttributes(..)
+        )
+    }
+
+    /// The name of the variant, e.g. `"UnknownNode"`. Stable enough to match
+    /// on in tooling, unlike the message.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParseError::InvalidPort(..) => "InvalidPort",
+            ParseError::ReservedPortName(..) => "ReservedPortName",
+            ParseError::InvalidOutputPortValue(..) => "InvalidOutputPortValue",
+            ParseError::MissingRequiredPort(..) => "MissingRequiredPort",
+            ParseError::AttrError(_) => "AttrError",
+            ParseError::XMLError(_) => "XMLError",
+            ParseError::MissingRoot => "MissingRoot",
+            ParseError::ExpectedRoot(_) => "ExpectedRoot",
+            ParseError::UnexpectedEof => "UnexpectedEof",
+            ParseError::Utf8Error(_) => "Utf8Error",
+            ParseError::UnknownNode(_) => "UnknownNode",
+            ParseError::InternalError(_) => "InternalError",
+            ParseError::MissingAttribute(_) => "MissingAttribute",
+            ParseError::UnknownTree(_) => "UnknownTree",
+            ParseError::NodeTypeMismatch(..) => "NodeTypeMismatch",
+            ParseError::MissingChild(_) => "MissingChild",
+            ParseError::EmptyTree(_) => "EmptyTree",
+            ParseError::NoMainTree => "NoMainTree",
+            ParseError::ParseStringError(_) => "ParseStringError",
+            ParseError::IoError(_) => "IoError",
+            ParseError::IncludeError(..) => "IncludeError",
+            ParseError::UnknownEnum(..) => "UnknownEnum",
+            ParseError::UnknownEnumVariant(..) => "UnknownEnumVariant",
+            ParseError::ChildCountMismatch(..) => "ChildCountMismatch",
+            ParseError::DanglingPort(..) => "DanglingPort",
+            ParseError::InvalidScript(..) => "InvalidScript",
+            ParseError::TooManyNodes => "TooManyNodes",
+            ParseError::ConflictingAttributes(..) => "ConflictingAttributes",
+        }
+    }
+}
+
+/// Serializes as `{ "kind": ..., "message": ..., "fields": { ... } }`, where
+/// `fields` holds the variant's named values, e.g. `port` and `node` for
+/// `InvalidPort`. Wrapped errors from other crates only appear in `message`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ParseError", 3)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("fields", &ParseErrorFields(self))?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ParseErrorFields<'a>(&'a ParseError);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ParseErrorFields<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        match self.0 {
+            ParseError::InvalidPort(port, node, ports) => {
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("node", node)?;
+                map.serialize_entry("ports", ports)?;
+            }
+            ParseError::ReservedPortName(port, node, reserved) => {
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("node", node)?;
+                map.serialize_entry("reserved", reserved)?;
+            }
+            ParseError::InvalidOutputPortValue(port, node, value) => {
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("node", node)?;
+                map.serialize_entry("value", value)?;
+            }
+            ParseError::MissingRequiredPort(port, node) => {
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("node", node)?;
+            }
+            ParseError::ExpectedRoot(tag) => map.serialize_entry("tag", tag)?,
+            ParseError::UnknownNode(node) | ParseError::MissingChild(node) => {
+                map.serialize_entry("node", node)?
+            }
+            ParseError::UnknownTree(tree) | ParseError::EmptyTree(tree) => {
+                map.serialize_entry("tree", tree)?
+            }
+            ParseError::NodeTypeMismatch(node, expected, found) => {
+                map.serialize_entry("node", node)?;
+                map.serialize_entry("expected", expected)?;
+                map.serialize_entry("found", &found.to_string())?;
+            }
+            ParseError::IncludeError(path, _) => map.serialize_entry("path", path)?,
+            ParseError::UnknownEnum(name, node) => {
+                map.serialize_entry("enum", name)?;
+                map.serialize_entry("node", node)?;
+            }
+            ParseError::UnknownEnumVariant(value, name, node) => {
+                map.serialize_entry("value", value)?;
+                map.serialize_entry("enum", name)?;
+                map.serialize_entry("node", node)?;
+            }
+            ParseError::ChildCountMismatch(node, expected, found) => {
+                map.serialize_entry("node", node)?;
+                map.serialize_entry("expected", expected)?;
+                map.serialize_entry("found", found)?;
+            }
+            ParseError::DanglingPort(key, port, node) => {
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("port", port)?;
+                map.serialize_entry("node", node)?;
+            }
+            ParseError::InvalidScript(attribute, node, _) => {
+                map.serialize_entry("attribute", attribute)?;
+                map.serialize_entry("node", node)?;
+            }
+            ParseError::ConflictingAttributes(attribute, other, node) => {
+                map.serialize_entry("attribute", attribute)?;
+                map.serialize_entry("other_attribute", other)?;
+                map.serialize_entry("node", node)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
 }
 
 type NodeCreateFnDyn = dyn Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync;
+type TreeBuiltFnDyn = dyn Fn(&mut AsyncTree) + Send + Sync;
+type BreakpointFnDyn = dyn Fn(&str, NodeStatus) -> bool + Send + Sync;
 
 enum TickOption {
     WhileRunning,
@@ -66,303 +282,2143 @@ enum TickOption {
 #[derive(Debug)]
 pub struct AsyncTree {
     root: TreeNodePtr,
+    /// Blackboard of every `<SubTree>`, with the subtree's path
+    subtree_blackboards: Vec<(String, Blackboard)>,
+    /// Shared with every node, see `WakeConditionRegistry`
+    wake_conditions: WakeConditionRegistry,
+    /// Changes of the keys watched since the last tick started
+    wake_ups: UnboundedReceiver<ValueChange>,
+    /// Shared with every node, see `add_status_callback()`
+    status_callbacks: StatusCallbacks,
+    /// Set by `enable_groot2_publisher()`
+    #[cfg(feature = "groot2")]
+    groot2: Option<Groot2Publisher>,
 }
 
 impl AsyncTree {
     pub fn new(root: TreeNodePtr) -> AsyncTree {
-        Self { root }
+        let (wake_conditions, wake_ups) = WakeConditionRegistry::new();
+        let mut tree = Self {
+            root,
+            subtree_blackboards: Vec::new(),
+            wake_conditions,
+            wake_ups,
+            status_callbacks: StatusCallbacks::default(),
+            #[cfg(feature = "groot2")]
+            groot2: None,
+        };
+
+        let wake_conditions = tree.wake_conditions.clone();
+        let status_callbacks = tree.status_callbacks.clone();
+        tree.visit_nodes_mut(|node| {
+            node.config_mut().wake_conditions = Some(wake_conditions.clone());
+            node.config_mut().status_callbacks = Some(status_callbacks.clone());
+        });
+
+        tree
     }
 
     async fn tick_root(&mut self, opt: TickOption) -> NodeResult {
         let mut status = NodeStatus::Idle;
+        let mut woken_up = false;
 
         while status == NodeStatus::Idle
             || (matches!(opt, TickOption::WhileRunning) && matches!(status, NodeStatus::Running))
+            || (matches!(opt, TickOption::OnceUnlessWokenUp)
+                && matches!(status, NodeStatus::Running)
+                && woken_up)
         {
-            status = self.root.execute_tick().await?;
+            self.wake_ups = self.wake_conditions.reset();
+            status = match self.root.execute_tick().await {
+                Ok(status) => status,
+                Err(e) => {
+                    // Groot2 still shows what happened until the error
+                    #[cfg(feature = "groot2")]
+                    self.publish_statuses();
+                    return Err(e);
+                }
+            };
 
-            // Not implemented: Check for wake-up conditions and tick again if so
+            // A watched key was set after it was watched, during this tick
+            woken_up = self.wake_ups.try_recv().is_ok();
 
-            if status.is_completed() {
+            // A skipped root finished its tick too, so it must be reset to be
+            // ticked again like any other terminal status
+            if status.is_completed() || status == NodeStatus::Skipped {
                 self.root.reset_status();
             }
+
+            #[cfg(feature = "groot2")]
+            self.publish_statuses();
         }
 
         Ok(status)
     }
 
+    /// Lets Groot2 monitor the tree, like BehaviorTree.CPP's `Groot2Publisher`.
+    /// Groot2's requests for the tree and the status of its nodes are
+    /// answered on `port`, and the status changes of every tick are published
+    /// on `port + 1`. Both listen on localhost until the tree is dropped; use
+    /// `enable_groot2_publisher_on()` to listen on another address.
+    ///
+    /// If `port` is 0, a free pair of ports is picked, see `groot2_port()`.
+    ///
+    /// Nodes are identified by their uids. The tree's structure is written
+    /// once, when this is called, so nodes grafted afterwards aren't shown.
+    ///
+    /// Requires the `groot2` feature.
+    #[cfg(feature = "groot2")]
+    pub fn enable_groot2_publisher(&mut self, port: u16) -> Result<(), Groot2Error> {
+        self.enable_groot2_publisher_on(std::net::Ipv4Addr::LOCALHOST.into(), port)
+    }
+
+    /// Like `enable_groot2_publisher()`, but listens on `ip`, e.g.
+    /// `Ipv4Addr::UNSPECIFIED` for all interfaces.
+    ///
+    /// Requires the `groot2` feature.
+    #[cfg(feature = "groot2")]
+    pub fn enable_groot2_publisher_on(
+        &mut self,
+        ip: std::net::IpAddr,
+        port: u16,
+    ) -> Result<(), Groot2Error> {
+        if self.groot2.is_some() {
+            return Err(Groot2Error::AlreadyEnabled);
+        }
+
+        let publisher = Groot2Publisher::new(&self.root, ip, port)?;
+        let hook = publisher.hook();
+        self.visit_nodes_mut(|node| node.config_mut().status_hook = Some(hook.clone()));
+        self.groot2 = Some(publisher);
+
+        Ok(())
+    }
+
+    /// The port Groot2 sends its requests to, if the publisher is enabled.
+    /// The status changes are published on the next port.
+    ///
+    /// Requires the `groot2` feature.
+    #[cfg(feature = "groot2")]
+    pub fn groot2_port(&self) -> Option<u16> {
+        self.groot2.as_ref().map(Groot2Publisher::port)
+    }
+
+    /// Publishes the status changes since the last call to Groot2.
+    #[cfg(feature = "groot2")]
+    fn publish_statuses(&self) {
+        if let Some(publisher) = &self.groot2 {
+            publisher.update(&self.status_report());
+        }
+    }
+
     pub async fn tick_exactly_once(&mut self) -> NodeResult {
         self.tick_root(TickOption::ExactlyOnce).await
     }
 
+    /// Ticks the tree once, and again as long as it's RUNNING and a key that
+    /// a node watches with `NodeConfig::wake_up_on_change()` was set during
+    /// the tick. See `WakeConditionRegistry`.
     pub async fn tick_once(&mut self) -> NodeResult {
         self.tick_root(TickOption::OnceUnlessWokenUp).await
     }
 
+    /// Waits for `duration`, or until a key watched during the last tick is
+    /// set. Returns `true` if it was woken up early. Use it between ticks
+    /// instead of a fixed sleep, so the tree reacts to changes right away.
+    ///
+    /// Needs a Tokio runtime with the time driver enabled.
+    pub async fn sleep(&mut self, duration: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => false,
+            _ = self.wake_ups.recv() => true,
+        }
+    }
+
     pub async fn tick_while_running(&mut self) -> NodeResult {
         self.tick_root(TickOption::WhileRunning).await
     }
 
+    /// Like `tick_once()`, but also returns the keys of the root blackboard
+    /// that were set during the tick, sorted. Setting a key counts even if
+    /// its value didn't change, so an empty list means nothing was written.
+    ///
+    /// Entries local to a subtree aren't included, unless they're remapped
+    /// to the root blackboard.
+    pub async fn tick_once_tracked(
+        &mut self,
+    ) -> Result<(NodeStatus, Vec<String>), nodes::NodeError> {
+        let blackboard = self.root_blackboard().await;
+        let before = blackboard.sequence_ids().await;
+
+        let status = self.tick_once().await?;
+
+        let mut changed: Vec<String> = blackboard
+            .sequence_ids()
+            .await
+            .into_iter()
+            .filter(|(key, sequence_id)| before.get(key) != Some(sequence_id))
+            .map(|(key, _)| key)
+            .collect();
+        changed.sort();
+
+        Ok((status, changed))
+    }
+
     pub async fn root_blackboard(&self) -> Blackboard {
         self.root.config().blackboard.clone()
     }
 
+    /// Returns the blackboard of every scope in the tree together with the
+    /// path of the subtree that introduced it. The root blackboard comes first,
+    /// with an empty path, followed by one entry per `<SubTree>` in pre-order.
+    pub async fn collect_blackboards(&self) -> Vec<(String, Blackboard)> {
+        let mut blackboards = vec![(String::new(), self.root_blackboard().await)];
+        blackboards.extend(self.subtree_blackboards.iter().cloned());
+
+        blackboards
+    }
+
+    /// Halts the whole tree. Every running node's `halt()` has completed,
+    /// including any async cleanup, by the time this returns.
     pub async fn halt_tree(&mut self) {
+        let running = self.root.status() == NodeStatus::Running;
         AsyncHalt::halt(&mut *self.root).await;
+        if running {
+            self.root.config().check_halted_condition().await;
+        }
+        self.root.reset_status();
+
+        #[cfg(feature = "groot2")]
+        self.publish_statuses();
     }
-}
 
-#[derive(Debug)]
-pub struct SyncTree {
-    root: AsyncTree,
-}
+    /// Halts the node at `path` and everything below it, leaving the rest of
+    /// the tree as it is. The node is reset to `Idle`, so its parent will
+    /// start it again on the next tick. If several nodes share the path, the
+    /// first one in pre-order is used. Nothing happens if the node isn't
+    /// running.
+    ///
+    /// Returns an error if there's no node at `path`.
+    pub async fn halt_subtree(&mut self, path: &str) -> Result<(), nodes::NodeError> {
+        let node = find_node_mut(&mut self.root, path).ok_or_else(|| {
+            nodes::NodeError::NodeStructureError(format!("No node at path [{path}]"))
+        })?;
+        halt_running_node(node).await;
 
-impl SyncTree {
-    pub fn new(root: TreeNodePtr) -> SyncTree {
-        Self {
-            root: AsyncTree::new(root),
-        }
+        Ok(())
     }
 
-    pub fn tick_exactly_once(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_exactly_once())
-    }
+    /// Same as `halt_subtree()`, with the node identified by its uid, see
+    /// `TreeNode::uid()`, e.g. to cancel a long running action from outside
+    /// the tree.
+    ///
+    /// Returns an error if there's no node with `uid`.
+    pub async fn halt_node(&mut self, uid: u16) -> Result<(), nodes::NodeError> {
+        let node = find_node_by_uid_mut(&mut self.root, uid).ok_or_else(|| {
+            nodes::NodeError::NodeStructureError(format!("No node with uid [{uid}]"))
+        })?;
+        halt_running_node(node).await;
 
-    pub fn tick_once(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_once())
+        Ok(())
     }
 
-    pub fn tick_while_running(&mut self) -> NodeResult {
-        futures::executor::block_on(self.root.tick_while_running())
-    }
+    /// Attaches `child` to the node at `parent_path`. A control node gets it
+    /// as its last child, while a decorator's child is replaced and returned,
+    /// after halting it if it was running. If several nodes share the path,
+    /// the first one in pre-order is used.
+    ///
+    /// The paths of the grafted nodes are prefixed with `parent_path`. Returns
+    /// an error if there's no node at `parent_path` or it's an action node.
+    pub async fn graft(
+        &mut self,
+        parent_path: &str,
+        mut child: TreeNodePtr,
+    ) -> Result<Option<TreeNodePtr>, nodes::NodeError> {
+        let parent = find_node_mut(&mut self.root, parent_path).ok_or_else(|| {
+            nodes::NodeError::NodeStructureError(format!("No node at path [{parent_path}]"))
+        })?;
+
+        prefix_paths(&mut child, &(parent_path.to_owned() + "/"));
+        let wake_conditions = self.wake_conditions.clone();
+        let status_callbacks = self.status_callbacks.clone();
+        visit_node_mut(&mut child, &mut |node: &mut TreeNodePtr| {
+            node.config_mut().wake_conditions = Some(wake_conditions.clone());
+            node.config_mut().status_callbacks = Some(status_callbacks.clone());
+        });
+
+        let mut replaced = parent.attach_child(child)?;
+        if let Some(old_child) = replaced.as_mut() {
+            if old_child.status() == NodeStatus::Running {
+                AsyncHalt::halt(&mut **old_child).await;
+                old_child.config().check_halted_condition().await;
+            }
+            old_child.reset_status();
+        }
 
-    pub fn root_blackboard(&self) -> Blackboard {
-        futures::executor::block_on(self.root.root_blackboard())
+        Ok(replaced)
     }
 
-    pub async fn halt_tree(&mut self) {
-        futures::executor::block_on(self.root.halt_tree());
+    /// Consumes the tree and returns its root node, e.g. to `graft()` it into
+    /// another tree.
+    pub fn into_root(self) -> TreeNodePtr {
+        self.root
     }
-}
 
-pub struct Factory {
-    node_map: HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)>,
-    blackboard: Blackboard,
-    tree_roots: HashMap<String, Reader<Cursor<Vec<u8>>>>,
-    main_tree_id: Option<String>,
-    // TODO: temporary solution, potentially replace later
-    tree_uid: std::sync::Mutex<u32>,
-}
+    /// Ticks the tree once like `tick_exactly_once()`, pausing whenever
+    /// `should_break` returns `true` for a node that is about to tick or has
+    /// just ticked. It's called with the node's path and its status: the one
+    /// before the tick, then the one the tick returned.
+    ///
+    /// Nothing runs until `resume()` is called on the returned session, which
+    /// returns `TickStep::Paused` at each breakpoint and `TickStep::Completed`
+    /// at the end of the tick. Between calls, the tree stays paused, and the
+    /// blackboard of the node in the `Breakpoint` can be inspected. Dropping
+    /// the session cancels the tick and removes the breakpoints, leaving
+    /// running nodes as they are; call `halt_tree()` afterwards to reset them.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut session = tree.tick_with_breakpoints(|path, _| path.ends_with("MoveBase"));
+    /// while let TickStep::Paused(breakpoint) = session.resume().await {
+    ///     println!("{} is {:?}", breakpoint.path, breakpoint.status);
+    /// }
+    /// ```
+    pub fn tick_with_breakpoints<F>(&mut self, should_break: F) -> BreakpointSession<'_>
+    where
+        F: Fn(&str, NodeStatus) -> bool + Send + Sync + 'static,
+    {
+        let (hits, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let hook = BreakpointHook {
+            should_break: Arc::new(should_break),
+            hits,
+        };
 
-impl Factory {
-    pub fn new() -> Factory {
-        let blackboard = Blackboard::create();
+        let tick = Box::pin(async move {
+            set_breakpoint_hook(&mut self.root, Some(&hook));
+            // Removes the hooks when the tick completes, or when it's dropped
+            // with the session
+            let guard = BreakpointGuard(self);
+            let result = guard.0.tick_exactly_once().await;
 
-        Self {
-            node_map: builtin_nodes(),
-            blackboard,
-            tree_roots: HashMap::new(),
-            main_tree_id: None,
-            tree_uid: std::sync::Mutex::new(0),
+            result
+        });
+
+        BreakpointSession {
+            tick: Some(tick),
+            hits: receiver,
+            paused: None,
         }
     }
 
-    pub fn blackboard(&mut self) -> &Blackboard {
-        &self.blackboard
+    /// Adds a callback that's invoked whenever a node of the tree returns a
+    /// status from its tick that differs from the one it had before the tick.
+    /// It's called with the node's uid, the previous status and the new one,
+    /// like the observers of BehaviorTree.CPP. `observer::TreeObserver` uses
+    /// it to count the results of every node.
+    ///
+    /// Nodes going back to IDLE when they are reset or halted aren't
+    /// reported. Callbacks are called in the order they were added, until
+    /// they're removed with the returned handle.
+    pub fn add_status_callback<F>(&mut self, callback: F) -> StatusCallbackHandle
+    where
+        F: FnMut(u16, NodeStatus, NodeStatus) + Send + 'static,
+    {
+        self.status_callbacks.add(callback)
     }
 
-    pub fn set_blackboard(&mut self, blackboard: Blackboard) {
-        self.blackboard = blackboard;
+    /// Calls `visitor` with every node of the tree in pre-order, i.e. parents
+    /// before their children, which is also the order of their uids.
+    pub fn visit_nodes<F>(&self, mut visitor: F)
+    where
+        F: FnMut(&TreeNodePtr),
+    {
+        visit_node(&self.root, &mut visitor);
     }
 
-    pub fn register_node<F>(&mut self, name: impl AsRef<str>, node_fn: F, node_type: NodeType)
+    /// Calls `visitor` with mutable access to every node of the tree, in the
+    /// same order as `visit_nodes()`. This is the place to initialize nodes
+    /// after the tree is built, using `as_any_mut()` to get the concrete type:
+    ///
+    /// ```ignore
+    /// tree.visit_nodes_mut(|node| {
+    ///     if let Some(action) = node.as_any_mut().downcast_mut::<ActionB>() {
+    ///         action.initialize(69, "interesting value");
+    ///     }
+    /// });
+    /// ```
+    pub fn visit_nodes_mut<F>(&mut self, mut visitor: F)
     where
-        F: Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync + 'static,
+        F: FnMut(&mut TreeNodePtr),
     {
-        self.node_map
-            .insert(name.as_ref().into(), (node_type, Arc::new(node_fn)));
+        visit_node_mut(&mut self.root, &mut visitor);
     }
 
-    fn create_node(
-        &self,
-        node_fn: &Arc<NodeCreateFnDyn>,
-        config: NodeConfig,
-        children: Vec<TreeNodePtr>,
-    ) -> TreeNodePtr {
-        node_fn(config, children)
+    /// Returns the current status of every node, keyed by uid. This is what a
+    /// monitor such as Groot2 needs to show the state of the tree, and can be
+    /// called between ticks.
+    pub fn status_report(&self) -> HashMap<u16, NodeStatus> {
+        let mut report = HashMap::new();
+        self.visit_nodes(|node| {
+            report.insert(node.uid(), node.status());
+        });
+
+        report
     }
+}
 
-    fn get_uid(&self) -> u32 {
-        let uid = *self.tree_uid.lock().unwrap();
-        *self.tree_uid.lock().unwrap() += 1;
+/// When a breakpoint of `AsyncTree::tick_with_breakpoints()` is checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakpointPhase {
+    /// The node is about to tick. The status is the one before the tick.
+    BeforeTick,
+    /// The node has ticked. The status is the one it returned.
+    AfterTick,
+}
+
+/// A node where a `BreakpointSession` paused.
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub path: String,
+    pub status: NodeStatus,
+    pub phase: BreakpointPhase,
+    /// The node's blackboard, to inspect or change entries while paused
+    pub blackboard: Blackboard,
+}
+
+/// Result of `BreakpointSession::resume()`.
+#[derive(Debug)]
+pub enum TickStep {
+    /// The tick is paused at a breakpoint until `resume()` is called again.
+    Paused(Breakpoint),
+    /// The tick finished, with the result of the root node.
+    Completed(NodeResult),
+}
+
+type BreakpointHit = (Breakpoint, tokio::sync::oneshot::Sender<()>);
+
+/// Installed in the `NodeConfig` of every node by
+/// `AsyncTree::tick_with_breakpoints()`.
+#[derive(Clone)]
+pub(crate) struct BreakpointHook {
+    should_break: Arc<BreakpointFnDyn>,
+    hits: tokio::sync::mpsc::UnboundedSender<BreakpointHit>,
+}
 
-        uid
+impl std::fmt::Debug for BreakpointHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BreakpointHook").finish_non_exhaustive()
     }
+}
 
-    async fn recursively_build_subtree(
+impl BreakpointHook {
+    /// Waits until the session resumes if `should_break` matches the node.
+    pub(crate) async fn check(
         &self,
-        tree_id: &String,
-        tree_name: &String,
-        path_prefix: &String,
-        blackboard: Blackboard,
-    ) -> Result<TreeNodePtr, ParseError> {
-        let mut reader = match self.tree_roots.get(tree_id) {
-            Some(root) => root.clone(),
-            None => {
-                return Err(ParseError::UnknownTree(tree_id.clone()));
-            }
-        };
+        config: &NodeConfig,
+        phase: BreakpointPhase,
+        status: &NodeStatus,
+    ) {
+        if !(self.should_break)(&config.path, status.clone()) {
+            return;
+        }
 
-        match self
-            .build_child(&mut reader, &blackboard, tree_name, path_prefix)
-            .await?
-        {
-            Some(child) => Ok(child),
-            None => Err(ParseError::NodeTypeMismatch("SubTree".to_string())),
+        let breakpoint = Breakpoint {
+            path: config.path.clone(),
+            status: status.clone(),
+            phase,
+            blackboard: config.blackboard.clone(),
+        };
+        let (resume, resumed) = tokio::sync::oneshot::channel();
+        // Without a session, there's nobody to resume the tick
+        if self.hits.send((breakpoint, resume)).is_ok() {
+            let _ = resumed.await;
         }
     }
+}
 
-    pub fn create_sync_tree_from_text(
-        &mut self,
-        text: String,
-        blackboard: &Blackboard,
-    ) -> Result<SyncTree, ParseError> {
-        self.register_bt_from_text(text)?;
+type StatusCallbackFnDyn = dyn FnMut(u16, NodeStatus, NodeStatus) + Send;
 
-        if self.tree_roots.len() > 1 && self.main_tree_id.is_none() {
-            Err(ParseError::NoMainTree)
-        } else if self.tree_roots.len() == 1 {
-            // Unwrap is safe because we check that tree_roots.len() == 1
-            let main_tree_id = self.tree_roots.iter().next().unwrap().0.clone();
+#[derive(Default)]
+struct StatusCallbackList {
+    next_id: u64,
+    callbacks: Vec<(u64, Arc<Mutex<StatusCallbackFnDyn>>)>,
+}
 
-            self.instantiate_sync_tree(blackboard, &main_tree_id)
-        } else {
-            // Unwrap is safe here because there are more than 1 root and
-            // self.main_tree_id is Some
-            let main_tree_id = self.main_tree_id.clone().unwrap();
-            self.instantiate_sync_tree(blackboard, &main_tree_id)
+/// The callbacks added with `AsyncTree::add_status_callback()`, shared by
+/// the tree and all its nodes.
+#[derive(Clone, Default)]
+pub(crate) struct StatusCallbacks(Arc<Mutex<StatusCallbackList>>);
+
+impl std::fmt::Debug for StatusCallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusCallbacks").finish_non_exhaustive()
+    }
+}
+
+impl StatusCallbacks {
+    fn add<F>(&self, callback: F) -> StatusCallbackHandle
+    where
+        F: FnMut(u16, NodeStatus, NodeStatus) + Send + 'static,
+    {
+        let mut list = self.0.lock().unwrap();
+        let id = list.next_id;
+        list.next_id += 1;
+        list.callbacks.push((id, Arc::new(Mutex::new(callback))));
+
+        StatusCallbackHandle {
+            id,
+            callbacks: Arc::downgrade(&self.0),
         }
     }
 
-    pub async fn create_async_tree_from_text(
-        &mut self,
-        text: String,
-        blackboard: &Blackboard,
-    ) -> Result<AsyncTree, ParseError> {
-        self.register_bt_from_text(text)?;
+    pub(crate) fn call(&self, uid: u16, previous: NodeStatus, status: NodeStatus) {
+        // Not locked while the callbacks run, so they can add or remove
+        // callbacks themselves
+        let callbacks: Vec<_> = self
+            .0
+            .lock()
+            .unwrap()
+            .callbacks
+            .iter()
+            .map(|(_, callback)| Arc::clone(callback))
+            .collect();
+
+        for callback in callbacks {
+            (callback.lock().unwrap())(uid, previous.clone(), status.clone());
+        }
+    }
+}
 
-        if self.tree_roots.len() > 1 && self.main_tree_id.is_none() {
-            Err(ParseError::NoMainTree)
-        } else if self.tree_roots.len() == 1 {
-            // Unwrap is safe because we check that tree_roots.len() == 1
-            let main_tree_id = self.tree_roots.iter().next().unwrap().0.clone();
+/// Returned by `AsyncTree::add_status_callback()`. Dropping the handle keeps
+/// the callback; call `remove()` to remove it.
+#[derive(Debug)]
+pub struct StatusCallbackHandle {
+    id: u64,
+    callbacks: std::sync::Weak<Mutex<StatusCallbackList>>,
+}
 
-            self.instantiate_async_tree(blackboard, &main_tree_id).await
-        } else {
-            // Unwrap is safe here because there are more than 1 root and
-            // self.main_tree_id is Some
-            let main_tree_id = self.main_tree_id.clone().unwrap();
-            self.instantiate_async_tree(blackboard, &main_tree_id).await
+impl StatusCallbackHandle {
+    /// Removes the callback from its tree. Does nothing if the tree was
+    /// dropped.
+    pub fn remove(self) {
+        if let Some(callbacks) = self.callbacks.upgrade() {
+            let mut list = callbacks.lock().unwrap();
+            list.callbacks.retain(|(id, _)| *id != self.id);
         }
     }
+}
 
-    pub fn instantiate_sync_tree(
-        &mut self,
-        blackboard: &Blackboard,
-        main_tree_id: &str,
-    ) -> Result<SyncTree, ParseError> {
-        // Clone ptr to Blackboard
-        let blackboard = blackboard.clone();
-
-        let main_tree_id = String::from(main_tree_id);
+/// The blackboard keys that wake up an `AsyncTree`, shared by all its nodes.
+///
+/// A node that returns RUNNING while it waits for a blackboard entry can
+/// watch it with `NodeConfig::wake_up_on_change()`. When the entry is set
+/// later in the same tick, `tick_once()` ticks the tree again right away,
+/// and `AsyncTree::sleep()` returns early when it's set between ticks.
+/// Watches last until the next tick starts, so a node that keeps waiting
+/// watches its keys again on every tick.
+#[derive(Clone, Debug)]
+pub struct WakeConditionRegistry {
+    /// Replaced for every tick, which ends the previous watches
+    sender: Arc<std::sync::Mutex<UnboundedSender<ValueChange>>>,
+}
 
-        let root_node = futures::executor::block_on(self.recursively_build_subtree(
-            &main_tree_id,
-            &String::new(),
-            &String::new(),
-            blackboard,
-        ))?;
+impl WakeConditionRegistry {
+    fn new() -> (WakeConditionRegistry, UnboundedReceiver<ValueChange>) {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let registry = WakeConditionRegistry {
+            sender: Arc::new(std::sync::Mutex::new(sender)),
+        };
 
-        Ok(SyncTree::new(root_node))
+        (registry, receiver)
     }
 
-    pub async fn instantiate_async_tree(
-        &mut self,
-        blackboard: &Blackboard,
-        main_tree_id: &str,
-    ) -> Result<AsyncTree, ParseError> {
-        // Clone ptr to Blackboard
-        let blackboard = blackboard.clone();
-
-        let main_tree_id = String::from(main_tree_id);
+    /// Wakes up the tree when `key` is set in `blackboard`.
+    pub async fn watch(&self, blackboard: &Blackboard, key: impl AsRef<str>) {
+        let sender = self.sender.lock().unwrap().clone();
+        blackboard.subscribe_with(key.as_ref(), sender).await;
+    }
 
-        let root_node = self
-            .recursively_build_subtree(&main_tree_id, &String::new(), &String::new(), blackboard)
-            .await?;
+    /// Ends all watches and returns the receiver for the new ones.
+    fn reset(&self) -> UnboundedReceiver<ValueChange> {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        *self.sender.lock().unwrap() = sender;
 
-        Ok(AsyncTree::new(root_node))
+        receiver
     }
+}
 
-    async fn build_leaf_node<'a>(
-        &self,
-        node_name: &String,
-        attributes: Attributes<'a>,
-        config: NodeConfig,
-    ) -> Result<TreeNodePtr, ParseError> {
-        // Get clone of node from node_map based on tag name
-        let (node_type, node_fn) = self
-            .node_map
-            .get(node_name)
-            .ok_or_else(|| ParseError::UnknownNode(node_name.clone()))?;
-        if !matches!(node_type, NodeType::Action) {
-            return Err(ParseError::NodeTypeMismatch(String::from("Action")));
+/// A tick started by `AsyncTree::tick_with_breakpoints()`. It only runs
+/// while `resume()` is awaited.
+pub struct BreakpointSession<'a> {
+    tick: Option<BoxFuture<'a, NodeResult>>,
+    hits: tokio::sync::mpsc::UnboundedReceiver<BreakpointHit>,
+    paused: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl BreakpointSession<'_> {
+    /// Continues the tick until the next breakpoint or the end of the tick.
+    /// The first call starts the tick.
+    ///
+    /// Once the tick has completed, returns `TickStep::Completed` with a
+    /// `NodeError::NodeStructureError`.
+    pub async fn resume(&mut self) -> TickStep {
+        if let Some(resume) = self.paused.take() {
+            let _ = resume.send(());
         }
 
-        let mut node = self.create_node(node_fn, config, Vec::new());
+        let Some(tick) = self.tick.as_mut() else {
+            return TickStep::Completed(Err(nodes::NodeError::NodeStructureError(
+                "The tick has already completed".to_string(),
+            )));
+        };
 
-        self.add_ports_to_node(&mut node, node_name, attributes)
-            .await?;
+        let hit = std::pin::pin!(self.hits.recv());
+        let result = match futures::future::select(tick, hit).await {
+            futures::future::Either::Left((result, _)) => result,
+            futures::future::Either::Right((Some((breakpoint, resume)), _)) => {
+                self.paused = Some(resume);
+                return TickStep::Paused(breakpoint);
+            }
+            // Can't happen while nodes hold the sender, but finish the tick anyway
+            futures::future::Either::Right((None, tick)) => tick.await,
+        };
 
-        Ok(node)
+        self.tick = None;
+        TickStep::Completed(result)
     }
 
-    async fn build_children(
-        &self,
-        reader: &mut Reader<Cursor<Vec<u8>>>,
-        blackboard: &Blackboard,
-        tree_name: &String,
-        path_prefix: &String,
-    ) -> Result<Vec<TreeNodePtr>, ParseError> {
-        let mut nodes = Vec::new();
-
-        while let Some(node) = self
-            .build_child(reader, blackboard, tree_name, path_prefix)
-            .await?
-        {
-            nodes.push(node);
-        }
+    pub fn resume_sync(&mut self) -> TickStep {
+        futures::executor::block_on(self.resume())
+    }
+}
 
-        Ok(nodes)
+impl Drop for BreakpointSession<'_> {
+    /// Cancels the tick if it hasn't completed. Its `BreakpointGuard` then
+    /// removes the breakpoint hooks from the tree.
+    fn drop(&mut self) {
+        self.tick.take();
     }
+}
 
-    async fn add_ports_to_node<'a>(
+/// Holds the tree during a tick of a `BreakpointSession`, and removes the
+/// breakpoint hooks from its nodes when dropped.
+struct BreakpointGuard<'a>(&'a mut AsyncTree);
+
+impl Drop for BreakpointGuard<'_> {
+    fn drop(&mut self) {
+        set_breakpoint_hook(&mut self.0.root, None);
+    }
+}
+
+fn visit_node<F>(node: &TreeNodePtr, visitor: &mut F)
+where
+    F: FnMut(&TreeNodePtr),
+{
+    visitor(node);
+
+    for child in node.child_nodes() {
+        visit_node(child, visitor);
+    }
+}
+
+fn visit_node_mut<F>(node: &mut TreeNodePtr, visitor: &mut F)
+where
+    F: FnMut(&mut TreeNodePtr),
+{
+    visitor(node);
+
+    for child in node.child_nodes_mut() {
+        visit_node_mut(child, visitor);
+    }
+}
+
+fn find_node_mut<'a>(node: &'a mut TreeNodePtr, path: &str) -> Option<&'a mut TreeNodePtr> {
+    if node.path() == path {
+        return Some(node);
+    }
+
+    node.child_nodes_mut()
+        .into_iter()
+        .find_map(|child| find_node_mut(child, path))
+}
+
+fn find_node_by_uid_mut(node: &mut TreeNodePtr, uid: u16) -> Option<&mut TreeNodePtr> {
+    if node.uid() == uid {
+        return Some(node);
+    }
+
+    node.child_nodes_mut()
+        .into_iter()
+        .find_map(|child| find_node_by_uid_mut(child, uid))
+}
+
+/// Halts `node` if it's running, which resets its descendants as well.
+async fn halt_running_node(node: &mut TreeNodePtr) {
+    if node.status() == NodeStatus::Running {
+        AsyncHalt::halt(&mut **node).await;
+        node.config().check_halted_condition().await;
+        node.reset_status();
+    }
+}
+
+fn set_breakpoint_hook(node: &mut TreeNodePtr, hook: Option<&BreakpointHook>) {
+    node.config_mut().breakpoint_hook = hook.cloned();
+
+    for child in node.child_nodes_mut() {
+        set_breakpoint_hook(child, hook);
+    }
+}
+
+fn prefix_paths(node: &mut TreeNodePtr, prefix: &str) {
+    node.config_mut().path = prefix.to_owned() + &node.config().path;
+
+    for child in node.child_nodes_mut() {
+        prefix_paths(child, prefix);
+    }
+}
+
+#[derive(Debug)]
+pub struct SyncTree {
+    root: AsyncTree,
+}
+
+impl SyncTree {
+    pub fn new(root: TreeNodePtr) -> SyncTree {
+        Self {
+            root: AsyncTree::new(root),
+        }
+    }
+
+    pub fn tick_exactly_once(&mut self) -> NodeResult {
+        futures::executor::block_on(self.root.tick_exactly_once())
+    }
+
+    pub fn tick_once(&mut self) -> NodeResult {
+        futures::executor::block_on(self.root.tick_once())
+    }
+
+    pub fn tick_while_running(&mut self) -> NodeResult {
+        futures::executor::block_on(self.root.tick_while_running())
+    }
+
+    pub fn tick_once_tracked(&mut self) -> Result<(NodeStatus, Vec<String>), nodes::NodeError> {
+        futures::executor::block_on(self.root.tick_once_tracked())
+    }
+
+    pub fn root_blackboard(&self) -> Blackboard {
+        futures::executor::block_on(self.root.root_blackboard())
+    }
+
+    pub fn collect_blackboards(&self) -> Vec<(String, Blackboard)> {
+        futures::executor::block_on(self.root.collect_blackboards())
+    }
+
+    pub fn halt_tree(&mut self) {
+        futures::executor::block_on(self.root.halt_tree());
+    }
+
+    pub fn halt_subtree(&mut self, path: &str) -> Result<(), nodes::NodeError> {
+        futures::executor::block_on(self.root.halt_subtree(path))
+    }
+
+    pub fn halt_node(&mut self, uid: u16) -> Result<(), nodes::NodeError> {
+        futures::executor::block_on(self.root.halt_node(uid))
+    }
+
+    /// Same as `AsyncTree::tick_with_breakpoints()`. Use
+    /// `BreakpointSession::resume_sync()` to run the tick.
+    pub fn tick_with_breakpoints<F>(&mut self, should_break: F) -> BreakpointSession<'_>
+    where
+        F: Fn(&str, NodeStatus) -> bool + Send + Sync + 'static,
+    {
+        self.root.tick_with_breakpoints(should_break)
+    }
+
+    pub fn graft(
+        &mut self,
+        parent_path: &str,
+        child: TreeNodePtr,
+    ) -> Result<Option<TreeNodePtr>, nodes::NodeError> {
+        futures::executor::block_on(self.root.graft(parent_path, child))
+    }
+
+    pub fn into_root(self) -> TreeNodePtr {
+        self.root.into_root()
+    }
+
+    pub fn visit_nodes<F>(&self, visitor: F)
+    where
+        F: FnMut(&TreeNodePtr),
+    {
+        self.root.visit_nodes(visitor)
+    }
+
+    pub fn visit_nodes_mut<F>(&mut self, visitor: F)
+    where
+        F: FnMut(&mut TreeNodePtr),
+    {
+        self.root.visit_nodes_mut(visitor)
+    }
+
+    pub fn status_report(&self) -> HashMap<u16, NodeStatus> {
+        self.root.status_report()
+    }
+
+    pub fn add_status_callback<F>(&mut self, callback: F) -> StatusCallbackHandle
+    where
+        F: FnMut(u16, NodeStatus, NodeStatus) + Send + 'static,
+    {
+        self.root.add_status_callback(callback)
+    }
+
+    #[cfg(feature = "groot2")]
+    pub fn enable_groot2_publisher(&mut self, port: u16) -> Result<(), Groot2Error> {
+        self.root.enable_groot2_publisher(port)
+    }
+
+    #[cfg(feature = "groot2")]
+    pub fn enable_groot2_publisher_on(
+        &mut self,
+        ip: std::net::IpAddr,
+        port: u16,
+    ) -> Result<(), Groot2Error> {
+        self.root.enable_groot2_publisher_on(ip, port)
+    }
+
+    #[cfg(feature = "groot2")]
+    pub fn groot2_port(&self) -> Option<u16> {
+        self.root.groot2_port()
+    }
+}
+
+/// Replacement for the nodes matched by a rule added with
+/// `Factory::add_substitution_rule()`.
+#[derive(Clone, Debug)]
+pub enum SubstitutionRule {
+    /// A `TestNode` with this behavior
+    TestNode(TestNodeConfig),
+    /// The registered node with this name
+    Node(String),
+}
+
+/// Returns `true` if `text` matches `pattern`, where `*` matches any
+/// sequence of characters, including none, and `?` matches one character.
+fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text it matched up to
+    let mut star = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more character
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A blackboard entry read by an input port, checked once the tree is built
+struct PortRead {
+    key: String,
+    port: String,
+    path: String,
+    blackboard: Blackboard,
+}
+
+/// The blackboard entries the ports of a tree read and write, collected
+/// while it's built
+#[derive(Default)]
+struct PortUsage {
+    written: HashSet<String>,
+    reads: Vec<PortRead>,
+}
+
+pub struct Factory {
+    node_map: HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)>,
+    blackboard: Blackboard,
+    tree_roots: HashMap<String, Reader<Cursor<Vec<u8>>>>,
+    main_tree_id: Option<String>,
+    subtree_nodes: HashMap<String, (String, PortsList)>,
+    /// Ports of the trees declared as `<SubTree>` in a `<TreeNodesModel>`,
+    /// by tree ID
+    subtree_models: HashMap<String, PortsList>,
+    /// Control nodes that may be written self-closing, i.e. without children
+    empty_control_nodes: HashSet<String>,
+    on_tree_built: Option<Box<TreeBuiltFnDyn>>,
+    /// Build unknown leaf nodes as placeholders that return `unknown_node_status`
+    allow_unknown_nodes: bool,
+    unknown_node_status: NodeStatus,
+    /// Next node uid. Reset for every tree that's instantiated, so the same
+    /// XML always yields the same uids.
+    tree_uid: std::sync::Mutex<u16>,
+    /// Blackboards of the subtrees built for the tree that's being instantiated
+    subtree_blackboards: std::sync::Mutex<Vec<(String, Blackboard)>>,
+    /// Errors collected by `validate_tree()`. `None` when building a tree
+    /// normally, where the first error ends the build.
+    collected_errors: std::sync::Mutex<Option<Vec<ParseError>>>,
+    port_usage: std::sync::Mutex<PortUsage>,
+    /// Warnings about the trees built so far, see `take_build_warnings()`
+    build_warnings: std::sync::Mutex<Vec<String>>,
+    /// Directory that relative `<include>` paths are resolved against
+    include_base_path: PathBuf,
+    /// Enums for `Match` nodes, see `register_enum()`
+    enums: HashMap<String, RegisteredEnum>,
+    /// Ancestor of every tree's blackboard, see `set_global_blackboard()`
+    global_blackboard: Option<Blackboard>,
+    /// Leaf nodes to replace, see `add_substitution_rule()`
+    substitution_rules: Vec<(String, SubstitutionRule)>,
+}
+
+impl Factory {
+    pub fn new() -> Factory {
+        let blackboard = Blackboard::create();
+
+        Self {
+            node_map: builtin_nodes(),
+            blackboard,
+            tree_roots: HashMap::new(),
+            main_tree_id: None,
+            subtree_nodes: HashMap::new(),
+            subtree_models: HashMap::new(),
+            empty_control_nodes: ["Sequence", "Parallel", "ParallelAll"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            on_tree_built: None,
+            allow_unknown_nodes: false,
+            unknown_node_status: NodeStatus::Success,
+            tree_uid: std::sync::Mutex::new(1),
+            subtree_blackboards: std::sync::Mutex::new(Vec::new()),
+            collected_errors: std::sync::Mutex::new(None),
+            port_usage: std::sync::Mutex::new(PortUsage::default()),
+            build_warnings: std::sync::Mutex::new(Vec::new()),
+            include_base_path: PathBuf::new(),
+            enums: HashMap::new(),
+            global_blackboard: None,
+            substitution_rules: Vec::new(),
+        }
+    }
+
+    /// The blackboard that `node_manifests()` creates nodes with. It isn't
+    /// connected to the trees this factory creates: each tree uses the
+    /// blackboard passed when creating it. See `set_global_blackboard()`
+    /// for entries shared by all trees.
+    pub fn blackboard(&mut self) -> &Blackboard {
+        &self.blackboard
+    }
+
+    pub fn set_blackboard(&mut self, blackboard: Blackboard) {
+        self.blackboard = blackboard;
+    }
+
+    /// Sets the global blackboard, which holds the entries shared by all
+    /// trees this factory creates. `@key`, e.g. in a script, reads the root
+    /// of a tree's blackboard, so the global blackboard must be that root:
+    ///
+    /// - Passing the global blackboard itself when creating a tree gives the
+    ///   tree a new blackboard whose parent is the global one.
+    /// - Passing a blackboard that descends from the global one, e.g. from
+    ///   `Blackboard::with_parent()`, uses it as is.
+    /// - Any other blackboard is used as is too, with a warning, since its
+    ///   `@` entries aren't the global ones.
+    pub fn set_global_blackboard(&mut self, blackboard: Blackboard) {
+        self.global_blackboard = Some(blackboard);
+    }
+
+    /// The blackboard set with `set_global_blackboard()`, if any.
+    pub fn global_blackboard(&self) -> Option<&Blackboard> {
+        self.global_blackboard.as_ref()
+    }
+
+    /// Returns the blackboard to build a tree on, given the blackboard passed
+    /// when creating it. See `set_global_blackboard()`.
+    async fn tree_blackboard(&self, blackboard: &Blackboard) -> Blackboard {
+        let Some(global) = &self.global_blackboard else {
+            return blackboard.clone();
+        };
+
+        if blackboard.ptr_eq(global) {
+            Blackboard::with_parent(global).await
+        } else {
+            if !blackboard.root().ptr_eq(global) {
+                warn!(
+                    "The tree's blackboard doesn't descend from the global blackboard, \
+                    so its `@` entries aren't the global ones"
+                );
+            }
+            blackboard.clone()
+        }
+    }
+
+    /// Sets a callback that's invoked with every tree this factory instantiates,
+    /// right after it's built and before it's returned. This is the place to
+    /// attach loggers or monitors to every tree automatically.
+    ///
+    /// The callback gets mutable access to the tree. For `SyncTree`s, it's
+    /// called with the wrapped `AsyncTree`. Setting a new callback replaces
+    /// the previous one.
+    pub fn on_tree_built<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut AsyncTree) + Send + Sync + 'static,
+    {
+        self.on_tree_built = Some(Box::new(callback));
+    }
+
+    fn finish_tree(&self, root_node: TreeNodePtr) -> AsyncTree {
+        let mut tree = AsyncTree::new(root_node);
+        tree.subtree_blackboards = std::mem::take(&mut *self.subtree_blackboards.lock().unwrap());
+
+        if let Some(callback) = &self.on_tree_built {
+            callback(&mut tree);
+        }
+
+        tree
+    }
+
+    pub fn register_node<F>(&mut self, name: impl AsRef<str>, node_fn: F, node_type: NodeType)
+    where
+        F: Fn(NodeConfig, Vec<TreeNodePtr>) -> TreeNodePtr + Send + Sync + 'static,
+    {
+        self.node_map
+            .insert(name.as_ref().into(), (node_type, Arc::new(node_fn)));
+    }
+
+    /// Registers the tree `tree_id` so it can be used as a leaf node named `name`,
+    /// e.g. `<MyBehavior goal="{target}" />` instead of `<SubTree ID="..." goal="{target}" />`.
+    ///
+    /// Attributes of the node are checked against `ports` and passed to the subtree
+    /// like `<SubTree>` attributes. Input ports with a default value that aren't
+    /// set in the XML use that default. The tree itself doesn't have to be registered
+    /// yet; it is only looked up when the node is built.
+    pub fn register_subtree_as_node(
+        &mut self,
+        name: impl AsRef<str>,
+        tree_id: impl AsRef<str>,
+        ports: PortsList,
+    ) {
+        self.subtree_nodes.insert(
+            name.as_ref().to_string(),
+            (tree_id.as_ref().to_string(), ports),
+        );
+    }
+
+    /// Registers an action node named `name` that calls `tick_fn` with its
+    /// `NodeConfig`, without writing a node type for it. The node has the
+    /// given `ports`. See `SimpleActionNode` and the `register_simple_action!`
+    /// macro, which also boxes the future.
+    ///
+    /// ```ignore
+    /// factory.register_simple_action(
+    ///     "SaySomething",
+    ///     |config| Box::pin(async move {
+    ///         let message: String = config.get_input("message").await?;
+    ///         println!("{message}");
+    ///         Ok(NodeStatus::Success)
+    ///     }),
+    ///     define_ports!(input_port!("message")),
+    /// );
+    /// ```
+    pub fn register_simple_action<F>(&mut self, name: impl AsRef<str>, tick_fn: F, ports: PortsList)
+    where
+        F: for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, NodeResult> + Send + Sync + 'static,
+    {
+        let name = name.as_ref().to_string();
+        let tick_fn = SimpleActionFn::new(tick_fn);
+
+        let node_fn = {
+            let name = name.clone();
+            move |config: NodeConfig, _: Vec<TreeNodePtr>| -> TreeNodePtr {
+                build_node_ptr!(config, &name, SimpleActionNode, tick_fn.clone(), ports.clone())
+            }
+        };
+        self.node_map
+            .insert(name, (NodeType::Action, Arc::new(node_fn)));
+    }
+
+    /// Registers a condition node named `name` that calls `tick_fn` with its
+    /// `NodeConfig`, and returns SUCCESS if it returns `true` and FAILURE if it
+    /// returns `false`. The node has the given `ports`. See
+    /// `SimpleConditionNode` and the `register_simple_condition!` macro.
+    ///
+    /// ```ignore
+    /// factory.register_simple_condition(
+    ///     "IsDoorClosed",
+    ///     |config| Box::pin(async move {
+    ///         Ok(config.blackboard_mut().get::<bool>("door_closed").await.unwrap_or(false))
+    ///     }),
+    ///     PortsList::new(),
+    /// );
+    /// ```
+    pub fn register_simple_condition<F>(
+        &mut self,
+        name: impl AsRef<str>,
+        tick_fn: F,
+        ports: PortsList,
+    ) where
+        F: for<'a> Fn(&'a mut NodeConfig) -> BoxFuture<'a, Result<bool, nodes::NodeError>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let name = name.as_ref().to_string();
+        let tick_fn = SimpleConditionFn::new(tick_fn);
+
+        let node_fn = {
+            let name = name.clone();
+            move |config: NodeConfig, _: Vec<TreeNodePtr>| -> TreeNodePtr {
+                build_node_ptr!(
+                    config,
+                    &name,
+                    SimpleConditionNode,
+                    tick_fn.clone(),
+                    ports.clone()
+                )
+            }
+        };
+        self.node_map
+            .insert(name, (NodeType::Condition, Arc::new(node_fn)));
+    }
+
+    /// Registers a decorator node named `name` that ticks its child and
+    /// returns `tick_fn` applied to the child's status. See
+    /// `SimpleDecoratorNode` and the `register_simple_decorator!` macro.
+    ///
+    /// ```ignore
+    /// // Turns a skipped child into a failure
+    /// factory.register_simple_decorator("FailIfSkipped", |status| match status {
+    ///     NodeStatus::Skipped => NodeStatus::Failure,
+    ///     status => status,
+    /// });
+    /// ```
+    pub fn register_simple_decorator<F>(&mut self, name: impl AsRef<str>, tick_fn: F)
+    where
+        F: Fn(NodeStatus) -> NodeStatus + Send + Sync + 'static,
+    {
+        let name = name.as_ref().to_string();
+        let tick_fn = SimpleDecoratorFn::new(tick_fn);
+
+        let node_fn = {
+            let name = name.clone();
+            move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+                let mut node =
+                    build_node_ptr!(config, &name, SimpleDecoratorNode, tick_fn.clone());
+                node.child = Some(children.remove(0));
+                node
+            }
+        };
+        self.node_map
+            .insert(name, (NodeType::Decorator, Arc::new(node_fn)));
+    }
+
+    /// Registers a `Loop` decorator named `name` for queues of `T`, i.e.
+    /// blackboard entries holding a `Vec<T>`, like the builtin `LoopInt` for
+    /// `i64`. See `LoopNode`.
+    pub fn register_loop_node<T>(&mut self, name: impl AsRef<str>)
+    where
+        T: Any + Send,
+    {
+        self.node_map.insert(
+            name.as_ref().to_string(),
+            (NodeType::Decorator, loop_node::<T>(name.as_ref())),
+        );
+    }
+
+    /// Registers the enum `name` with the given variants, so `Match`, `SetState`
+    /// and `InState` nodes can use it with `enum="name"`. A variant's name is its
+    /// `Display` output.
+    ///
+    /// The blackboard entry read by a `Match` or `InState` node can hold a `T`,
+    /// or a `String` with the variant's name. `SetState` writes a `T`.
+    pub fn register_enum<T>(&mut self, name: impl AsRef<str>, variants: impl IntoIterator<Item = T>)
+    where
+        T: Any + Clone + Display + Send + Sync,
+    {
+        let variants: Vec<T> = variants.into_iter().collect();
+        let registered = RegisteredEnum {
+            variants: variants.iter().map(|v| v.to_string()).collect(),
+            read: read_enum::<T>,
+            write: enum_writer(variants),
+        };
+
+        self.enums.insert(name.as_ref().to_string(), registered);
+    }
+
+    /// Replaces the leaf nodes that match `pattern` with `rule` in the trees
+    /// created from now on, e.g. to test a tree without the side effects of
+    /// its real nodes. The pattern is matched against the node's registration
+    /// name, e.g. `MoveBase`, and its path, e.g. `Sequence/MoveBase`. In the
+    /// pattern, `*` matches any characters and `?` matches one.
+    ///
+    /// The replacement keeps the node's path. A `SubstitutionRule::Node` is
+    /// given the original node's attributes, so its ports must accept them,
+    /// while a `TestNode` ignores them. Nodes that don't need to be registered
+    /// can be replaced too. If several rules match, the first one added wins.
+    ///
+    /// ```
+    /// use behaviortree_rs::{
+    ///     basic_types::NodeStatus,
+    ///     nodes::action::TestNodeConfig,
+    ///     tree::{Factory, SubstitutionRule},
+    /// };
+    ///
+    /// let mut factory = Factory::new();
+    /// let failing = TestNodeConfig {
+    ///     statuses: vec![NodeStatus::Failure],
+    ///     ..Default::default()
+    /// };
+    /// factory.add_substitution_rule("Move*", SubstitutionRule::TestNode(failing));
+    /// ```
+    pub fn add_substitution_rule(&mut self, pattern: &str, rule: SubstitutionRule) {
+        self.substitution_rules.push((pattern.to_string(), rule));
+    }
+
+    /// Returns the rule for the leaf node `node_name` at `path`, if any.
+    fn substitution_rule(&self, node_name: &str, path: &str) -> Option<&SubstitutionRule> {
+        self.substitution_rules
+            .iter()
+            .find(|(pattern, _)| {
+                matches_wildcard(pattern, node_name) || matches_wildcard(pattern, path)
+            })
+            .map(|(_, rule)| rule)
+    }
+
+    /// Allows the control node `name` to be written self-closing (`<Name />`),
+    /// building it without children instead of failing. The node's `tick()` must
+    /// handle having no children, e.g. by returning `Success`.
+    ///
+    /// Of the builtin control nodes, `Sequence`, `Parallel` and `ParallelAll`
+    /// allow this; they return `Success` when they have no children.
+    pub fn allow_empty_control_node(&mut self, name: impl AsRef<str>) {
+        self.empty_control_nodes.insert(name.as_ref().to_string());
+    }
+
+    /// Lets trees use leaf nodes that aren't registered. Each one is built as a
+    /// `PlaceholderNode` that logs a warning when it's ticked and returns the
+    /// status set with `set_unknown_node_status()`, SUCCESS by default. Useful
+    /// to run a tree while it's still being developed.
+    ///
+    /// Disabled by default, so unknown nodes are a `ParseError::UnknownNode`.
+    pub fn set_allow_unknown_nodes(&mut self, allow: bool) {
+        self.allow_unknown_nodes = allow;
+    }
+
+    /// Sets the status returned by placeholders for unknown nodes, see
+    /// `set_allow_unknown_nodes()`.
+    pub fn set_unknown_node_status(&mut self, status: NodeStatus) {
+        self.unknown_node_status = status;
+    }
+
+    /// Returns the manifests of all registered node types, including the builtin
+    /// ones and trees registered with `register_subtree_as_node()`, sorted by ID.
+    ///
+    /// A node's manifest is only known once the node exists, so this creates
+    /// (and drops) one instance of every registered node.
+    pub fn node_manifests(&self) -> Vec<TreeNodeManifest> {
+        let mut manifests: Vec<TreeNodeManifest> = self
+            .node_map
+            .iter()
+            .map(|(name, (node_type, node_fn))| {
+                // Decorators expect exactly one child
+                let children = match node_type {
+                    NodeType::Decorator => {
+                        let config = NodeConfig::new(self.blackboard.clone());
+                        let child: TreeNodePtr =
+                            build_node_ptr!(config, "Sequence", nodes::control::SequenceNode);
+                        vec![child]
+                    }
+                    _ => Vec::new(),
+                };
+                let config = NodeConfig::new(self.blackboard.clone());
+                let node = self.create_node(node_fn, config, children);
+
+                match &node.config().manifest {
+                    Some(manifest) => manifest.as_ref().clone(),
+                    None => {
+                        TreeNodeManifest::new(node_type.clone(), name, node.provided_ports(), "")
+                    }
+                }
+            })
+            .collect();
+
+        manifests.extend(self.subtree_nodes.iter().map(|(name, (_, ports))| {
+            TreeNodeManifest::new(NodeType::SubTree, name, ports.clone(), "")
+        }));
+        manifests.sort_by(|a, b| a.registration_id.cmp(&b.registration_id));
+
+        manifests
+    }
+
+    /// Returns the manifests from `node_manifests()` as a pretty-printed JSON
+    /// array, for generating documentation or a node palette. Each entry has the
+    /// node's `type`, `id`, `description` and `ports`.
+    #[cfg(feature = "serde")]
+    pub fn export_node_library_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.node_manifests())
+    }
+
+    /// Returns the `<TreeNodesModel>` of all node types from `node_manifests()`,
+    /// in the format BehaviorTree.CPP writes it, e.g. as a palette file that
+    /// Groot2 can load. Each node is an `<Action>`, `<Control>`, `<Decorator>`
+    /// or `<SubTree>` element with its ports as `<input_port>`, `<output_port>`
+    /// or `<inout_port>` children, including their defaults and types.
+    pub fn write_tree_nodes_model(&self) -> String {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 4);
+
+        writer
+            .create_element("root")
+            .with_attribute(("BTCPP_format", "4"))
+            .write_inner_content(|writer| {
+                writer
+                    .create_element("TreeNodesModel")
+                    .write_inner_content(|writer| {
+                        for manifest in self.node_manifests() {
+                            write_node_model(writer, &manifest)?;
+                        }
+                        Ok(())
+                    })?;
+                Ok(())
+            })
+            .expect("Writing to a Vec can't fail");
+
+        String::from_utf8(writer.into_inner()).expect("The model is written from strings")
+    }
+
+    fn create_node(
+        &self,
+        node_fn: &Arc<NodeCreateFnDyn>,
+        config: NodeConfig,
+        children: Vec<TreeNodePtr>,
+    ) -> TreeNodePtr {
+        node_fn(config, children)
+    }
+
+    /// Sets up `node` if it uses a registered enum, i.e. it's a `Match`,
+    /// `SetState` or `InState` node, and checks the children of a `Switch`
+    /// node. Does nothing for other nodes.
+    fn setup_node(&self, node: &mut TreeNodePtr) -> Result<(), ParseError> {
+        let node = node.as_any_mut();
+
+        if let Some(node) = node.downcast_mut::<MatchNode>() {
+            self.setup_match_node(node)
+        } else if let Some(node) = node.downcast_mut::<SetStateNode>() {
+            node.registered = Some(self.setup_state_node(&node.config, true)?);
+            Ok(())
+        } else if let Some(node) = node.downcast_mut::<InStateNode>() {
+            node.registered = Some(self.setup_state_node(&node.config, false)?);
+            Ok(())
+        } else if let Some(node) = node.downcast_mut::<SwitchNode>() {
+            // One child per case, and the default
+            let children = node.children.len();
+            if children != node.cases + 1 {
+                return Err(ParseError::ChildCountMismatch(
+                    node.config.path.clone(),
+                    (node.cases + 1).to_string(),
+                    children,
+                ));
+            }
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the name of the enum in the "enum" port of the node, and the enum.
+    fn registered_enum<'a>(
+        &self,
+        config: &'a NodeConfig,
+    ) -> Result<(&'a str, &RegisteredEnum), ParseError> {
+        let path = &config.path;
+        let enum_name = config
+            .input_ports
+            .get("enum")
+            .map(|value| value.trim())
+            .ok_or_else(|| ParseError::MissingRequiredPort(String::from("enum"), path.clone()))?;
+        let registered = self
+            .enums
+            .get(enum_name)
+            .ok_or_else(|| ParseError::UnknownEnum(enum_name.to_string(), path.clone()))?;
+
+        Ok((enum_name, registered))
+    }
+
+    /// Checks the "variable" and "state" ports of a `SetState` (`writes`) or
+    /// `InState` node against its enum, and returns the enum.
+    fn setup_state_node(
+        &self,
+        config: &NodeConfig,
+        writes: bool,
+    ) -> Result<RegisteredEnum, ParseError> {
+        let path = &config.path;
+        let port = |name: &str| config.input_ports.get(name).map(|value| value.trim());
+        let (enum_name, registered) = self.registered_enum(config)?;
+
+        let variable = port("variable").unwrap_or_default();
+        let is_literal = get_remapped_key("variable", variable).is_none();
+        if writes && is_literal {
+            return Err(ParseError::InvalidOutputPortValue(
+                String::from("variable"),
+                path.clone(),
+                variable.to_string(),
+            ));
+        }
+
+        // InState accepts several states, separated by ';'. A blackboard
+        // pointer is only checked when ticking.
+        let states = port("state").filter(|state| !state.is_bb_pointer());
+        let states = states.into_iter().flat_map(|states| match writes {
+            true => vec![states],
+            false => states.split(';').map(str::trim).collect(),
+        });
+        let literal = Some(variable).filter(|_| is_literal);
+
+        for value in states.chain(literal) {
+            if registered.index_of(value).is_none() {
+                return Err(ParseError::UnknownEnumVariant(
+                    value.to_string(),
+                    enum_name.to_string(),
+                    path.clone(),
+                ));
+            }
+        }
+
+        Ok(registered.clone())
+    }
+
+    /// Checks the ports and children of a `MatchNode` against its enum, and
+    /// gives the node the enum's cases and reader.
+    fn setup_match_node(&self, node: &mut MatchNode) -> Result<(), ParseError> {
+        let path = node.config.path.clone();
+        let port = |name: &str| node.config.input_ports.get(name).map(|value| value.trim());
+        let (enum_name, registered) = self.registered_enum(&node.config)?;
+
+        let cases: Vec<String> = port("cases")
+            .unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|case| !case.is_empty())
+            .map(String::from)
+            .collect();
+
+        // A literal variable is checked too, a blackboard entry only when ticking
+        let literal = port("variable").filter(|v| get_remapped_key("variable", v).is_none());
+        for value in cases.iter().map(String::as_str).chain(literal) {
+            if !registered.variants.iter().any(|variant| variant == value) {
+                return Err(ParseError::UnknownEnumVariant(
+                    value.to_string(),
+                    enum_name.to_string(),
+                    path,
+                ));
+            }
+        }
+
+        let children = node.children.len();
+        if children != cases.len() && children != cases.len() + 1 {
+            return Err(ParseError::ChildCountMismatch(
+                path,
+                format!("{} or {}", cases.len(), cases.len() + 1),
+                children,
+            ));
+        }
+
+        node.read = Some(registered.read);
+        node.cases = cases;
+
+        Ok(())
+    }
+
+    /// Returns the uid for the next node. Nodes are numbered in pre-order
+    /// (parents before their children), starting at 1. Fails once the uids
+    /// run out, so no two nodes of a tree share one.
+    fn next_uid(&self) -> Result<u16, ParseError> {
+        let mut next_uid = self.tree_uid.lock().unwrap();
+        let uid = *next_uid;
+        *next_uid = uid.checked_add(1).ok_or(ParseError::TooManyNodes)?;
+
+        Ok(uid)
+    }
+
+    /// Resets the state collected while building a tree, before building the next one.
+    fn reset_build_state(&self) {
+        *self.tree_uid.lock().unwrap() = 1;
+        // Drop what's left over from a failed build
+        self.subtree_blackboards.lock().unwrap().clear();
+        *self.port_usage.lock().unwrap() = PortUsage::default();
+    }
+
+    /// Returns the warnings about the trees built since the last call, and
+    /// forgets them. They don't stop a tree from being built, but most likely
+    /// point to a mistake in the XML.
+    ///
+    /// Currently these are `ParseError::DanglingPort`: an input port remapped
+    /// to a blackboard entry, e.g. `goal="{GoalPostion}"`, that isn't set when
+    /// the tree is built and that no node writes, through an output port or
+    /// a script. Nodes that write entries in other ways can declare them with
+    /// `NodePorts::written_keys()`.
+    pub fn take_build_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut *self.build_warnings.lock().unwrap())
+    }
+
+    /// Records the keys `node` writes, and the keys its input ports in
+    /// `reads` read, to check them once the tree is built.
+    fn record_port_usage(&self, node: &TreeNodePtr, reads: Vec<(String, String)>) {
+        let config = node.config();
+        let mut port_usage = self.port_usage.lock().unwrap();
+
+        port_usage.written.extend(node.written_keys());
+        for (port, key) in reads {
+            port_usage.reads.push(PortRead {
+                key,
+                port,
+                path: config.path.clone(),
+                blackboard: config.blackboard.clone(),
+            });
+        }
+    }
+
+    /// Warns about input ports that read a blackboard entry which isn't set
+    /// and which no node writes.
+    async fn check_port_reads(&self) {
+        let PortUsage { written, reads } = std::mem::take(&mut *self.port_usage.lock().unwrap());
+        // Scripts and output ports may name a key with or without the root
+        // blackboard `@` prefix, so both sides are compared without it
+        let written: HashSet<&str> = written
+            .iter()
+            .map(|key| key.strip_prefix('@').unwrap_or(key))
+            .collect();
+
+        for PortRead {
+            key,
+            port,
+            path,
+            mut blackboard,
+        } in reads
+        {
+            let written_key = key.strip_prefix('@').unwrap_or(&key);
+            if written.contains(written_key) || blackboard.entry_type(&key).await.is_some() {
+                continue;
+            }
+
+            let warning = ParseError::DanglingPort(key, port, path).to_string();
+            warn!("{warning}");
+            self.build_warnings.lock().unwrap().push(warning);
+        }
+    }
+
+    /// Records `error` if `validate_tree()` is collecting errors and the build
+    /// can continue after it. Otherwise returns it.
+    fn recover(&self, error: ParseError) -> Result<(), ParseError> {
+        match self.collected_errors.lock().unwrap().as_mut() {
+            Some(errors) if error.is_recoverable() => {
+                errors.push(error);
+                Ok(())
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Stands in for a node that couldn't be built while `validate_tree()`
+    /// collects errors.
+    fn placeholder_node(&self, node_name: &str, blackboard: &Blackboard, path: String) -> TreeNodePtr {
+        let mut config = NodeConfig::new(blackboard.clone());
+        config.path = path;
+
+        let status = self.unknown_node_status.clone();
+        build_node_ptr!(config, node_name, nodes::action::PlaceholderNode, status)
+    }
+
+    /// Builds the children of a node that couldn't be built, so their errors
+    /// are collected too and the reader ends up after the node's end tag.
+    async fn placeholder_with_children(
+        &self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+        blackboard: &Blackboard,
+        tree_name: &String,
+        node_name: &str,
+        path: String,
+    ) -> Result<Option<TreeNodePtr>, ParseError> {
+        self.build_children(reader, blackboard, tree_name, &(path.clone() + "/"))
+            .await?;
+
+        Ok(Some(self.placeholder_node(node_name, blackboard, path)))
+    }
+
+    async fn recursively_build_subtree(
+        &self,
+        tree_id: &String,
+        tree_name: &String,
+        path_prefix: &String,
+        blackboard: Blackboard,
+    ) -> Result<TreeNodePtr, ParseError> {
+        let mut reader = match self.tree_roots.get(tree_id) {
+            Some(root) => root.clone(),
+            None => {
+                return Err(ParseError::UnknownTree(tree_id.clone()));
+            }
+        };
+
+        match self
+            .build_child(&mut reader, &blackboard, tree_name, path_prefix)
+            .await?
+        {
+            Some(child) => Ok(child),
+            None => Err(ParseError::EmptyTree(tree_id.clone())),
+        }
+    }
+
+    /// Sets the tree that `create_*_tree_*()` instantiates when several trees
+    /// are registered, instead of `main_tree_to_execute` in the XML. XML
+    /// registered later that sets `main_tree_to_execute` replaces it.
+    ///
+    /// Returns `ParseError::UnknownTree` if no tree with `id` is registered.
+    pub fn set_main_tree_id(&mut self, id: &str) -> Result<(), ParseError> {
+        if !self.tree_roots.contains_key(id) {
+            return Err(ParseError::UnknownTree(String::from(id)));
+        }
+
+        self.main_tree_id = Some(String::from(id));
+        Ok(())
+    }
+
+    /// Returns the ID of the tree that `create_*_tree_*()` should instantiate:
+    /// the only registered tree, or `main_tree_to_execute` if there are several.
+    fn main_tree_to_create(&self) -> Result<String, ParseError> {
+        if self.tree_roots.len() > 1 && self.main_tree_id.is_none() {
+            Err(ParseError::NoMainTree)
+        } else if self.tree_roots.len() == 1 {
+            // Unwrap is safe because we check that tree_roots.len() == 1
+            Ok(self.tree_roots.iter().next().unwrap().0.clone())
+        } else {
+            // self.main_tree_id is Some here if there are more than 1 root
+            self.main_tree_id.clone().ok_or(ParseError::NoMainTree)
+        }
+    }
+
+    pub fn create_sync_tree_from_text(
+        &mut self,
+        text: String,
+        blackboard: &Blackboard,
+    ) -> Result<SyncTree, ParseError> {
+        self.register_bt_from_text(text)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_sync_tree(blackboard, &main_tree_id)
+    }
+
+    pub async fn create_async_tree_from_text(
+        &mut self,
+        text: String,
+        blackboard: &Blackboard,
+    ) -> Result<AsyncTree, ParseError> {
+        self.register_bt_from_text(text)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_async_tree(blackboard, &main_tree_id).await
+    }
+
+    /// Same as `create_sync_tree_from_text()`, but instantiates the tree
+    /// `main_tree_id`, whatever `main_tree_to_execute` in the XML is. Returns
+    /// `ParseError::UnknownTree` if no tree with that ID is registered.
+    pub fn create_sync_tree_from_text_with_main(
+        &mut self,
+        text: String,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<SyncTree, ParseError> {
+        self.register_bt_from_text(text)?;
+
+        self.instantiate_sync_tree(blackboard, main_tree_id)
+    }
+
+    /// Same as `create_async_tree_from_text()`, but instantiates the tree
+    /// `main_tree_id`, whatever `main_tree_to_execute` in the XML is. Returns
+    /// `ParseError::UnknownTree` if no tree with that ID is registered.
+    pub async fn create_async_tree_from_text_with_main(
+        &mut self,
+        text: String,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<AsyncTree, ParseError> {
+        self.register_bt_from_text(text)?;
+
+        self.instantiate_async_tree(blackboard, main_tree_id).await
+    }
+
+    /// Same as `create_sync_tree_from_text()`, but reads the XML from `reader`.
+    pub fn create_sync_tree_from_reader(
+        &mut self,
+        reader: impl Read,
+        blackboard: &Blackboard,
+    ) -> Result<SyncTree, ParseError> {
+        self.register_bt_from_reader(reader)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_sync_tree(blackboard, &main_tree_id)
+    }
+
+    /// Same as `create_async_tree_from_text()`, but reads the XML from `reader`.
+    pub async fn create_async_tree_from_reader(
+        &mut self,
+        reader: impl Read,
+        blackboard: &Blackboard,
+    ) -> Result<AsyncTree, ParseError> {
+        self.register_bt_from_reader(reader)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_async_tree(blackboard, &main_tree_id).await
+    }
+
+    /// Same as `create_sync_tree_from_text()`, but reads the XML from the file
+    /// at `path`. See `register_bt_from_file()`.
+    pub fn create_sync_tree_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        blackboard: &Blackboard,
+    ) -> Result<SyncTree, ParseError> {
+        self.register_bt_from_file(path)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_sync_tree(blackboard, &main_tree_id)
+    }
+
+    /// Same as `create_async_tree_from_text()`, but reads the XML from the file
+    /// at `path`. See `register_bt_from_file()`.
+    pub async fn create_async_tree_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        blackboard: &Blackboard,
+    ) -> Result<AsyncTree, ParseError> {
+        self.register_bt_from_file(path)?;
+
+        let main_tree_id = self.main_tree_to_create()?;
+        self.instantiate_async_tree(blackboard, &main_tree_id).await
+    }
+
+    /// Registers the trees in `xml` and builds the main tree like
+    /// `create_sync_tree_from_text()`, but keeps going after errors in a
+    /// node or subtree, so all of them can be fixed at once. Returns every
+    /// error found, in the order they were found.
+    ///
+    /// Errors that make the rest of the XML unreadable, such as malformed
+    /// XML, still end the validation. The tree itself is discarded, and the
+    /// trees in `xml` are only registered while it's validated.
+    pub fn validate_tree(&mut self, xml: String) -> Result<(), Vec<ParseError>> {
+        let registered = (
+            self.tree_roots.clone(),
+            self.main_tree_id.clone(),
+            self.subtree_models.clone(),
+        );
+        let result = self.register_and_validate(xml);
+        (self.tree_roots, self.main_tree_id, self.subtree_models) = registered;
+
+        result
+    }
+
+    /// Registers the trees in `xml` and builds the main tree for
+    /// `validate_tree()`, collecting the errors.
+    fn register_and_validate(&mut self, xml: String) -> Result<(), Vec<ParseError>> {
+        self.register_bt_from_text(xml).map_err(|e| vec![e])?;
+        let main_tree_id = self.main_tree_to_create().map_err(|e| vec![e])?;
+
+        self.reset_build_state();
+        *self.collected_errors.lock().unwrap() = Some(Vec::new());
+
+        let result = futures::executor::block_on(self.recursively_build_subtree(
+            &main_tree_id,
+            &String::new(),
+            &String::new(),
+            Blackboard::create(),
+        ));
+
+        let mut errors = self
+            .collected_errors
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_default();
+        if let Err(e) = result {
+            errors.push(e);
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    pub fn instantiate_sync_tree(
+        &mut self,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<SyncTree, ParseError> {
+        let blackboard = futures::executor::block_on(self.tree_blackboard(blackboard));
+
+        let main_tree_id = String::from(main_tree_id);
+
+        self.reset_build_state();
+
+        let root_node = futures::executor::block_on(self.recursively_build_subtree(
+            &main_tree_id,
+            &String::new(),
+            &String::new(),
+            blackboard,
+        ))?;
+        futures::executor::block_on(self.check_port_reads());
+
+        Ok(SyncTree {
+            root: self.finish_tree(root_node),
+        })
+    }
+
+    pub async fn instantiate_async_tree(
+        &mut self,
+        blackboard: &Blackboard,
+        main_tree_id: &str,
+    ) -> Result<AsyncTree, ParseError> {
+        let blackboard = self.tree_blackboard(blackboard).await;
+
+        let main_tree_id = String::from(main_tree_id);
+
+        self.reset_build_state();
+
+        let root_node = self
+            .recursively_build_subtree(&main_tree_id, &String::new(), &String::new(), blackboard)
+            .await?;
+        self.check_port_reads().await;
+
+        Ok(self.finish_tree(root_node))
+    }
+
+    async fn build_leaf_node<'a>(
+        &self,
+        node_name: &String,
+        attributes: Attributes<'a>,
+        config: NodeConfig,
+    ) -> Result<TreeNodePtr, ParseError> {
+        let node_name = match self.substitution_rule(node_name, &config.path) {
+            Some(SubstitutionRule::TestNode(test_config)) => {
+                let test_config = test_config.clone();
+                let mut node: TreeNodePtr =
+                    build_node_ptr!(config, node_name, nodes::action::TestNode, test_config);
+
+                // The attributes are the ports of the replaced node
+                self.add_ports_to_node(&mut node, node_name, attributes, true)
+                    .await?;
+
+                return Ok(node);
+            }
+            Some(SubstitutionRule::Node(replacement)) => replacement,
+            None => node_name,
+        };
+
+        // Get clone of node from node_map based on tag name
+        let (node_type, node_fn) = match self.node_map.get(node_name) {
+            Some(entry) => entry,
+            None if self.allow_unknown_nodes => {
+                warn!("Unknown node [{node_name}] is replaced by a placeholder");
+
+                let status = self.unknown_node_status.clone();
+                return Ok(build_node_ptr!(
+                    config,
+                    node_name,
+                    nodes::action::PlaceholderNode,
+                    status
+                ));
+            }
+            None => return Err(ParseError::UnknownNode(node_name.clone())),
+        };
+        match node_type {
+            NodeType::Action | NodeType::Condition => {}
+            // Control nodes that opted in are built without children
+            NodeType::Control if self.empty_control_nodes.contains(node_name) => {}
+            NodeType::Control | NodeType::Decorator => {
+                return Err(ParseError::MissingChild(node_name.clone()))
+            }
+            _ => {
+                return Err(ParseError::NodeTypeMismatch(
+                    node_name.clone(),
+                    NodeType::Action.to_string(),
+                    node_type.clone(),
+                ))
+            }
+        }
+
+        let mut node = self.create_node(node_fn, config, Vec::new());
+
+        self.add_ports_to_node(&mut node, node_name, attributes, false)
+            .await?;
+        self.setup_node(&mut node)?;
+
+        Ok(node)
+    }
+
+    async fn build_children(
+        &self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+        blackboard: &Blackboard,
+        tree_name: &String,
+        path_prefix: &String,
+    ) -> Result<Vec<TreeNodePtr>, ParseError> {
+        let mut nodes = Vec::new();
+
+        while let Some(node) = self
+            .build_child(reader, blackboard, tree_name, path_prefix)
+            .await?
+        {
+            nodes.push(node);
+        }
+
+        Ok(nodes)
+    }
+
+    /// Sets the port `port_name` of a subtree to `value`: a blackboard pointer
+    /// remaps the port to the parent's entry, anything else is written to the
+    /// subtree's blackboard as a string.
+    async fn set_subtree_port(
+        &self,
+        child_blackboard: &mut Blackboard,
+        port_name: &str,
+        value: &str,
+    ) {
+        // Trimmed like the port values of other nodes
+        let value = value.trim();
+
+        if let Some(parent_key) = value.strip_bb_pointer() {
+            // Either side may be written by the other, so both count as
+            // written when checking the port remapping
+            {
+                let mut port_usage = self.port_usage.lock().unwrap();
+                port_usage.written.insert(port_name.to_string());
+                port_usage.written.insert(parent_key.clone());
+            }
+
+            // Add remapping if `value` is a Blackboard pointer
+            child_blackboard
+                .add_subtree_remapping(port_name.to_string(), parent_key)
+                .await;
+        } else {
+            // Set string value into Blackboard
+            child_blackboard.set(port_name, value.to_string()).await;
+        }
+    }
+
+    /// Builds the tree `id` as a subtree with its own child `Blackboard`,
+    /// using `attributes` as remappings/values for it. The subtree's root is
+    /// wrapped in a `SubTreeNode` named `node_name`, which belongs to the
+    /// parent's scope.
+    ///
+    /// With `_shared="true"`, the subtree uses the parent's `Blackboard`
+    /// instead, so it has no scope of its own: every key is shared without
+    /// remapping. Port attributes and `_isolated="true"` are an error then,
+    /// and `_autoremap` is ignored.
+    ///
+    /// If the tree has a model in `<TreeNodesModel>`, attributes that aren't
+    /// declared ports are an error, and `_autoremap` only remaps the declared
+    /// ports.
+    async fn build_subtree(
+        &self,
+        node_name: &str,
+        id: &String,
+        attributes: &HashMap<String, String>,
+        blackboard: &Blackboard,
+        tree_name: &str,
+    ) -> Result<TreeNodePtr, ParseError> {
+        let uid = self.next_uid()?;
+
+        let shared = match attributes.get("_shared") {
+            Some(value) => <bool as FromString>::from_string(value)?,
+            None => false,
+        };
+        let mut child_blackboard = match shared {
+            true => blackboard.clone(),
+            false => Blackboard::with_parent(blackboard).await,
+        };
+
+        // An isolated subtree only sees parent entries through explicit remapping,
+        // so it ignores `_autoremap`
+        let isolated = match attributes.get("_isolated") {
+            Some(value) => <bool as FromString>::from_string(value)?,
+            None => false,
+        };
+        if shared && isolated {
+            return Err(ParseError::ConflictingAttributes(
+                String::from("_shared"),
+                String::from("_isolated"),
+                node_name.to_string(),
+            ));
+        }
+
+        // A subtree with a model in `<TreeNodesModel>` only has the ports
+        // declared there
+        let model = self.subtree_models.get(id);
+
+        // Process attributes (Ports, special fields, etc)
+        for (attr, value) in attributes.iter() {
+            // Set autoremapping to true or false. With a model, only the
+            // declared ports are remapped, see below.
+            if attr == "_autoremap" {
+                if !isolated && !shared && model.is_none() {
+                    child_blackboard
+                        .enable_auto_remapping(<bool as FromString>::from_string(value)?)
+                        .await;
+                }
+                continue;
+            } else if !attr.is_allowed_port_name() {
+                continue;
+            } else if shared {
+                // Remapping or setting it would change the parent's entries
+                return Err(ParseError::InvalidPort(
+                    attr.clone(),
+                    node_name.to_string(),
+                    Vec::new(),
+                ));
+            }
+
+            if let Some(model) = model {
+                if !model.contains_key(attr) {
+                    let mut ports: Vec<String> = model.keys().cloned().collect();
+                    ports.sort();
+                    return Err(ParseError::InvalidPort(attr.clone(), node_name.to_string(), ports));
+                }
+            }
+
+            self.set_subtree_port(&mut child_blackboard, attr, value).await;
+        }
+
+        // Declared ports that aren't in the attributes use the parent's entry
+        // of the same name with `_autoremap`, or else their default
+        if let (Some(model), false) = (model, shared) {
+            let autoremap = match attributes.get("_autoremap") {
+                Some(value) => !isolated && <bool as FromString>::from_string(value)?,
+                None => false,
+            };
+
+            for (port_name, port_info) in model {
+                if attributes.contains_key(port_name) {
+                    continue;
+                }
+
+                if autoremap {
+                    let parent_key = format!("{{{port_name}}}");
+                    self.set_subtree_port(&mut child_blackboard, port_name, &parent_key)
+                        .await;
+                } else if let Some(default) = port_info.default_value() {
+                    self.set_subtree_port(&mut child_blackboard, port_name, default)
+                        .await;
+                }
+            }
+        }
+
+        let mut subtree_name = tree_name.to_string();
+        if !subtree_name.is_empty() {
+            subtree_name += "/";
+        }
+
+        if let Some(name_attr) = attributes.get("name") {
+            subtree_name += name_attr;
+        } else {
+            subtree_name += &format!("{id}::{uid}");
+        }
+
+        let new_prefix = format!("{subtree_name}/");
+
+        // Recorded before building the subtree so nested subtrees come after it
+        self.subtree_blackboards
+            .lock()
+            .unwrap()
+            .push((subtree_name.clone(), child_blackboard.clone()));
+
+        let root = self
+            .recursively_build_subtree(id, &subtree_name, &new_prefix, child_blackboard)
+            .await?;
+
+        let mut config = NodeConfig::new(blackboard.clone());
+        config.path = subtree_name;
+        config.uid = uid;
+
+        for (attr, value) in attributes.iter() {
+            if let Some(pre_cond) = PreCond::from_attribute(attr) {
+                config.set_pre_condition(pre_cond, value);
+            } else if let Some(post_cond) = PostCond::from_attribute(attr) {
+                if let Err(e) = config.set_post_condition(post_cond, value) {
+                    return Err(ParseError::InvalidScript(attr.clone(), config.path, e));
+                }
+            }
+        }
+
+        let mut node = build_node_ptr!(config, node_name, nodes::decorator::SubTreeNode);
+        node.child = Some(root);
+
+        Ok(node)
+    }
+
+    /// Sets the ports and the pre- and post-conditions of a node from its
+    /// attributes. A `TestNode` standing in for another node gets the ports
+    /// of that node as well, which aren't in its manifest; they're kept as
+    /// input ports instead of being rejected.
+    async fn add_ports_to_node<'a>(
         &self,
         node_ptr: &mut TreeNodePtr,
         node_name: &str,
         attributes: Attributes<'a>,
+        substituted: bool,
     ) -> Result<(), ParseError> {
+        let strict = node_ptr.strict_ports();
         let config = node_ptr.config_mut();
         let manifest = config.manifest()?;
 
+        // Reserved attributes are never forwarded to the node, so a port with
+        // one of those names could never be set
+        if let Some(port_name) = manifest.ports.keys().find(|p| p.is_reserved_attribute()) {
+            return Err(ParseError::ReservedPortName(
+                port_name.clone(),
+                node_name.to_owned(),
+                RESERVED_ATTRIBUTES.iter().map(|s| s.to_string()).collect(),
+            ));
+        }
+
         let mut remap = PortsRemapping::new();
 
         for (port_name, port_value) in attributes.to_map()? {
+            if let Some(pre_cond) = PreCond::from_attribute(&port_name) {
+                config.set_pre_condition(pre_cond, port_value);
+                continue;
+            } else if let Some(post_cond) = PostCond::from_attribute(&port_name) {
+                let path = config.path.clone();
+                let script = config
+                    .set_post_condition(post_cond, port_value)
+                    .map_err(|e| ParseError::InvalidScript(port_name, path, e))?;
+                let targets = script.targets().into_iter().map(String::from);
+                self.port_usage.lock().unwrap().written.extend(targets);
+                continue;
+            } else if port_name.is_reserved_attribute() {
+                continue;
+            }
+
             remap.insert(port_name, port_value);
         }
 
         // Check if all ports from XML match ports in manifest
         for port_name in remap.keys() {
-            if !manifest.ports.contains_key(port_name) {
+            if !substituted && !manifest.ports.contains_key(port_name) {
                 return Err(ParseError::InvalidPort(
                     port_name.clone(),
                     node_name.to_owned(),
@@ -371,29 +2427,95 @@ impl Factory {
             }
         }
 
+        // Output ports can only write to the blackboard, so they need a pointer
+        // (or "=" to use the port name as key)
+        for (port_name, port_value) in remap.iter() {
+            let is_output = manifest
+                .ports
+                .get(port_name)
+                .is_some_and(|port| matches!(port.direction(), PortDirection::Output));
+
+            let port_value = port_value.trim();
+            if is_output && !port_value.is_bb_pointer() && port_value != "=" {
+                return Err(ParseError::InvalidOutputPortValue(
+                    port_name.clone(),
+                    config.path.clone(),
+                    port_value.to_string(),
+                ));
+            }
+        }
+
+        // Blackboard entries the ports read and write, checked once the tree
+        // is built
+        let mut reads = Vec::new();
+        for (port_name, port_value) in remap.iter() {
+            let port_value = port_value.trim();
+            let key = match port_value {
+                "=" => port_name.clone(),
+                _ => match port_value.strip_bb_pointer() {
+                    Some(key) => key,
+                    None => continue,
+                },
+            };
+
+            match manifest.ports.get(port_name).map(|port| port.direction()) {
+                Some(PortDirection::Input) => reads.push((port_name.clone(), key)),
+                Some(_) => {
+                    self.port_usage.lock().unwrap().written.insert(key);
+                }
+                // A port of the node a `TestNode` replaces, which isn't used
+                None => {}
+            }
+        }
+
         // Add ports to NodeConfig
         for (remap_name, remap_val) in remap {
-            if let Some(port) = manifest.ports.get(&remap_name) {
-                config.add_port(port.direction().clone(), remap_name, remap_val);
+            match manifest.ports.get(&remap_name) {
+                Some(port) => config.add_port(port.direction().clone(), remap_name, remap_val),
+                None => config.add_port(PortDirection::Input, remap_name, remap_val),
             }
         }
 
-        // Try to use defaults for unspecified port values
-        for (port_name, port_info) in manifest.ports.iter() {
-            let direction = port_info.direction();
-
-            if !matches!(direction, PortDirection::Output)
-                && !config.has_port(direction, port_name)
-                && port_info.default_value().is_some()
-            {
-                config.add_port(
-                    PortDirection::Input,
-                    port_name.clone(),
-                    port_info.default_value_str().unwrap(),
-                );
+        // Strict nodes need a value for every input port in the XML
+        if strict {
+            let mut missing: Vec<&String> = manifest
+                .ports
+                .iter()
+                .filter(|(port_name, port_info)| {
+                    let value = config.input_ports.get(*port_name);
+                    matches!(port_info.direction(), PortDirection::Input)
+                        && value.is_none_or(|value| value.trim().is_empty())
+                })
+                .map(|(port_name, _)| port_name)
+                .collect();
+            missing.sort();
+
+            if let Some(port_name) = missing.first() {
+                return Err(ParseError::MissingRequiredPort(
+                    port_name.to_string(),
+                    config.path.clone(),
+                ));
+            }
+        } else {
+            // Try to use defaults for unspecified port values
+            for (port_name, port_info) in manifest.ports.iter() {
+                let direction = port_info.direction();
+
+                if !matches!(direction, PortDirection::Output)
+                    && !config.has_port(direction, port_name)
+                    && port_info.default_value().is_some()
+                {
+                    config.add_port(
+                        PortDirection::Input,
+                        port_name.clone(),
+                        port_info.default_value_str().unwrap(),
+                    );
+                }
             }
         }
 
+        self.record_port_usage(node_ptr, reads);
+
         Ok(())
     }
 
@@ -422,11 +2544,24 @@ impl Factory {
 
                     let mut config = NodeConfig::new(blackboard.clone());
                     config.path = path_prefix.to_owned() + &node_name;
-
-                    let (node_type, node_fn) = self
-                        .node_map
-                        .get(&node_name)
-                        .ok_or_else(|| ParseError::UnknownNode(node_name.clone()))?;
+                    // Assigned before building the children to number in pre-order
+                    config.uid = self.next_uid()?;
+
+                    let (node_type, node_fn) = match self.node_map.get(&node_name) {
+                        Some(entry) => entry,
+                        None => {
+                            self.recover(ParseError::UnknownNode(node_name.clone()))?;
+                            return self
+                                .placeholder_with_children(
+                                    reader,
+                                    blackboard,
+                                    tree_name,
+                                    &node_name,
+                                    config.path,
+                                )
+                                .await;
+                        }
+                    };
 
                     let node = match node_type {
                         NodeType::Control => {
@@ -441,8 +2576,11 @@ impl Factory {
 
                             let mut node = self.create_node(node_fn, config, children);
 
-                            self.add_ports_to_node(&mut node, &node_name, attributes)
-                                .await?;
+                            self.add_ports_to_node(&mut node, &node_name, attributes, false)
+                                .await
+                                .or_else(|e| self.recover(e))?;
+                            self.setup_node(&mut node)
+                                .or_else(|e| self.recover(e))?;
 
                             node
                         }
@@ -458,16 +2596,20 @@ impl Factory {
                             {
                                 Some(node) => node,
                                 None => {
-                                    return Err(ParseError::NodeTypeMismatch(
-                                        "Decorator".to_string(),
-                                    ));
+                                    self.recover(ParseError::MissingChild(node_name.clone()))?;
+                                    return Ok(Some(self.placeholder_node(
+                                        &node_name,
+                                        blackboard,
+                                        config.path,
+                                    )));
                                 }
                             };
 
                             let mut node = self.create_node(node_fn, config, vec![child]);
 
-                            self.add_ports_to_node(&mut node, &node_name, attributes)
-                                .await?;
+                            self.add_ports_to_node(&mut node, &node_name, attributes, false)
+                                .await
+                                .or_else(|e| self.recover(e))?;
 
                             // Advance pointer one time to skip the end tag
                             let mut buf = Vec::new();
@@ -476,7 +2618,22 @@ impl Factory {
                             node
                         }
                         // TODO: expand more
-                        x => return Err(ParseError::NodeTypeMismatch(format!("{x:?}"))),
+                        x => {
+                            self.recover(ParseError::NodeTypeMismatch(
+                                node_name.clone(),
+                                format!("{} or {}", NodeType::Control, NodeType::Decorator),
+                                x.clone(),
+                            ))?;
+                            return self
+                                .placeholder_with_children(
+                                    reader,
+                                    blackboard,
+                                    tree_name,
+                                    &node_name,
+                                    config.path,
+                                )
+                                .await;
+                        }
                     };
 
                     Some(node)
@@ -490,66 +2647,67 @@ impl Factory {
                     let mut config = NodeConfig::new(blackboard.clone());
                     config.path = path_prefix.to_owned() + &node_name;
 
-                    let node = match node_name.as_str() {
+                    let result = match node_name.as_str() {
                         "SubTree" => {
                             let attributes = attributes.to_map()?;
-                            let mut child_blackboard = Blackboard::with_parent(blackboard).await;
-
-                            // Process attributes (Ports, special fields, etc)
-                            for (attr, value) in attributes.iter() {
-                                // Set autoremapping to true or false
-                                if attr == "_autoremap" {
-                                    child_blackboard
-                                        .enable_auto_remapping(<bool as FromString>::from_string(
-                                            value,
-                                        )?)
-                                        .await;
-                                    continue;
-                                } else if !attr.is_allowed_port_name() {
-                                    continue;
-                                }
 
-                                if let Some(port_name) = value.strip_bb_pointer() {
-                                    // Add remapping if `value` is a Blackboard pointer
-                                    child_blackboard
-                                        .add_subtree_remapping(attr.clone(), port_name)
-                                        .await;
-                                } else {
-                                    // Set string value into Blackboard
-                                    child_blackboard.set(attr, value.clone()).await;
+                            match attributes.get("ID") {
+                                Some(id) => {
+                                    self.build_subtree(&node_name, id, &attributes, blackboard, tree_name)
+                                        .await
                                 }
+                                None => Err(ParseError::MissingAttribute("ID".to_string())),
                             }
-
-                            let id = match attributes.get("ID") {
-                                Some(id) => id,
-                                None => return Err(ParseError::MissingAttribute("ID".to_string())),
-                            };
-
-                            let mut subtree_name = tree_name.clone();
-                            if !subtree_name.is_empty() {
-                                subtree_name += "/";
+                        }
+                        _ if self.subtree_nodes.contains_key(&node_name) => {
+                            // Safe to unwrap because .contains_key() is true
+                            let (id, ports) = self.subtree_nodes.get(&node_name).unwrap();
+                            let mut attributes = attributes.to_map()?;
+
+                            // Check if all ports from XML match the declared ports
+                            for port_name in attributes.keys() {
+                                if !port_name.is_reserved_attribute()
+                                    && !ports.contains_key(port_name)
+                                {
+                                    self.recover(ParseError::InvalidPort(
+                                        port_name.clone(),
+                                        node_name.clone(),
+                                        ports.keys().cloned().collect(),
+                                    ))?;
+                                }
                             }
 
-                            if let Some(name_attr) = attributes.get("name") {
-                                subtree_name += name_attr;
-                            } else {
-                                subtree_name += &format!("{id}::{}", self.get_uid());
+                            // Use defaults for unspecified input ports
+                            for (port_name, port_info) in ports.iter() {
+                                if !matches!(port_info.direction(), PortDirection::Output)
+                                    && !attributes.contains_key(port_name)
+                                {
+                                    if let Some(default) = port_info.default_value_str() {
+                                        attributes.insert(port_name.clone(), default);
+                                    }
+                                }
                             }
 
-                            let new_prefix = format!("{subtree_name}/");
-
-                            self.recursively_build_subtree(
-                                id,
-                                &subtree_name,
-                                &new_prefix,
-                                child_blackboard,
-                            )
-                            .await?
+                            self.build_subtree(&node_name, id, &attributes, blackboard, tree_name)
+                                .await
+                        }
+                        _ => {
+                            config.uid = self.next_uid()?;
+                            self.build_leaf_node(&node_name, attributes, config).await
                         }
-                        _ => self.build_leaf_node(&node_name, attributes, config).await?,
                     };
 
-                    Some(node)
+                    match result {
+                        Ok(node) => Some(node),
+                        Err(e) => {
+                            self.recover(e)?;
+                            Some(self.placeholder_node(
+                                &node_name,
+                                blackboard,
+                                path_prefix.to_owned() + &node_name,
+                            ))
+                        }
+                    }
                 }
                 Event::End(_e) => None,
                 e => {
@@ -567,13 +2725,100 @@ impl Factory {
     }
 
     pub fn register_bt_from_text(&mut self, xml: String) -> Result<(), ParseError> {
-        let mut reader = Reader::from_reader(Cursor::new(xml.as_bytes().to_vec()));
+        self.register_bt_from_bytes(xml.into_bytes())
+    }
+
+    /// Reads all of `reader` and registers the trees in it, like
+    /// `register_bt_from_text()`. Invalid UTF-8 is reported when it's parsed.
+    pub fn register_bt_from_reader(&mut self, mut reader: impl Read) -> Result<(), ParseError> {
+        let mut xml = Vec::new();
+        reader.read_to_end(&mut xml)?;
+
+        self.register_bt_from_bytes(xml)
+    }
+
+    /// Reads the file at `path` and registers the trees in it, like
+    /// `register_bt_from_text()`. Relative paths of `<include path="..." />`
+    /// tags in the file are resolved against the file's directory.
+    ///
+    /// Returns `ParseError::IoError` if the file can't be read.
+    pub fn register_bt_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), ParseError> {
+        let path = path.as_ref();
+        let xml = std::fs::read(path)?;
+
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.register_bt_with_includes(xml, &dir, &mut Vec::new())
+    }
+
+    /// Sets the directory that relative paths of `<include path="..." />`
+    /// tags are resolved against, for XML registered from text or a reader.
+    /// Defaults to the current working directory.
+    ///
+    /// Paths in an included file are resolved against that file's directory.
+    pub fn set_include_base_path(&mut self, path: impl Into<PathBuf>) {
+        self.include_base_path = path.into();
+    }
+
+    fn register_bt_from_bytes(&mut self, xml: Vec<u8>) -> Result<(), ParseError> {
+        let base_path = self.include_base_path.clone();
+        self.register_bt_with_includes(xml, &base_path, &mut Vec::new())
+    }
+
+    /// Registers the trees of the file at `path`, resolved against `dir`.
+    /// `chain` holds the files that are being included, to detect cycles.
+    fn register_include(
+        &mut self,
+        path: &str,
+        dir: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        let path = dir.join(path);
+        let include_error = |e| ParseError::IncludeError(path.clone(), e);
+
+        let path = path.canonicalize().map_err(include_error)?;
+        if chain.contains(&path) {
+            return Err(include_error(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "circular include",
+            )));
+        }
+        let xml = std::fs::read(&path).map_err(include_error)?;
+
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        chain.push(path);
+        let result = self.register_bt_with_includes(xml, &dir, chain);
+        chain.pop();
+
+        result
+    }
+
+    fn register_include_tag(
+        &mut self,
+        attributes: &HashMap<String, String>,
+        dir: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        match attributes.get("path") {
+            Some(path) => self.register_include(path, dir, chain),
+            None => Err(ParseError::MissingAttribute(
+                "Found include without path. Cannot continue parsing.".to_string(),
+            )),
+        }
+    }
+
+    /// Registers the trees in `xml` and in the files it includes, which are
+    /// resolved against `dir`. Only the outermost XML can set the main tree.
+    fn register_bt_with_includes(
+        &mut self,
+        xml: Vec<u8>,
+        dir: &Path,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<(), ParseError> {
+        let mut reader = Reader::from_reader(Cursor::new(xml));
         reader.trim_text(true);
 
         let mut buf = Vec::new();
 
-        // TODO: Check includes
-
         // TODO: Parse for correctness
 
         loop {
@@ -591,8 +2836,10 @@ impl Factory {
                     }
 
                     if let Some(tree_id) = attributes.get("main_tree_to_execute") {
-                        info!("Found main tree ID: {tree_id}");
-                        self.main_tree_id = Some(tree_id.clone());
+                        if chain.is_empty() {
+                            info!("Found main tree ID: {tree_id}");
+                            self.main_tree_id = Some(tree_id.clone());
+                        }
                     }
 
                     buf.clear();
@@ -617,10 +2864,11 @@ impl Factory {
                     let end_name = end.name().as_ref().to_vec().clone();
                     let end_name = QName(end_name.as_slice());
 
-                    // TODO: Maybe do something with TreeNodesModel?
-                    // For now, just ignore it
                     if name.as_str() == "TreeNodesModel" {
+                        self.register_tree_nodes_model(&mut reader)?;
+                    } else if name.as_str() == "include" {
                         reader.read_to_end_into(end_name, &mut buf)?;
+                        self.register_include_tag(&attributes, dir, chain)?;
                     } else {
                         // Add error for missing BT
                         if name.as_str() != "BehaviorTree" {
@@ -637,6 +2885,10 @@ impl Factory {
                         reader.read_to_end_into(end_name, &mut buf)?;
                     }
                 }
+                Event::Empty(e) if e.name().as_ref() == b"include" => {
+                    let attributes = e.attributes().to_map()?;
+                    self.register_include_tag(&attributes, dir, chain)?;
+                }
                 Event::End(e) => {
                     let name = String::from_utf8(e.name().0.into())?;
                     if name != "root" {
@@ -657,6 +2909,93 @@ impl Factory {
 
         Ok(())
     }
+
+    /// Reads a `<TreeNodesModel>` up to its end tag. Only the `<SubTree>`
+    /// models are kept, with their ports:
+    ///
+    /// ```xml
+    /// <TreeNodesModel>
+    ///     <SubTree ID="MoveRobot">
+    ///         <input_port name="target" />
+    ///         <output_port name="result" default="{move_result}" />
+    ///     </SubTree>
+    /// </TreeNodesModel>
+    /// ```
+    ///
+    /// The models of other node types are ignored, since the registered
+    /// nodes declare their own ports.
+    fn register_tree_nodes_model(
+        &mut self,
+        reader: &mut Reader<Cursor<Vec<u8>>>,
+    ) -> Result<(), ParseError> {
+        let mut buf = Vec::new();
+        // The `<SubTree>` being read
+        let mut subtree: Option<(String, PortsList)> = None;
+
+        loop {
+            let (e, is_empty) = match reader.read_event_into(&mut buf)? {
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                Event::End(e) => {
+                    match e.name().as_ref() {
+                        b"TreeNodesModel" => return Ok(()),
+                        b"SubTree" => {
+                            if let Some((id, ports)) = subtree.take() {
+                                self.subtree_models.insert(id, ports);
+                            }
+                        }
+                        _ => {}
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Event::Eof => return Err(ParseError::UnexpectedEof),
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+
+            let name = String::from_utf8(e.name().0.into())?;
+            let attributes = e.attributes().to_map()?;
+            let direction = match name.as_str() {
+                "input_port" => Some(PortDirection::Input),
+                "output_port" => Some(PortDirection::Output),
+                "inout_port" => Some(PortDirection::InOut),
+                _ => None,
+            };
+
+            match (name.as_str(), direction, subtree.as_mut()) {
+                ("SubTree", _, _) => {
+                    let id = attributes.get("ID").cloned().ok_or_else(|| {
+                        ParseError::MissingAttribute("Found SubTree model without ID".to_string())
+                    })?;
+                    match is_empty {
+                        true => {
+                            self.subtree_models.insert(id, PortsList::new());
+                        }
+                        false => subtree = Some((id, PortsList::new())),
+                    }
+                }
+                (_, Some(direction), Some((id, ports))) => {
+                    let port_name = attributes.get("name").cloned().ok_or_else(|| {
+                        ParseError::MissingAttribute(format!(
+                            "Found port without name in the model of SubTree [{id}]"
+                        ))
+                    })?;
+
+                    let mut port_info = PortInfo::new(direction);
+                    if let Some(default) = attributes.get("default") {
+                        port_info.set_default(default.clone());
+                    }
+                    ports.insert(port_name, port_info);
+                }
+                _ => {}
+            }
+
+            buf.clear();
+        }
+    }
 }
 
 impl Default for Factory {
@@ -665,6 +3004,145 @@ impl Default for Factory {
     }
 }
 
+/// Sets up a `Factory` and builds a tree from XML in a single expression.
+///
+/// Nodes are registered with the usual `register_*_node!` macros, called
+/// on the factory passed to `register()`:
+///
+/// ```ignore
+/// let mut tree = TreeBuilder::new()
+///     .register(|factory| register_action_node!(factory, "SaySomething", SaySomething))
+///     .register(|factory| register_action_node!(factory, "Counter", Counter, 10u32))
+///     .xml(XML)
+///     .build_sync(&blackboard)?;
+/// ```
+pub struct TreeBuilder {
+    factory: Factory,
+    xml: Vec<String>,
+}
+
+impl TreeBuilder {
+    pub fn new() -> TreeBuilder {
+        Self::with_factory(Factory::new())
+    }
+
+    /// Starts from an existing factory, keeping its registered nodes and trees.
+    pub fn with_factory(factory: Factory) -> TreeBuilder {
+        Self {
+            factory,
+            xml: Vec::new(),
+        }
+    }
+
+    /// Calls `register` with the factory, e.g. to register nodes with the
+    /// `register_*_node!` macros.
+    pub fn register<F>(mut self, register: F) -> TreeBuilder
+    where
+        F: FnOnce(&mut Factory),
+    {
+        register(&mut self.factory);
+        self
+    }
+
+    /// Adds XML with tree definitions. Can be called several times, e.g. to
+    /// load subtrees from separate files; the main tree is chosen the same
+    /// way as in `Factory::create_sync_tree_from_text()`.
+    pub fn xml(mut self, xml: impl Into<String>) -> TreeBuilder {
+        self.xml.push(xml.into());
+        self
+    }
+
+    fn register_trees(&mut self) -> Result<String, ParseError> {
+        for xml in std::mem::take(&mut self.xml) {
+            self.factory.register_bt_from_text(xml)?;
+        }
+
+        self.factory.main_tree_to_create()
+    }
+
+    pub fn build_sync(mut self, blackboard: &Blackboard) -> Result<SyncTree, ParseError> {
+        let main_tree_id = self.register_trees()?;
+        self.factory.instantiate_sync_tree(blackboard, &main_tree_id)
+    }
+
+    pub async fn build_async(mut self, blackboard: &Blackboard) -> Result<AsyncTree, ParseError> {
+        let main_tree_id = self.register_trees()?;
+        self.factory
+            .instantiate_async_tree(blackboard, &main_tree_id)
+            .await
+    }
+}
+
+impl Default for TreeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the element of one node type, see `Factory::write_tree_nodes_model()`.
+pub(crate) fn write_node_model(
+    writer: &mut Writer<Vec<u8>>,
+    manifest: &TreeNodeManifest,
+) -> Result<(), quick_xml::Error> {
+    let mut ports: Vec<_> = manifest.ports.iter().collect();
+    ports.sort_by(|a, b| a.0.cmp(b.0));
+
+    let tag = manifest.node_type.to_string();
+    let element = writer
+        .create_element(&tag)
+        .with_attribute(("ID", manifest.registration_id.as_str()));
+    if ports.is_empty() {
+        element.write_empty()?;
+        return Ok(());
+    }
+
+    element.write_inner_content(|writer| {
+        for (name, port) in ports {
+            let tag = match port.direction() {
+                PortDirection::Input => "input_port",
+                PortDirection::Output => "output_port",
+                PortDirection::InOut => "inout_port",
+            };
+            let mut element = writer
+                .create_element(tag)
+                .with_attribute(("name", name.as_str()));
+            if let Some(type_name) = port.type_name() {
+                element = element.with_attribute(("type", type_name));
+            }
+            if let Some(default) = port.default_value() {
+                element = element.with_attribute(("default", default.as_str()));
+            }
+
+            match port.description() {
+                "" => element.write_empty()?,
+                description => element.write_text_content(BytesText::new(description))?,
+            };
+        }
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+/// Returns the function that builds a `LoopNode` named `name` for queues of `T`.
+fn loop_node<T: Any + Send>(name: &str) -> Arc<NodeCreateFnDyn> {
+    let name = name.to_string();
+
+    Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(
+                config,
+                &name,
+                nodes::decorator::LoopNode,
+                nodes::decorator::pop_front::<T> as nodes::decorator::QueuePopFn
+            );
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    )
+}
+
 fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
     let mut node_map = HashMap::new();
 
@@ -771,6 +3249,85 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
     );
     node_map.insert(String::from("WhileDoElse"), (NodeType::Control, node));
 
+    let node = Arc::new(
+        move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "Match", nodes::control::MatchNode);
+
+            node.children = children;
+            node
+        },
+    );
+    node_map.insert(String::from("Match"), (NodeType::Control, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node =
+                build_node_ptr!(config, "ManualSelector", nodes::control::ManualSelectorNode);
+
+            node.children = children;
+            node
+        },
+    );
+    node_map.insert(String::from("ManualSelector"), (NodeType::Control, node));
+
+    for cases in 2..=6 {
+        let name = format!("Switch{cases}");
+        let node = Arc::new({
+            let name = name.clone();
+            move |config: NodeConfig, children: Vec<TreeNodePtr>| -> TreeNodePtr {
+                let mut node = build_node_ptr!(config, &name, nodes::control::SwitchNode, cases);
+
+                node.children = children;
+                node
+            }
+        });
+        node_map.insert(name, (NodeType::Control, node));
+    }
+
+    // Action nodes
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "SetBlackboard", nodes::action::SetBlackboardNode)
+        },
+    );
+    node_map.insert(String::from("SetBlackboard"), (NodeType::Action, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "Script", nodes::action::ScriptNode)
+        },
+    );
+    node_map.insert(String::from("Script"), (NodeType::Action, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "ScriptCondition", nodes::action::ScriptConditionNode)
+        },
+    );
+    node_map.insert(String::from("ScriptCondition"), (NodeType::Condition, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "SetState", nodes::action::SetStateNode)
+        },
+    );
+    node_map.insert(String::from("SetState"), (NodeType::Action, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            build_node_ptr!(config, "InState", nodes::action::InStateNode)
+        },
+    );
+    node_map.insert(String::from("InState"), (NodeType::Condition, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, _children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let test_config = TestNodeConfig::default();
+            build_node_ptr!(config, "TestNode", nodes::action::TestNode, test_config)
+        },
+    );
+    node_map.insert(String::from("TestNode"), (NodeType::Action, node));
+
     // Decorator nodes
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
@@ -794,6 +3351,27 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
     );
     node_map.insert(String::from("ForceSuccess"), (NodeType::Decorator, node));
 
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node =
+                build_node_ptr!(config, "ForceRunning", nodes::decorator::ForceRunningNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("ForceRunning"), (NodeType::Decorator, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "ForceSkip", nodes::decorator::ForceSkipNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("ForceSkip"), (NodeType::Decorator, node));
+
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
             let mut node = build_node_ptr!(config, "Inverter", nodes::decorator::InverterNode);
@@ -821,6 +3399,26 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
         (NodeType::Decorator, node),
     );
 
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "Cooldown", nodes::decorator::CooldownNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("Cooldown"), (NodeType::Decorator, node));
+
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "Delay", nodes::decorator::DelayNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("Delay"), (NodeType::Decorator, node));
+
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
             let mut node = build_node_ptr!(config, "Repeat", nodes::decorator::RepeatNode);
@@ -831,6 +3429,16 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
     );
     node_map.insert(String::from("Repeat"), (NodeType::Decorator, node));
 
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "RunN", nodes::decorator::RunNNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("RunN"), (NodeType::Decorator, node));
+
     let node = Arc::new(
         move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
             let mut node = build_node_ptr!(config, "Retry", nodes::decorator::RetryNode);
@@ -851,5 +3459,26 @@ fn builtin_nodes() -> HashMap<String, (NodeType, Arc<NodeCreateFnDyn>)> {
     );
     node_map.insert(String::from("RunOnce"), (NodeType::Decorator, node));
 
+    node_map.insert(String::from("LoopInt"), (NodeType::Decorator, loop_node::<i64>("LoopInt")));
+    node_map.insert(
+        String::from("LoopDouble"),
+        (NodeType::Decorator, loop_node::<f64>("LoopDouble")),
+    );
+    node_map.insert(
+        String::from("LoopString"),
+        (NodeType::Decorator, loop_node::<String>("LoopString")),
+    );
+    node_map.insert(String::from("LoopBool"), (NodeType::Decorator, loop_node::<bool>("LoopBool")));
+
+    let node = Arc::new(
+        move |config: NodeConfig, mut children: Vec<TreeNodePtr>| -> TreeNodePtr {
+            let mut node = build_node_ptr!(config, "Timeout", nodes::decorator::TimeoutNode);
+
+            node.child = Some(children.remove(0));
+            node
+        },
+    );
+    node_map.insert(String::from("Timeout"), (NodeType::Decorator, node));
+
     node_map
 }
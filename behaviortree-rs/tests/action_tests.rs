@@ -0,0 +1,669 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use behaviortree_rs::{
+    basic_types::{NodeStatus, NodeType, PortsList},
+    blackboard::Blackboard,
+    bt_node,
+    macros::{
+        define_ports, input_port, output_port, register_action_node, register_condition_node,
+        register_simple_action, register_simple_condition,
+    },
+    nodes::{
+        with_timeout, with_timeout_error, AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts,
+        NodeResult, SyncStatefulActionNode,
+    },
+    tree::Factory,
+};
+use futures::future::BoxFuture;
+
+mod nodes;
+
+#[test]
+fn set_blackboard() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SetBlackboard output_key="name" value="5" />
+                    <SetBlackboard output_key="count" value="5" />
+                    <SetBlackboard output_key="{status}" value="Failure" />
+                    <SetBlackboard output_key="speed" value="1.5" type="float" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("count", 1i32);
+    blackboard.set_sync("status", NodeStatus::Success);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // New keys are stored as strings, unless `type` is given
+    assert_eq!(
+        blackboard.get_exact_sync::<String>("name"),
+        Some(String::from("5"))
+    );
+    assert_eq!(blackboard.get_exact_sync::<f64>("speed"), Some(1.5));
+    // Existing entries keep their type
+    assert_eq!(blackboard.get_exact_sync::<i32>("count"), Some(5));
+    assert_eq!(
+        blackboard.get_exact_sync::<NodeStatus>("status"),
+        Some(NodeStatus::Failure)
+    );
+}
+
+#[test]
+fn set_blackboard_parse_error() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <SetBlackboard output_key="count" value="five" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("count", 1i32);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert!(tree.tick_once().is_err());
+    assert_eq!(blackboard.get_exact_sync::<i32>("count"), Some(1));
+}
+
+#[test]
+fn script() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Script code="A := 5; B := A * 2" />
+                    <SubTree ID="sub" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Script code="local := 'sub'; @from_sub := @B + 1; @speed := speed" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("speed", 1.5f32);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap();
+
+    // `speed` isn't remapped into the subtree, so only `@speed` is found
+    assert!(matches!(
+        tree.tick_once(),
+        Err(NodeError::ExpressionError(port, _)) if port == "code"
+    ));
+    assert_eq!(blackboard.get_exact_sync::<i64>("B"), Some(10));
+    assert_eq!(blackboard.get_exact_sync::<i64>("from_sub"), Some(11));
+    assert_eq!(blackboard.get_exact_sync::<String>("local"), None);
+
+    let xml = xml.replace("@speed := speed", "@speed := @speed * 2");
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact_sync::<f32>("speed"), Some(3.0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <ReactiveSequence>
+                    <ScriptCondition code="go" />
+                    <TestNode async_delay="30" success_script="done := true" />
+                </ReactiveSequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set("go", true).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // Waiting doesn't block the tree, and halting cancels the wait
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(29)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    blackboard.set("go", false).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    blackboard.set("go", true).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(29)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_exact::<bool>("done").await, None);
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact::<bool>("done").await, Some(true));
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <TestNode return_status="{status}" failure_script="tries := tries + 1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    blackboard.set("status", NodeStatus::Failure).await;
+    blackboard.set("tries", 0_i64).await;
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get_exact::<i64>("tries").await, Some(2));
+
+    blackboard.set("status", NodeStatus::Success).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact::<i64>("tries").await, Some(2));
+}
+
+#[test]
+fn script_condition() {
+    nodes::test_setup();
+
+    let xml = |code: &str| {
+        format!(
+            r#"
+            <root>
+                <BehaviorTree ID="main">
+                    <ScriptCondition code="{code}" />
+                </BehaviorTree>
+            </root>
+            "#
+        )
+    };
+
+    let mut factory = Factory::new();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("battery", 42u8);
+    blackboard.set_sync("mode", String::from("auto"));
+
+    let mut tick = |code: &str| {
+        factory
+            .create_sync_tree_from_text(xml(code), &blackboard)
+            .unwrap()
+            .tick_once()
+    };
+
+    assert_eq!(tick("battery > 20").unwrap(), NodeStatus::Success);
+    assert_eq!(
+        tick("battery > 50 || mode == 'manual'").unwrap(),
+        NodeStatus::Failure
+    );
+    match tick("battery + 1") {
+        Err(NodeError::ExpressionError(port, e)) => {
+            assert_eq!(port, "code");
+            // Same message as for a pre-condition
+            assert_eq!(
+                e.to_string(),
+                "Expected the expression to be a bool, found int [43]"
+            );
+        }
+        result => panic!("expected an expression error, got {result:?}"),
+    }
+    assert!(matches!(
+        tick("missing"),
+        Err(NodeError::ExpressionError(port, _)) if port == "code"
+    ));
+
+    let manifest = factory
+        .node_manifests()
+        .into_iter()
+        .find(|manifest| manifest.registration_id == "ScriptCondition")
+        .unwrap();
+    assert_eq!(manifest.node_type, NodeType::Condition);
+}
+
+/// Calls a "service" that takes `delay_ms`, bounded by `timeout_ms`.
+#[bt_node(SyncActionNode)]
+struct SlowServiceNode {}
+
+impl AsyncTick for SlowServiceNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let delay: u64 = self.config.get_input("delay_ms").await?;
+            let timeout: u64 = self.config.get_input("timeout_ms").await?;
+            let error: bool = self.config.get_input("error").await?;
+
+            let service = async {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                Ok(NodeStatus::Success)
+            };
+
+            let timeout = Duration::from_millis(timeout);
+            match error {
+                true => with_timeout_error(timeout, service).await,
+                false => with_timeout(timeout, service).await,
+            }
+        })
+    }
+}
+
+impl NodePorts for SlowServiceNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("delay_ms"),
+            input_port!("timeout_ms"),
+            input_port!("error", false)
+        )
+    }
+}
+
+impl AsyncHalt for SlowServiceNode {}
+
+#[tokio::test]
+async fn timeout_helpers() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "SlowServiceNode", SlowServiceNode);
+    let blackboard = Blackboard::create();
+
+    let cases = [
+        (
+            r#"delay_ms="1" timeout_ms="1000""#,
+            Some(NodeStatus::Success),
+        ),
+        (
+            r#"delay_ms="1000" timeout_ms="10""#,
+            Some(NodeStatus::Failure),
+        ),
+        (r#"delay_ms="1000" timeout_ms="10" error="true""#, None),
+    ];
+
+    for (ports, expected) in cases {
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <SlowServiceNode {ports} />
+                </BehaviorTree>
+            </root>
+            "#
+        );
+
+        let mut tree = factory
+            .create_async_tree_from_text(xml, &blackboard)
+            .await
+            .unwrap();
+
+        match expected {
+            Some(status) => assert_eq!(tree.tick_once().await.unwrap(), status),
+            None => assert!(matches!(
+                tree.tick_once().await,
+                Err(NodeError::Timeout(timeout)) if timeout == Duration::from_millis(10)
+            )),
+        }
+    }
+}
+
+fn double(config: &mut NodeConfig) -> BoxFuture<NodeResult> {
+    Box::pin(async move {
+        let value: i64 = config.get_input("value").await?;
+        config.set_output("doubled", value * 2).await?;
+
+        Ok(NodeStatus::Success)
+    })
+}
+
+#[test]
+fn simple_action() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Count />
+                    <Double value="21" doubled="{doubled}" />
+                    <Count />
+                    <Fail />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_simple_action!(factory, "Count", |config| async move {
+        let count = config
+            .blackboard_mut()
+            .get::<u32>("count")
+            .await
+            .unwrap_or(0);
+        config.blackboard_mut().set("count", count + 1).await;
+        Ok(NodeStatus::Success)
+    });
+    register_simple_action!(
+        factory,
+        "Double",
+        double,
+        define_ports!(input_port!("value"), output_port!("doubled"))
+    );
+    register_simple_action!(
+        factory,
+        "Fail",
+        |_config| async move { Ok(NodeStatus::Failure) }
+    );
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.clone().get_sync::<u32>("count"), Some(2));
+    assert_eq!(blackboard.clone().get_sync::<i64>("doubled"), Some(42));
+
+    // Ports are checked like for any other node
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Double value="1" result="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    assert!(factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .is_err());
+}
+
+#[test]
+fn simple_condition() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <IsDoorClosed />
+                    <BatteryOK level="{battery}" />
+                </Fallback>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_simple_condition!(factory, "IsDoorClosed", |config| async move {
+        Ok(config
+            .blackboard_mut()
+            .get::<bool>("door_closed")
+            .await
+            .unwrap_or(false))
+    });
+    register_simple_condition!(
+        factory,
+        "BatteryOK",
+        |config| async move {
+            let level: f64 = config.get_input("level").await?;
+            Ok(level > 20.0)
+        },
+        define_ports!(input_port!("level"))
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("battery", 50.0);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    blackboard.set_sync("battery", 10.0);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    blackboard.set_sync("door_closed", true);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    // Registered as conditions, not actions
+    let manifest = factory
+        .node_manifests()
+        .into_iter()
+        .find(|manifest| manifest.registration_id == "BatteryOK")
+        .unwrap();
+    assert_eq!(manifest.node_type, NodeType::Condition);
+    assert!(manifest.ports.contains_key("level"));
+
+    let mut node_types = Vec::new();
+    tree.visit_nodes(|node| node_types.push(node.node_type()));
+    assert_eq!(
+        node_types,
+        [NodeType::Control, NodeType::Condition, NodeType::Condition]
+    );
+}
+
+/// Succeeds if the `value` input port is even.
+#[bt_node(ConditionNode)]
+struct IsEven {}
+
+impl AsyncTick for IsEven {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let value: u32 = self.config.get_input("value").await?;
+
+            match value % 2 {
+                0 => Ok(NodeStatus::Success),
+                _ => Ok(NodeStatus::Failure),
+            }
+        })
+    }
+}
+
+impl NodePorts for IsEven {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("value"))
+    }
+}
+
+impl AsyncHalt for IsEven {}
+
+#[test]
+fn condition_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <IsEven value="{value}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_condition_node!(factory, "IsEven", IsEven);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("value", 4u32);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    blackboard.set_sync("value", 5u32);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    let manifest = factory
+        .node_manifests()
+        .into_iter()
+        .find(|manifest| manifest.registration_id == "IsEven")
+        .unwrap();
+    assert_eq!(manifest.node_type, NodeType::Condition);
+}
+
+/// Sends a goal on start and waits for it, like a `MoveBase` action. The
+/// calls of its callbacks are recorded in `calls`.
+#[bt_node(StatefulActionNode, Sync)]
+struct MoveBaseNode {
+    calls: Arc<Mutex<Vec<&'static str>>>,
+    #[bt(default)]
+    goal_active: bool,
+}
+
+impl NodePorts for MoveBaseNode {}
+
+impl SyncStatefulActionNode for MoveBaseNode {
+    fn on_start(&mut self) -> NodeResult {
+        self.calls.lock().unwrap().push("on_start");
+        self.goal_active = true;
+        Ok(NodeStatus::Running)
+    }
+
+    fn on_running(&mut self) -> NodeResult {
+        self.calls.lock().unwrap().push("on_running");
+        assert!(self.goal_active);
+        Ok(NodeStatus::Running)
+    }
+
+    fn on_halted(&mut self) {
+        self.calls.lock().unwrap().push("on_halted");
+        // Cancels the goal
+        self.goal_active = false;
+    }
+}
+
+#[test]
+fn stateful_action_halt() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <MoveBase />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "MoveBase", MoveBaseNode, calls.clone());
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    tree.halt_tree();
+    // Halting an idle node doesn't call `on_halted()` again
+    tree.halt_tree();
+
+    // The node starts over after a halt
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "on_start",
+            "on_running",
+            "on_halted",
+            "on_start",
+            "on_running"
+        ]
+    );
+}
+
+/// Blocks in `on_start()` until a task it spawned has run.
+#[bt_node(StatefulActionNode, Sync)]
+struct BlockingStartNode {}
+
+impl NodePorts for BlockingStartNode {}
+
+impl SyncStatefulActionNode for BlockingStartNode {
+    fn on_start(&mut self) -> NodeResult {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        tokio::spawn(async move { sender.send(()) });
+
+        match receiver.recv_timeout(Duration::from_secs(5)) {
+            Ok(()) => Ok(NodeStatus::Success),
+            Err(_) => Ok(NodeStatus::Failure),
+        }
+    }
+
+    fn on_running(&mut self) -> NodeResult {
+        Ok(NodeStatus::Success)
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn stateful_action_blocking() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <BlockingStart />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "BlockingStart", BlockingStartNode);
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // The only worker ticks the tree, so the spawned task can only run if
+    // the blocking callback gave up the worker
+    let status = tokio::spawn(async move { tree.tick_once().await })
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), NodeStatus::Success);
+}
@@ -0,0 +1,514 @@
+use behaviortree_rs::{
+    basic_types::{NodeStatus, PortDirection, PortsList},
+    blackboard::{Blackboard, EvictionPolicy},
+    bt_node,
+    macros::{define_ports, input_port, output_port, register_action_node},
+    nodes::{AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts, NodeResult},
+    tree::{Factory, ParseError},
+};
+use futures::future::BoxFuture;
+
+mod nodes;
+
+/// Copies the string at `in` to `out`.
+#[bt_node(SyncActionNode)]
+struct CopyNode {}
+
+impl AsyncTick for CopyNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let value: String = self.config.get_input("in").await?;
+            self.config.set_output("out", value).await?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for CopyNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("in"), output_port!("out"))
+    }
+}
+
+impl AsyncHalt for CopyNode {}
+
+/// Counts its ticks in the `ticks` entry of its blackboard.
+#[bt_node(SyncActionNode)]
+struct TickCounterNode {}
+
+impl AsyncTick for TickCounterNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let blackboard = self.config.blackboard_mut();
+            let ticks = blackboard.get::<u32>("ticks").await.unwrap_or_default();
+            blackboard.set("ticks", ticks + 1).await;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for TickCounterNode {}
+
+impl AsyncHalt for TickCounterNode {}
+
+#[tokio::test]
+async fn subtree_remapping() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SubTree ID="explicit" name="explicit" target=" {goal} " result="{plan}" greeting="hello" />
+                    <SubTree ID="auto" name="auto" _autoremap="true" />
+                    <SubTree ID="unmapped" name="unmapped" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="explicit">
+                <Sequence>
+                    <CopyNode in="{target}" out="{result}" />
+                    <CopyNode in="{greeting}" out="{local}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="auto">
+                <CopyNode in="{plan}" out="{auto_plan}" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="unmapped">
+                <SetBlackboard output_key="unmapped_done" value="true" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "CopyNode", CopyNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("goal", String::from("kitchen")).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    let blackboards = tree.collect_blackboards().await;
+    let scope = |path: &str| {
+        blackboards
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, blackboard)| blackboard.clone())
+            .unwrap()
+    };
+    let mut root = scope("");
+    let mut explicit = scope("explicit");
+    let mut auto = scope("auto");
+    let mut unmapped = scope("unmapped");
+
+    // Explicit remapping: the input flows in, the output flows out under the
+    // parent's key, and local entries stay in the subtree
+    assert_eq!(root.get::<String>("plan").await.as_deref(), Some("kitchen"));
+    assert_eq!(
+        explicit.get::<String>("result").await.as_deref(),
+        Some("kitchen")
+    );
+    assert_eq!(
+        explicit.get::<String>("local").await.as_deref(),
+        Some("hello")
+    );
+    assert_eq!(root.get::<String>("local").await, None);
+    assert_eq!(root.get::<String>("result").await, None);
+    assert_eq!(root.get::<String>("greeting").await, None);
+
+    // Autoremapping: reads and writes go to the parent under the same key
+    assert_eq!(
+        root.get::<String>("auto_plan").await.as_deref(),
+        Some("kitchen")
+    );
+    assert_eq!(
+        auto.get::<String>("auto_plan").await.as_deref(),
+        Some("kitchen")
+    );
+
+    // Without remapping, parent entries aren't visible
+    assert_eq!(unmapped.get::<String>("goal").await, None);
+    assert_eq!(unmapped.get::<bool>("unmapped_done").await, Some(true));
+    assert_eq!(root.get::<bool>("unmapped_done").await, None);
+
+    // Writes from the parent are seen by the subtrees on the next tick
+    root.set("goal", String::from("garage")).await;
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+    assert_eq!(root.get::<String>("plan").await.as_deref(), Some("garage"));
+    assert_eq!(
+        root.get::<String>("auto_plan").await.as_deref(),
+        Some("garage")
+    );
+}
+
+#[tokio::test]
+async fn root_key_ports() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Sequence>
+                    <CopyNode in="{@goal}" out="{@plan}" />
+                    <Script code="@done := true" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "CopyNode", CopyNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("goal", String::from("kitchen")).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    // The subtree has no remapping, but reaches the root with `@`
+    assert_eq!(
+        blackboard.get::<String>("plan").await.as_deref(),
+        Some("kitchen")
+    );
+    assert_eq!(blackboard.get::<bool>("done").await, Some(true));
+}
+
+#[tokio::test]
+async fn subtree_model() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SubTree ID="move" name="auto" target="{goal}" _autoremap="true" />
+                    <SubTree ID="move" name="defaults" target="{goal}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="move">
+                <Sequence>
+                    <CopyNode in="{target}" out="{result}" />
+                    <CopyNode in="{speed}" out="{local}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <TreeNodesModel>
+                <Action ID="CopyNode" />
+                <SubTree ID="move">
+                    <input_port name="target" />
+                    <input_port name="speed" default="slow">Speed of the robot</input_port>
+                    <output_port name="result" default="{move_result}" />
+                </SubTree>
+            </TreeNodesModel>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "CopyNode", CopyNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("goal", String::from("kitchen")).await;
+    blackboard.set("speed", String::from("fast")).await;
+    blackboard.set("local", String::from("parent")).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    let blackboards = tree.collect_blackboards().await;
+    let scope = |path: &str| {
+        blackboards
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, blackboard)| blackboard.clone())
+            .unwrap()
+    };
+    let mut root = scope("");
+    let mut auto = scope("auto");
+    let mut defaults = scope("defaults");
+
+    // `_autoremap` only remaps the declared ports that aren't given
+    assert_eq!(
+        root.get::<String>("result").await.as_deref(),
+        Some("kitchen")
+    );
+    assert_eq!(auto.get::<String>("local").await.as_deref(), Some("fast"));
+    assert_eq!(root.get::<String>("local").await.as_deref(), Some("parent"));
+
+    // Without it, ports that aren't given use their defaults
+    assert_eq!(
+        root.get::<String>("move_result").await.as_deref(),
+        Some("kitchen")
+    );
+    assert_eq!(
+        defaults.get::<String>("local").await.as_deref(),
+        Some("slow")
+    );
+
+    // Attributes must be declared ports
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="move" target="{goal}" speeed="fast" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    let error = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, ParseError::InvalidPort(port, _, ports) if port == "speeed" && ports.len() == 3),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn node_blackboard_access() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <TickCounterNode />
+                    <TickCounterNode />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "TickCounterNode", TickCounterNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    tree.tick_while_running().await.unwrap();
+    tree.tick_while_running().await.unwrap();
+
+    // Both nodes write to the tree's blackboard
+    assert_eq!(blackboard.get::<u32>("ticks").await, Some(4));
+}
+
+/// A large value that's shared through the blackboard. It isn't `Clone`, so
+/// it can only be read by reference.
+struct PointCloud {
+    points: Vec<(f64, f64, f64)>,
+}
+
+/// Writes a `PointCloud` to `cloud`.
+#[bt_node(SyncActionNode)]
+struct AcquirePointCloud {}
+
+impl AsyncTick for AcquirePointCloud {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let points = (0..1000).map(|i| (i as f64, 2.0, 0.5)).collect();
+            self.config
+                .set_output("cloud", PointCloud { points })
+                .await?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for AcquirePointCloud {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(output_port!("cloud"))
+    }
+}
+
+impl AsyncHalt for AcquirePointCloud {}
+
+/// Writes the centroid of the `PointCloud` at `cloud` to `obj_pose`.
+#[bt_node(SyncActionNode)]
+struct SegmentObject {}
+
+impl AsyncTick for SegmentObject {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let centroid = {
+                let cloud = self
+                    .config
+                    .get_input_ref::<PointCloud>("cloud")
+                    .await
+                    .ok_or_else(|| NodeError::PortError(String::from("cloud")))?;
+                let count = cloud.points.len() as f64;
+                let sum = cloud.points.iter().fold((0.0, 0.0, 0.0), |sum, p| {
+                    (sum.0 + p.0, sum.1 + p.1, sum.2 + p.2)
+                });
+
+                (sum.0 / count, sum.1 / count, sum.2 / count)
+            };
+            self.config.set_output("obj_pose", centroid).await?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for SegmentObject {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("cloud"), output_port!("obj_pose"))
+    }
+}
+
+impl AsyncHalt for SegmentObject {}
+
+#[tokio::test]
+async fn input_by_reference() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <AcquirePointCloud cloud="{pointcloud}" />
+                    <SubTree ID="segment" cloud="{pointcloud}" pose="{pose}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="segment">
+                <SegmentObject cloud="{cloud}" obj_pose="{pose}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "AcquirePointCloud", AcquirePointCloud);
+    register_action_node!(factory, "SegmentObject", SegmentObject);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    assert_eq!(
+        blackboard.get_exact::<(f64, f64, f64)>("pose").await,
+        Some((499.5, 2.0, 0.5))
+    );
+    let cloud = blackboard
+        .get_ref::<PointCloud>("pointcloud")
+        .await
+        .unwrap();
+    assert_eq!(cloud.points.len(), 1000);
+    drop(cloud);
+
+    // A reference is only handed out for the stored type
+    assert!(blackboard.get_ref::<String>("pointcloud").await.is_none());
+    assert!(blackboard.get_ref::<PointCloud>("missing").await.is_none());
+}
+
+#[tokio::test]
+async fn global_blackboard() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Script code="@count := @count + 1; local := @count" />
+            </BehaviorTree>
+        </root>
+    "#;
+
+    let mut global = Blackboard::create();
+    global.set("count", 0_i64).await;
+
+    let mut factory = Factory::new();
+    factory.set_global_blackboard(global.clone());
+    assert!(factory.global_blackboard().unwrap().ptr_eq(&global));
+
+    // Passing the global blackboard gives the tree a child of it
+    let mut first = factory
+        .create_async_tree_from_text(xml.to_string(), &global)
+        .await
+        .unwrap();
+    let mut first_blackboard = first.root_blackboard().await;
+    assert!(!first_blackboard.ptr_eq(&global));
+    assert!(first_blackboard.root().ptr_eq(&global));
+
+    // A blackboard that descends from it is used as is
+    let mut second_blackboard = Blackboard::with_parent(&global).await;
+    let mut second = factory
+        .create_async_tree_from_text(xml.to_string(), &second_blackboard)
+        .await
+        .unwrap();
+    assert!(second.root_blackboard().await.ptr_eq(&second_blackboard));
+
+    assert_eq!(first.tick_once().await.unwrap(), NodeStatus::Success);
+    assert_eq!(second.tick_once().await.unwrap(), NodeStatus::Success);
+
+    // Both trees count in the same global entry, but keep their own locals
+    assert_eq!(global.get::<i64>("count").await, Some(2));
+    assert_eq!(global.get::<i64>("local").await, None);
+    assert_eq!(first_blackboard.get::<i64>("local").await, Some(1));
+    assert_eq!(second_blackboard.get::<i64>("local").await, Some(2));
+}
+
+#[tokio::test]
+async fn full_blackboard_output() {
+    let mut blackboard = Blackboard::create();
+    blackboard
+        .set_max_entries(Some(1), EvictionPolicy::Reject)
+        .await;
+    blackboard.set("existing", 1u32).await;
+
+    let mut config = NodeConfig::new(blackboard.clone());
+    config.add_port(PortDirection::Output, "old".into(), "{existing}".into());
+    config.add_port(PortDirection::Output, "new".into(), "{missing}".into());
+
+    // Existing keys can be written, new ones are refused
+    config.set_output("old", 2u32).await.unwrap();
+    assert_eq!(blackboard.get::<u32>("existing").await, Some(2));
+
+    match config.set_output("new", 3u32).await {
+        Err(NodeError::BlackboardFull(port, e)) => {
+            assert_eq!(port, "new");
+            assert_eq!(e.key, "missing");
+        }
+        other => panic!("Expected BlackboardFull, got {other:?}"),
+    }
+    assert_eq!(blackboard.get::<u32>("missing").await, None);
+}
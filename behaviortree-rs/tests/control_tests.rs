@@ -1,9 +1,25 @@
-use behaviortree_rs::{blackboard::Blackboard, macros::register_action_node, tree::Factory};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use behaviortree_rs::{
+    basic_types::{NodeStatus, NodeType, PortsList},
+    blackboard::Blackboard,
+    bt_node,
+    macros::{define_ports, input_port, register_action_node},
+    nodes::{control::ManualSelectorNode, AsyncHalt, AsyncTick, NodePorts, NodeResult},
+    tree::{Factory, ParseError, SyncTree},
+};
+use futures::future::BoxFuture;
 use log::{error, info};
 
 mod nodes;
 
-use nodes::{EchoNode, RunForNode, StatusNode};
+use nodes::{EchoNode, RunForNode, RunUntilHaltedNode, StatusNode, SuccessThenFailure};
 
 #[test]
 fn fallback() {
@@ -74,6 +90,135 @@ fn if_then_else() {
     }
 }
 
+#[test]
+fn if_then_else_branches() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    let blackboard = Blackboard::create();
+
+    let mut build = |children: &[&str]| {
+        let children = children.join("\n");
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <IfThenElse>
+                        {children}
+                    </IfThenElse>
+                </BehaviorTree>
+            </root>
+            "#
+        );
+        factory
+            .create_sync_tree_from_text(xml, &blackboard)
+            .unwrap()
+    };
+    let success = r#"<StatusNode status="Success" />"#;
+    let failure = r#"<StatusNode status="Failure" />"#;
+
+    // The branch's result is the node's result
+    for (condition, then, otherwise, expected) in [
+        (success, success, failure, NodeStatus::Success),
+        (success, failure, success, NodeStatus::Failure),
+        (failure, failure, success, NodeStatus::Success),
+        (failure, success, failure, NodeStatus::Failure),
+    ] {
+        let mut tree = build(&[condition, then, otherwise]);
+        assert_eq!(tree.tick_once().unwrap(), expected);
+        // Children are reset once the node completes
+        assert!(tree
+            .status_report()
+            .values()
+            .all(|status| *status == NodeStatus::Idle));
+    }
+
+    // Without an else branch, a failing condition fails the node
+    let mut tree = build(&[failure, success]);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert!(tree
+        .status_report()
+        .values()
+        .all(|status| *status == NodeStatus::Idle));
+    let mut tree = build(&[success, success]);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // A running condition is ticked again before a branch is chosen
+    let mut tree = build(&[
+        r#"<RunForNode iters="1" status="Failure" />"#,
+        success,
+        failure,
+    ]);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    let report = tree.status_report();
+    assert_eq!(report[&2], NodeStatus::Running);
+    assert_eq!(report[&4], NodeStatus::Idle);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // A skipped condition skips the node
+    let mut tree = build(&[
+        r#"<ForceSkip><StatusNode status="Success" /></ForceSkip>"#,
+        success,
+        failure,
+    ]);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+
+    // Wrong number of children
+    let mut tree = build(&[success]);
+    assert!(tree.tick_once().is_err());
+}
+
+#[test]
+fn if_then_else_reentry() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <IfThenElse>
+                    <SuccessThenFailure iters="1" />
+                    <RunForNode iters="2" />
+                    <StatusNode status="Failure" />
+                </IfThenElse>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    register_action_node!(factory, "SuccessThenFailure", SuccessThenFailure);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    // The condition succeeds once and isn't ticked again while the branch runs,
+    // otherwise the node would switch to the else branch
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.status_report()[&3], NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // The next run evaluates the condition again, which now fails
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // Halting a running branch also restarts from the condition
+    let mut tree = factory
+        .instantiate_sync_tree(&Blackboard::create(), "main")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    tree.halt_tree();
+    assert_eq!(tree.status_report()[&3], NodeStatus::Idle);
+    // The condition's only success was used up by the first tick
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+}
+
 #[test]
 fn parallel_all() {
     nodes::test_setup();
@@ -146,6 +291,145 @@ fn parallel() {
     }
 }
 
+#[test]
+fn parallel_thresholds() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHalted", RunUntilHaltedNode);
+
+    let mut build = |children: &str| {
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <Parallel success_count="1" failure_count="3">
+                        <RunUntilHalted halted="{{halted}}" />
+                        {children}
+                    </Parallel>
+                </BehaviorTree>
+            </root>
+            "#
+        );
+        factory
+            .create_sync_tree_from_text(xml, &Blackboard::create())
+            .unwrap()
+    };
+    let success = r#"<StatusNode status="Success" />"#;
+    let failure = r#"<StatusNode status="Failure" />"#;
+
+    // One success is enough, and the running child is halted
+    let mut tree = build(&[failure, success].concat());
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(
+        tree.root_blackboard().get_sync::<bool>("halted"),
+        Some(true)
+    );
+
+    // Two failures aren't enough to fail
+    let mut tree = build(&[failure, failure].concat());
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.root_blackboard().get_sync::<bool>("halted"), None);
+
+    // The third failure is
+    let mut tree = build(&[failure, failure, failure].concat());
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(
+        tree.root_blackboard().get_sync::<bool>("halted"),
+        Some(true)
+    );
+
+    // Negative values count from the number of children
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Parallel success_count="-2" failure_count="-1">
+                    <RunUntilHalted />
+                    <StatusNode status="Success" />
+                    <StatusNode status="Success" />
+                </Parallel>
+            </BehaviorTree>
+        </root>
+    "#;
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.to_string(), &Blackboard::create())
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn parallel_required_children() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    let blackboard = Blackboard::create();
+
+    // A and B are required, C is optional
+    let mut build = |a: &str, b: &str, c: &str| {
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <Parallel required_children="0;1">
+                        {a}
+                        {b}
+                        {c}
+                    </Parallel>
+                </BehaviorTree>
+            </root>
+            "#
+        );
+        factory
+            .create_sync_tree_from_text(xml, &blackboard)
+            .unwrap()
+    };
+    let success = r#"<StatusNode status="Success" />"#;
+    let failure = r#"<StatusNode status="Failure" />"#;
+    let running = r#"<RunForNode iters="10" />"#;
+    let fail_later = r#"<RunForNode iters="1" status="Failure" />"#;
+
+    // The optional child's result doesn't matter
+    for c in [success, failure, running] {
+        let mut tree = build(success, success, c);
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    }
+
+    // Any failing required child fails the node
+    for (a, b) in [(success, failure), (failure, success), (failure, failure)] {
+        let mut tree = build(a, b, success);
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    }
+    let mut tree = build(success, fail_later, success);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // Waits for the required children
+    let mut tree = build(running, success, failure);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    // Indexes must refer to existing children
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Parallel required_children="0;2">
+                    <StatusNode status="Success" />
+                    <StatusNode status="Success" />
+                </Parallel>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert!(tree.tick_once().is_err());
+}
+
 #[test]
 fn reactive_fallback() {
     nodes::test_setup();
@@ -180,6 +464,55 @@ fn reactive_fallback() {
     }
 }
 
+#[test]
+fn reactive_fallback_recovers() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <ReactiveFallback>
+                    <StatusNode status="{condition}" />
+                    <RunUntilHaltedNode halted="{halted}" />
+                </ReactiveFallback>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("condition", NodeStatus::Failure);
+    blackboard.set_sync("halted", false);
+
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+
+    // The first child fails, so the second one keeps running
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(false));
+
+    // The first child is re-ticked every cycle, so its recovery is noticed
+    // immediately and the running child is halted
+    blackboard.set_sync("condition", NodeStatus::Success);
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+
+    // Failing again restarts the second child
+    blackboard.set_sync("condition", NodeStatus::Failure);
+    blackboard.set_sync("halted", false);
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(false));
+}
+
 #[test]
 fn reactive_sequence() {
     nodes::test_setup();
@@ -216,6 +549,81 @@ fn reactive_sequence() {
     }
 }
 
+#[test]
+fn reactive_sequence_multiple_running() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    register_action_node!(factory, "SuccessThenFailure", SuccessThenFailure);
+
+    let mut build = |policy: &str, children: &str| {
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <ReactiveSequence {policy}>
+                        {children}
+                    </ReactiveSequence>
+                </BehaviorTree>
+            </root>
+            "#
+        );
+        factory
+            .create_sync_tree_from_text(xml, &Blackboard::create())
+            .unwrap()
+    };
+    let children = r#"
+        <StatusNode status="Success" />
+        <RunForNode iters="1" />
+        <RunForNode iters="2" />
+    "#;
+
+    // By default, a second running child is an error
+    let mut tree = build("", children);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert!(tree.tick_once().is_err());
+
+    // With the policy, both run at the same time and a finished child isn't
+    // restarted while the other one is still running
+    let mut tree = build(r#"_allow_multiple_running="true""#, children);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    let report = tree.status_report();
+    assert_eq!(report[&3], NodeStatus::Running);
+    assert_eq!(report[&4], NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.status_report()[&3], NodeStatus::Success);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // The node starts over after completing
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.status_report()[&3], NodeStatus::Running);
+
+    // Conditions are checked on every tick, and a failure halts all running children
+    let mut tree = build(
+        r#"_allow_multiple_running="true""#,
+        r#"
+        <SuccessThenFailure iters="2" />
+        <RunUntilHaltedNode halted="{first_halted}" />
+        <RunUntilHaltedNode halted="{second_halted}" />
+        "#,
+    );
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    let mut blackboard = tree.root_blackboard();
+    assert_eq!(blackboard.get_sync::<bool>("first_halted"), Some(true));
+    assert_eq!(blackboard.get_sync::<bool>("second_halted"), Some(true));
+    assert!(tree
+        .status_report()
+        .values()
+        .all(|status| *status == NodeStatus::Idle));
+}
+
 #[test]
 fn sequence_star() {
     nodes::test_setup();
@@ -288,6 +696,108 @@ fn sequence_vanilla() {
     }
 }
 
+#[test]
+fn sequence_skipped() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Skipped" />
+                    <StatusNode status="Skipped" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="mixed">
+                <Sequence>
+                    <StatusNode status="Skipped" />
+                    <StatusNode status="Success" />
+                    <StatusNode status="Skipped" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let blackboard = Blackboard::create();
+
+    factory.register_bt_from_text(xml).unwrap();
+
+    // Only skipped children
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+
+    // Any successful child means the sequence succeeded
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "mixed").unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn control_nodes_skipped() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let blackboard = Blackboard::create();
+
+    for control in [
+        "Sequence",
+        "SequenceStar",
+        "Fallback",
+        "Parallel",
+        "ParallelAll",
+    ] {
+        // Every child is skipped by its precondition
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <{control}>
+                        <StatusNode status="Failure" _skipIf="true" />
+                        <StatusNode status="Success" _skipIf="true" />
+                    </{control}>
+                </BehaviorTree>
+            </root>
+        "#
+        );
+        let mut tree = factory
+            .create_sync_tree_from_text(xml, &blackboard)
+            .unwrap();
+
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped, "{control}");
+
+        // Only skipped children don't change the result
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <{control}>
+                        <StatusNode status="Failure" _skipIf="true" />
+                        <StatusNode status="Success" _skipIf="false" />
+                    </{control}>
+                </BehaviorTree>
+            </root>
+        "#
+        );
+        let mut tree = factory
+            .create_sync_tree_from_text(xml, &blackboard)
+            .unwrap();
+
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success, "{control}");
+    }
+}
+
 #[test]
 fn while_do_else() {
     nodes::test_setup();
@@ -322,3 +832,471 @@ fn while_do_else() {
         Err(e) => error!("{e}"),
     }
 }
+
+/// Returns its "status" port and counts how often it was ticked.
+#[bt_node(SyncActionNode)]
+struct CountingStatusNode {
+    ticks: Arc<AtomicUsize>,
+}
+
+impl AsyncTick for CountingStatusNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.ticks.fetch_add(1, Ordering::SeqCst);
+            self.config.get_input("status").await
+        })
+    }
+}
+
+impl NodePorts for CountingStatusNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("status"))
+    }
+}
+
+impl AsyncHalt for CountingStatusNode {}
+
+#[tokio::test(start_paused = true)]
+async fn while_do_else_check_period() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <WhileDoElse check_period_msec="50">
+                    <CountingStatusNode status="Success" />
+                    <RunForNode iters="10" />
+                </WhileDoElse>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let ticks = Arc::new(AtomicUsize::new(0));
+
+    let mut factory = Factory::new();
+
+    register_action_node!(factory, "CountingStatusNode", CountingStatusNode, ticks);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let blackboard = Blackboard::create();
+
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut tree = factory
+        .instantiate_async_tree(&blackboard, "main")
+        .await
+        .unwrap();
+
+    // The statement is only checked on the first tick within the period
+    for _ in 0..3 {
+        assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    }
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+
+    tokio::time::advance(Duration::from_millis(1)).await;
+    for _ in 0..3 {
+        assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    }
+    assert_eq!(ticks.load(Ordering::SeqCst), 2);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RobotState {
+    Idle,
+    Moving,
+    Charging,
+}
+
+impl std::fmt::Display for RobotState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+fn match_factory() -> Factory {
+    let mut factory = Factory::new();
+    factory.register_enum(
+        "RobotState",
+        [RobotState::Idle, RobotState::Moving, RobotState::Charging],
+    );
+
+    factory
+}
+
+#[test]
+fn match_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Match variable="{state}" enum="RobotState" cases="Idle;Moving">
+                    <SetBlackboard output_key="branch" value="idle" />
+                    <SetBlackboard output_key="branch" value="moving" />
+                    <SetBlackboard output_key="branch" value="default" />
+                </Match>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = match_factory();
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let mut branch = |state: RobotState| {
+        blackboard.set_sync("state", state);
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+        blackboard.get_sync::<String>("branch").unwrap()
+    };
+    assert_eq!(branch(RobotState::Moving), "moving");
+    assert_eq!(branch(RobotState::Idle), "idle");
+    // Values without a case go to the last child
+    assert_eq!(branch(RobotState::Charging), "default");
+
+    // The variant's name works as well
+    blackboard.set_sync("state", String::from("Moving"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(
+        blackboard.get_sync::<String>("branch").as_deref(),
+        Some("moving")
+    );
+}
+
+#[test]
+fn match_node_without_default() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Match variable="Charging" enum="RobotState" cases="Idle;Moving">
+                    <SetBlackboard output_key="branch" value="idle" />
+                    <SetBlackboard output_key="branch" value="moving" />
+                </Match>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = match_factory();
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get_sync::<String>("branch"), None);
+}
+
+#[test]
+fn match_node_build_errors() {
+    nodes::test_setup();
+
+    let xml = |attributes: &str| {
+        format!(
+            r#"
+            <root>
+                <BehaviorTree ID="main">
+                    <Match variable="{{state}}" {attributes}>
+                        <AlwaysSuccess />
+                        <AlwaysSuccess />
+                    </Match>
+                </BehaviorTree>
+            </root>
+            "#
+        )
+    };
+    let blackboard = Blackboard::create();
+    let build = |attributes: &str| {
+        let mut factory = match_factory();
+        register_action_node!(factory, "AlwaysSuccess", StatusNode);
+        factory
+            .create_sync_tree_from_text(xml(attributes), &blackboard)
+            .err()
+            .unwrap()
+    };
+
+    // Typo in a case label
+    assert!(matches!(
+        build(r#"enum="RobotState" cases="Idle;Moveing""#),
+        ParseError::UnknownEnumVariant(value, name, _) if value == "Moveing" && name == "RobotState"
+    ));
+    assert!(matches!(
+        build(r#"enum="State" cases="Idle""#),
+        ParseError::UnknownEnum(name, _) if name == "State"
+    ));
+    assert!(matches!(
+        build(r#"enum="RobotState" cases="Idle;Moving;Charging;""#),
+        ParseError::ChildCountMismatch(_, _, 2)
+    ));
+}
+
+#[test]
+fn state_machine() {
+    nodes::test_setup();
+
+    // Idle -> Moving, which drains the battery, -> Charging -> Idle
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Match variable="{state}" enum="RobotState" cases="Idle;Moving;Charging">
+                    <SetState enum="RobotState" state="Moving" />
+                    <Fallback>
+                        <Sequence>
+                            <ScriptCondition code="battery < 20" />
+                            <SetState enum="RobotState" state="Charging" />
+                        </Sequence>
+                        <Script code="battery := battery - 30" />
+                    </Fallback>
+                    <Sequence>
+                        <Script code="battery := 100" />
+                        <SetState enum="RobotState" state="Idle" />
+                    </Sequence>
+                </Match>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = match_factory();
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("state", RobotState::Idle);
+    blackboard.set_sync("battery", 50_i64);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    let mut states = Vec::new();
+    for _ in 0..5 {
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+        states.push(blackboard.get_exact_sync::<RobotState>("state").unwrap());
+    }
+    assert_eq!(
+        states,
+        vec![
+            RobotState::Moving,
+            RobotState::Moving,
+            RobotState::Moving,
+            RobotState::Charging,
+            RobotState::Idle,
+        ]
+    );
+    assert_eq!(blackboard.get_sync::<i64>("battery"), Some(100));
+}
+
+#[test]
+fn in_state() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <InState enum="RobotState" state="Moving;Charging" />
+                    <SetState enum="RobotState" state="Idle" variable="{next}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = match_factory();
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    // Without a state, the guard can't be checked
+    assert!(tree.tick_once().is_err());
+
+    blackboard.set_sync("state", RobotState::Idle);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get_exact_sync::<RobotState>("next"), None);
+
+    // The variant's name works as well
+    blackboard.set_sync("state", String::from("Charging"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(
+        blackboard.get_exact_sync::<RobotState>("next"),
+        Some(RobotState::Idle)
+    );
+
+    let mut node_types = Vec::new();
+    tree.visit_nodes(|node| node_types.push(node.node_type()));
+    assert_eq!(
+        node_types,
+        [NodeType::Control, NodeType::Condition, NodeType::Action]
+    );
+}
+
+#[test]
+fn state_node_build_errors() {
+    nodes::test_setup();
+
+    let blackboard = Blackboard::create();
+    let build = |node: &str| {
+        let xml = format!(
+            r#"
+            <root>
+                <BehaviorTree ID="main">
+                    {node}
+                </BehaviorTree>
+            </root>
+            "#
+        );
+        match_factory()
+            .create_sync_tree_from_text(xml, &blackboard)
+            .err()
+            .unwrap()
+    };
+
+    assert!(matches!(
+        build(r#"<SetState enum="RobotState" state="Flying" />"#),
+        ParseError::UnknownEnumVariant(value, _, _) if value == "Flying"
+    ));
+    assert!(matches!(
+        build(r#"<InState enum="RobotState" state="Idle;Flying" />"#),
+        ParseError::UnknownEnumVariant(value, _, _) if value == "Flying"
+    ));
+    assert!(matches!(
+        build(r#"<InState enum="State" state="Idle" />"#),
+        ParseError::UnknownEnum(name, _) if name == "State"
+    ));
+    // SetState writes the state, so it needs a blackboard entry
+    assert!(matches!(
+        build(r#"<SetState enum="RobotState" state="Idle" variable="Moving" />"#),
+        ParseError::InvalidOutputPortValue(port, _, _) if port == "variable"
+    ));
+}
+
+#[test]
+fn switch() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Switch3 variable="{color}" case_1="red" case_2=" green " case_3="{yellow}">
+                    <StatusNode status="Failure" />
+                    <RunUntilHaltedNode halted="{halted}" />
+                    <StatusNode status="Skipped" />
+                    <StatusNode status="Success" />
+                </Switch3>
+            </BehaviorTree>
+
+            <BehaviorTree ID="numbers">
+                <Switch2 variable="{number}" case_1="3" case_2="4">
+                    <StatusNode status="Failure" />
+                    <StatusNode status="Skipped" />
+                    <StatusNode status="Success" />
+                </Switch2>
+            </BehaviorTree>
+
+            <BehaviorTree ID="missing_default">
+                <Switch2 variable="red" case_1="red" case_2="green">
+                    <StatusNode status="Failure" />
+                    <StatusNode status="Success" />
+                </Switch2>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("yellow", String::from("yellow"));
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+
+    blackboard.set_sync("color", String::from("red"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    blackboard.set_sync("color", String::from("green"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+
+    // Switching cases halts the running child
+    blackboard.set_sync("color", String::from("yellow"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+
+    // No case matches, so the default child is ticked
+    blackboard.set_sync("color", String::from("blue"));
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // Entries that aren't strings are compared as text
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "numbers")
+        .unwrap();
+    blackboard.set_sync("number", 3_i64);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    blackboard.set_sync("number", 4_i64);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    blackboard.set_sync("number", 5_i64);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    assert!(matches!(
+        factory.instantiate_sync_tree(&blackboard, "missing_default"),
+        Err(ParseError::ChildCountMismatch(_, expected, 2)) if expected == "3"
+    ));
+}
+
+#[test]
+fn manual_selector() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <ManualSelector repeat_last_selection="{repeat}">
+                    <StatusNode status="Failure" />
+                    <RunForNode iters="1" status="Success" />
+                </ManualSelector>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("repeat", true);
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+
+    let select = |tree: &mut SyncTree, index| {
+        tree.visit_nodes_mut(|node| {
+            if let Some(node) = node.as_any_mut().downcast_mut::<ManualSelectorNode>() {
+                node.select(index);
+            }
+        });
+    };
+
+    // Without the cli feature, nothing is selected unless `select()` is used
+    #[cfg(not(feature = "cli"))]
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    select(&mut tree, 1);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    // The last selection is repeated
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    select(&mut tree, 0);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    select(&mut tree, 2);
+    assert!(tree.tick_once().is_err());
+}
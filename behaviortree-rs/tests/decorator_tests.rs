@@ -1,11 +1,22 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
 use behaviortree_rs::{
-    basic_types::NodeStatus, blackboard::Blackboard, macros::register_action_node, tree::Factory,
+    basic_types::{NodeStatus, PortsList},
+    blackboard::Blackboard,
+    bt_node,
+    macros::{define_ports, input_port, register_action_node, register_simple_decorator},
+    nodes::{AsyncHalt, AsyncTick, NodePorts, NodeResult},
+    tree::Factory,
 };
+use futures::future::BoxFuture;
 use log::{error, info};
 
 mod nodes;
 
-use nodes::{RunForNode, StatusNode};
+use nodes::{RunForNode, RunUntilHaltedNode, StatusNode};
 
 use crate::nodes::SuccessThenFailure;
 
@@ -188,6 +199,89 @@ fn repeat() {
     }
 }
 
+#[test]
+fn run_n() {
+    nodes::test_setup();
+
+    let xml = |threshold: &str| {
+        format!(
+            r#"
+            <root>
+                <BehaviorTree ID="main">
+                    <RunN num_runs="5" {threshold} successes="{{successes}}">
+                        <SuccessThenFailure iters="2" />
+                    </RunN>
+                </BehaviorTree>
+            </root>
+            "#
+        )
+    };
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "SuccessThenFailure", SuccessThenFailure);
+    let mut blackboard = Blackboard::create();
+
+    // All 5 runs happen in one tick, although the child fails after 2
+    let mut tree = factory
+        .create_sync_tree_from_text(xml(r#"success_threshold="2""#), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<usize>("successes"), Some(2));
+
+    // By default, every run must succeed
+    let mut tree = factory
+        .create_sync_tree_from_text(xml(""), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // A running child continues the same run on the next tick
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <RunN num_runs="2">
+                    <RunForNode iters="1" />
+                </RunN>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    register_action_node!(factory, "RunForNode", RunForNode);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    }
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // An error of the child starts the runs over
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <RunN num_runs="2" successes="{successes}">
+                    <RunForNode iters="0" status="{status}" />
+                </RunN>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    blackboard.set_sync("status", NodeStatus::Success);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    blackboard.remove_sync("status");
+    assert!(tree.tick_once().is_err());
+
+    blackboard.set_sync("status", NodeStatus::Failure);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get_sync::<usize>("successes"), Some(0));
+}
+
 #[test]
 fn retry() {
     nodes::test_setup();
@@ -263,3 +357,440 @@ fn run_once() {
         Err(e) => error!("{e}"),
     }
 }
+
+/// Records the `attempt` input on every tick and returns `status`.
+#[bt_node(SyncActionNode)]
+struct AttemptNode {
+    attempts: Arc<Mutex<Vec<usize>>>,
+}
+
+impl AsyncTick for AttemptNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let attempt: usize = self.config.get_input("attempt").await?;
+            self.attempts.lock().unwrap().push(attempt);
+
+            self.config.get_input("status").await
+        })
+    }
+}
+
+impl NodePorts for AttemptNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("attempt"), input_port!("status"))
+    }
+}
+
+impl AsyncHalt for AttemptNode {}
+
+#[test]
+fn current_attempt() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="retry">
+                <Retry num_attempts="3" current_attempt="{attempt}">
+                    <AttemptNode attempt="{attempt}" status="Failure" />
+                </Retry>
+            </BehaviorTree>
+            <BehaviorTree ID="repeat">
+                <Repeat num_cycles="3" current_attempt="{attempt}">
+                    <AttemptNode attempt="{attempt}" status="Success" />
+                </Repeat>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "AttemptNode", AttemptNode, attempts.clone());
+    factory.register_bt_from_text(xml).unwrap();
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "retry").unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+    assert_eq!(*attempts.lock().unwrap(), vec![1, 2, 3]);
+
+    attempts.lock().unwrap().clear();
+
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "repeat")
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(*attempts.lock().unwrap(), vec![1, 2, 3]);
+}
+
+#[test]
+fn force_skip() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <ForceSkip>
+                    <AttemptNode attempt="1" status="Success" />
+                </ForceSkip>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let attempts = Arc::new(Mutex::new(Vec::new()));
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "AttemptNode", AttemptNode, attempts.clone());
+    factory.register_bt_from_text(xml).unwrap();
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Skipped);
+    tree.halt_tree();
+
+    // The child is never ticked
+    assert!(attempts.lock().unwrap().is_empty());
+}
+
+#[test]
+fn force_running() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="completing">
+                <ForceRunning>
+                    <StatusNode status="Failure" />
+                </ForceRunning>
+            </BehaviorTree>
+            <BehaviorTree ID="halting">
+                <ForceRunning>
+                    <RunUntilHaltedNode halted="{halted}" />
+                </ForceRunning>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    factory.register_bt_from_text(xml).unwrap();
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "completing")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+
+    // Halts are forwarded to the child
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "halting")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), None);
+
+    tree.halt_tree();
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+}
+
+#[tokio::test(start_paused = true)]
+async fn delay() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Delay delay_msec="50">
+                    <RunForNode iters="1" status="Failure" />
+                </Delay>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+    factory.register_bt_from_text(xml).unwrap();
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .instantiate_async_tree(&blackboard, "main")
+        .await
+        .unwrap();
+
+    // The child isn't ticked until the delay has passed
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(1)).await;
+    for _ in 0..2 {
+        assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    }
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    // Halting cancels the delay, the next tick waits again
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(50)).await;
+    tree.halt_tree().await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(1)).await;
+    // The child kept its count, so it completes right after starting
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeout() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Timeout msec="50">
+                    <RunUntilHaltedNode halted="{halted}" />
+                </Timeout>
+            </BehaviorTree>
+            <BehaviorTree ID="completing">
+                <Timeout msec="50">
+                    <RunForNode iters="2" status="Success" />
+                </Timeout>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    factory.register_bt_from_text(xml).unwrap();
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .instantiate_async_tree(&blackboard, "main")
+        .await
+        .unwrap();
+
+    // The child is halted once the time is up
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get::<bool>("halted").await, Some(true));
+
+    // Halting clears the timeout, so it starts over
+    blackboard.set("halted", false).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(50)).await;
+    tree.halt_tree().await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    // A child that completes in time isn't affected
+    let mut tree = factory
+        .instantiate_async_tree(&blackboard, "completing")
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn cooldown() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Cooldown rate_msec="50">
+                    <RunForNode iters="1" status="Success" />
+                </Cooldown>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+    factory.register_bt_from_text(xml).unwrap();
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .instantiate_async_tree(&blackboard, "main")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    // The child isn't ticked again until the cooldown is over, even after
+    // a halt
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    tree.halt_tree().await;
+    tokio::time::advance(Duration::from_millis(49)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    tokio::time::advance(Duration::from_millis(1)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+}
+
+#[test]
+fn loop_queue() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <LoopInt queue="{queue}" value="{item}">
+                    <Script code="sum := sum + item" />
+                </LoopInt>
+            </BehaviorTree>
+
+            <BehaviorTree ID="failing">
+                <LoopInt queue="{queue}" value="{item}" if_empty="FAILURE">
+                    <ScriptCondition code="item < 3" />
+                </LoopInt>
+            </BehaviorTree>
+
+            <BehaviorTree ID="halting">
+                <LoopBytes queue="{bytes}" value="{byte}">
+                    <RunForNode iters="1" status="Success" />
+                </LoopBytes>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "RunForNode", RunForNode);
+    factory.register_loop_node::<u8>("LoopBytes");
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("sum", 0_i64);
+
+    // Without a queue, the node fails
+    let mut tree = factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // One item per tick
+    blackboard.set_sync("queue", vec![1_i64, 2, 3]);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<i64>("sum"), Some(6));
+    assert_eq!(blackboard.get_exact_sync::<Vec<i64>>("queue"), Some(vec![]));
+
+    // An empty queue returns "if_empty"
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "failing")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    // A failing child stops the loop
+    blackboard.set_sync("queue", vec![1_i64, 3, 2]);
+    let sequence_id = blackboard.sequence_id_sync("queue").unwrap();
+    let mut changes = blackboard.subscribe_sync("queue");
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    // Taking an item is a change of the queue
+    assert_eq!(blackboard.sequence_id_sync("queue"), Some(sequence_id + 1));
+    assert!(changes.try_recv().is_ok());
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(
+        blackboard.get_exact_sync::<Vec<i64>>("queue"),
+        Some(vec![2])
+    );
+
+    // Halting keeps the rest of the queue
+    blackboard.set_sync("bytes", vec![7_u8, 8]);
+    let mut tree = factory
+        .instantiate_sync_tree(&blackboard, "halting")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get_exact_sync::<u8>("byte"), Some(7));
+    tree.halt_tree();
+    assert_eq!(blackboard.get_exact_sync::<Vec<u8>>("bytes"), Some(vec![8]));
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_exact_sync::<u8>("byte"), Some(8));
+}
+
+#[test]
+fn simple_decorator() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <FailIfSkipped>
+                        <StatusNode status="Success" />
+                    </FailIfSkipped>
+                    <FailIfSkipped>
+                        <StatusNode status="Skipped" />
+                    </FailIfSkipped>
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_simple_decorator!(factory, "FailIfSkipped", |status| match status {
+        NodeStatus::Skipped => NodeStatus::Failure,
+        status => status,
+    });
+
+    let mut blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    // A running child is halted when the decorator completes anyway
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <AlwaysSucceed>
+                    <RunUntilHaltedNode halted="{halted}" />
+                </AlwaysSucceed>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    register_simple_decorator!(factory, "AlwaysSucceed", |_| NodeStatus::Success);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<bool>("halted"), Some(true));
+
+    // The decorator expects a child, like any other decorator
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <FailIfSkipped />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    assert!(factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .is_err());
+}
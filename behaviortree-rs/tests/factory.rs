@@ -1,8 +1,24 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
 use behaviortree_rs::{
-    basic_types::NodeStatus, blackboard::Blackboard, macros::register_action_node, tree::Factory,
+    basic_types::{
+        FromString, NodeStatus, NodeType, PortDirection, PortInfo, PortsList, TreeNodeManifest,
+    },
+    blackboard::{Blackboard, BlackboardError},
+    bt_node,
+    derive::EnumFromString,
+    macros::{define_ports, input_port, output_port, raw_input_port, register_action_node},
+    nodes::{
+        action::TestNodeConfig, AsyncHalt, AsyncTick, NodeConfig, NodeError, NodePorts, NodeResult,
+    },
+    tree::{Factory, ParseError, SubstitutionRule, TreeBuilder},
 };
+use futures::future::BoxFuture;
 
-use crate::nodes::{DataNode, EchoNode, StatusNode};
+use crate::nodes::{DataNode, EchoNode, RunUntilHaltedNode, StatusNode};
 
 mod nodes;
 
@@ -207,6 +223,215 @@ fn node_not_registered() {
     assert!(tree.is_err());
 }
 
+#[tokio::test]
+async fn input_expressions() {
+    let ports = define_ports!(
+        input_port!("timeout"),
+        input_port!("enabled"),
+        input_port!("ratio"),
+        raw_input_port!("raw")
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set("base", 1500u64).await;
+    blackboard.set("mode", String::from("fast")).await;
+
+    let mut config = NodeConfig::new(blackboard);
+    config.set_manifest(Arc::new(TreeNodeManifest::new(
+        NodeType::Action,
+        "Test",
+        ports,
+        "",
+    )));
+    config.add_port(
+        PortDirection::Input,
+        "timeout".into(),
+        "eval: base * 2".into(),
+    );
+    config.add_port(
+        PortDirection::Input,
+        "enabled".into(),
+        " eval: base > 1000 && mode == 'fast' ".into(),
+    );
+    config.add_port(
+        PortDirection::Input,
+        "ratio".into(),
+        "eval: base / 1000".into(),
+    );
+    config.add_port(PortDirection::Input, "raw".into(), "eval: 1 + 1".into());
+
+    assert_eq!(config.get_input::<u64>("timeout").await.unwrap(), 3000);
+    assert!(config.get_input::<bool>("enabled").await.unwrap());
+    // Integer division, as both operands are integers
+    assert_eq!(config.get_input::<f64>("ratio").await.unwrap(), 1.0);
+    assert_eq!(
+        config.get_input::<String>("raw").await.unwrap(),
+        "eval: 1 + 1"
+    );
+
+    // The result must be convertible to the port type
+    assert!(matches!(
+        config.get_input::<bool>("timeout").await,
+        Err(NodeError::InvalidPortValue(..))
+    ));
+
+    config.add_port(
+        PortDirection::Input,
+        "timeout".into(),
+        "eval: missing * 2".into(),
+    );
+    assert!(matches!(
+        config.get_input::<u64>("timeout").await,
+        Err(NodeError::ExpressionError(port, _)) if port == "timeout"
+    ));
+}
+
+#[tokio::test]
+async fn error_hints() {
+    let ports = define_ports!(input_port!("target"), input_port!("count"));
+
+    let mut config = NodeConfig::new(Blackboard::create());
+    config.set_manifest(Arc::new(TreeNodeManifest::new(
+        NodeType::Action,
+        "Test",
+        ports,
+        "",
+    )));
+
+    let err = config.get_input::<String>("target").await.unwrap_err();
+    assert!(matches!(&err, NodeError::PortError(port) if port == "target"));
+    assert!(err.to_string().contains("Hint: is the port set in the XML"));
+
+    config.add_port(PortDirection::Input, "target".into(), "{goal}".into());
+    let err = config.get_input::<String>("target").await.unwrap_err();
+    assert!(matches!(&err, NodeError::BlackboardError(key) if key == "goal"));
+    assert!(err
+        .to_string()
+        .contains("Hint: is the key set before this node runs?"));
+
+    config.add_port(PortDirection::Input, "count".into(), "many".into());
+    let err = config.get_input::<u32>("count").await.unwrap_err();
+    assert!(matches!(
+        &err,
+        NodeError::InvalidPortValue(port, value, type_name)
+            if port == "count" && value == "many" && type_name == "u32"
+    ));
+    assert!(err
+        .to_string()
+        .contains("Hint: check the port's value in the XML"));
+
+    // A string in the blackboard is parsed like a port value
+    config
+        .blackboard_mut()
+        .set("count", String::from("a few"))
+        .await;
+    config.add_port(PortDirection::Input, "count".into(), "{count}".into());
+    let err = config.get_input::<u32>("count").await.unwrap_err();
+    assert!(matches!(
+        &err,
+        NodeError::InvalidPortValue(port, value, type_name)
+            if port == "count" && value == "a few" && type_name == "u32"
+    ));
+
+    // An entry of another type is a type mismatch, not a missing entry
+    config.blackboard_mut().set("count", 2.5_f64).await;
+    let err = config.get_input::<u32>("count").await.unwrap_err();
+    assert!(matches!(
+        &err,
+        NodeError::BlackboardTypeMismatch(port, BlackboardError::TypeMismatch { key, expected, found })
+            if port == "count" && key == "count" && *expected == "u32" && *found == "f64"
+    ));
+    assert!(err.to_string().contains("is of type [f64], expected [u32]"));
+}
+
+#[derive(Clone, Debug, PartialEq, EnumFromString)]
+enum Speed {
+    Slow,
+    Fast,
+}
+
+#[derive(Clone, Debug, PartialEq, EnumFromString)]
+struct Meters(f64);
+
+#[tokio::test]
+async fn enum_from_string() {
+    let ports = define_ports!(
+        input_port!("speed"),
+        input_port!("default_speed", Speed::Fast),
+        input_port!("distance")
+    );
+
+    let mut config = NodeConfig::new(Blackboard::create());
+    config.set_manifest(Arc::new(TreeNodeManifest::new(
+        NodeType::Action,
+        "Test",
+        ports,
+        "",
+    )));
+    config.add_port(PortDirection::Input, "speed".into(), "SLOW".into());
+    config.add_port(PortDirection::Input, "default_speed".into(), "".into());
+    config.add_port(PortDirection::Input, "distance".into(), "2.5".into());
+
+    assert_eq!(
+        config.get_input::<Speed>("speed").await.unwrap(),
+        Speed::Slow
+    );
+    assert_eq!(
+        config.get_input::<Speed>("default_speed").await.unwrap(),
+        Speed::Fast
+    );
+    assert_eq!(
+        config.get_input::<Meters>("distance").await.unwrap(),
+        Meters(2.5)
+    );
+
+    let err = Speed::from_string("medium").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "[medium] isn't a variant of Speed. Expected one of: Slow, Fast"
+    );
+    assert!(Meters::from_string("far").is_err());
+}
+
+#[test]
+fn allow_unknown_nodes() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <NotImplementedYet target="door" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    // Strict by default
+    assert!(matches!(
+        factory.create_sync_tree_from_text(xml.clone(), &blackboard),
+        Err(ParseError::UnknownNode(name)) if name == "NotImplementedYet"
+    ));
+
+    factory.set_allow_unknown_nodes(true);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    factory.set_unknown_node_status(NodeStatus::Failure);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+}
+
 #[test]
 fn ignore_treenodesmodel() {
     nodes::test_setup();
@@ -334,3 +559,1534 @@ fn async_test() {
         assert!(res.is_ok());
     });
 }
+
+#[bt_node(SyncActionNode)]
+struct NamePortNode {}
+
+impl AsyncTick for NamePortNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Success) })
+    }
+}
+
+impl NodePorts for NamePortNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("name"))
+    }
+}
+
+impl AsyncHalt for NamePortNode {}
+
+#[test]
+fn reserved_attributes() {
+    nodes::test_setup();
+
+    // `name` is consumed by the parser, not passed as a port
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode name="my_status" status="Success" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    // A node can't declare a port with a reserved name
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <NamePortNode />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "NamePortNode", NamePortNode);
+    let blackboard = Blackboard::create();
+
+    let tree = factory.create_sync_tree_from_text(xml, &blackboard);
+
+    assert!(matches!(
+        tree,
+        Err(ParseError::ReservedPortName(port, node, _)) if port == "name" && node == "NamePortNode"
+    ));
+}
+
+#[test]
+fn subtree_as_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Check />
+                    <Check result="{outer}" />
+                    <Check name="named_check" result="Success" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="check">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.register_subtree_as_node(
+        "Check",
+        "check",
+        define_ports!(input_port!("result", NodeStatus::Success)),
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("outer", NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Failure);
+
+    blackboard.set_sync("outer", NodeStatus::Success);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    // Attributes that aren't declared ports are rejected
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Check status="Success" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="check">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.register_subtree_as_node(
+        "Check",
+        "check",
+        define_ports!(input_port!("result"), output_port!("unused")),
+    );
+
+    let tree = factory.create_sync_tree_from_text(xml, &Blackboard::create());
+
+    assert!(matches!(tree, Err(ParseError::InvalidPort(port, _, _)) if port == "status"));
+}
+
+#[test]
+fn isolated_subtree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" _isolated="true" _autoremap="true" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("result", NodeStatus::Success);
+
+    // The parent entry isn't visible despite `_autoremap`
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert!(tree.tick_once().is_err());
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" _isolated="true" result="{outer}" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("outer", NodeStatus::Failure);
+
+    // Explicit remapping still works
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+}
+
+#[test]
+fn shared_subtree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SubTree ID="sub" _shared="true" />
+                    <StatusNode status="{inner}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Sequence>
+                    <StatusNode status="{result}" />
+                    <SetBlackboard output_key="inner" value="Failure" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("result", NodeStatus::Success);
+
+    // The subtree reads the parent's entry and writes to the parent's
+    // blackboard, both without remapping
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(
+        blackboard.get_sync::<NodeStatus>("inner"),
+        Some(NodeStatus::Failure)
+    );
+
+    // Ports can't be remapped without a scope of their own
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" _shared="true" result="{outer}" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let result = factory.create_sync_tree_from_text(xml, &blackboard);
+    assert!(matches!(result, Err(ParseError::InvalidPort(port, _, _)) if port == "result"));
+
+    // A shared subtree can't be isolated from its parent
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" _shared="true" _isolated="true" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="{result}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    let result = factory.create_sync_tree_from_text(xml, &blackboard);
+    assert!(matches!(
+        result,
+        Err(ParseError::ConflictingAttributes(attribute, other, _))
+            if attribute == "_shared" && other == "_isolated"
+    ));
+}
+
+#[tokio::test]
+async fn main_tree_selection() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="succeed">
+                <StatusNode status="Success" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="fail">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_sync_tree_from_text_with_main(xml.clone(), &blackboard, "fail")
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_async_tree_from_text_with_main(xml.clone(), &blackboard, "succeed")
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    assert!(matches!(
+        factory.create_sync_tree_from_text_with_main(xml.clone(), &blackboard, "unknown"),
+        Err(ParseError::UnknownTree(id)) if id == "unknown"
+    ));
+
+    // Without `main_tree_to_execute`, the main tree can be set afterwards
+    assert!(matches!(
+        factory.set_main_tree_id("unknown"),
+        Err(ParseError::UnknownTree(id)) if id == "unknown"
+    ));
+    factory.set_main_tree_id("fail").unwrap();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+}
+
+#[test]
+fn include_files() {
+    nodes::test_setup();
+
+    let dir = std::env::temp_dir().join(format!("bt_include_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+
+    // Nested includes are relative to the including file
+    std::fs::write(
+        dir.join("sub.xml"),
+        r#"
+        <root main_tree_to_execute="sub">
+            <include path="nested/leaf.xml" />
+            <BehaviorTree ID="sub">
+                <SubTree ID="leaf" />
+            </BehaviorTree>
+        </root>
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("nested/leaf.xml"),
+        r#"
+        <root>
+            <BehaviorTree ID="leaf">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("a.xml"),
+        r#"<root><include path="b.xml"></include></root>"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("b.xml"),
+        r#"<root><include path="a.xml" /></root>"#,
+    )
+    .unwrap();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <include path="sub.xml" />
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.set_include_base_path(&dir);
+
+    // The included file doesn't change the main tree
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    let result =
+        factory.register_bt_from_text(r#"<root><include path="a.xml" /></root>"#.to_string());
+    assert!(matches!(
+        result,
+        Err(ParseError::IncludeError(path, e))
+            if path.ends_with("a.xml") && e.kind() == std::io::ErrorKind::InvalidInput
+    ));
+
+    let result =
+        factory.register_bt_from_text(r#"<root><include path="missing.xml" /></root>"#.to_string());
+    assert!(matches!(
+        result,
+        Err(ParseError::IncludeError(path, e))
+            if path == dir.join("missing.xml") && e.kind() == std::io::ErrorKind::NotFound
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn from_file() {
+    nodes::test_setup();
+
+    let dir = std::env::temp_dir().join(format!("bt_from_file_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Includes are relative to the file, not the include base path
+    std::fs::write(
+        dir.join("main.xml"),
+        r#"
+        <root main_tree_to_execute="main">
+            <include path="sub.xml" />
+            <BehaviorTree ID="main">
+                <SubTree ID="sub" />
+            </BehaviorTree>
+        </root>
+        "#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("sub.xml"),
+        r#"
+        <root>
+            <BehaviorTree ID="sub">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+        "#,
+    )
+    .unwrap();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let mut tree = factory
+        .create_sync_tree_from_file(dir.join("main.xml"), &Blackboard::create())
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_async_tree_from_file(dir.join("main.xml"), &Blackboard::create())
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    let result = factory.register_bt_from_file(dir.join("missing.xml"));
+    assert!(matches!(
+        result,
+        Err(ParseError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn from_reader() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#;
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_sync_tree_from_reader(xml.as_bytes(), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+
+    let mut tree = factory
+        .create_async_tree_from_reader(std::io::Cursor::new(xml), &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    // Invalid UTF-8 in a tag name goes through the usual UTF-8 error
+    let mut xml = b"<root><BehaviorTree ID=\"main\"><".to_vec();
+    xml.extend_from_slice(&[0xC3, 0x28]);
+    xml.extend_from_slice(b" /></BehaviorTree></root>");
+
+    let mut factory = Factory::new();
+    let tree = factory.create_sync_tree_from_reader(xml.as_slice(), &blackboard);
+    assert!(matches!(tree, Err(ParseError::Utf8Error(_))));
+}
+
+#[tokio::test]
+async fn on_tree_built() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <StatusNode status="Success" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let built = Arc::new(AtomicUsize::new(0));
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let counter = Arc::clone(&built);
+    factory.on_tree_built(move |_tree| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let blackboard = Blackboard::create();
+
+    factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(built.load(Ordering::SeqCst), 1);
+
+    factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+
+    // Failed builds don't invoke the callback
+    assert!(factory
+        .instantiate_sync_tree(&blackboard, "missing")
+        .is_err());
+    assert_eq!(built.load(Ordering::SeqCst), 2);
+}
+
+#[bt_node(SyncActionNode)]
+struct MixedPortsNode {}
+
+impl AsyncTick for MixedPortsNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Success) })
+    }
+}
+
+impl NodePorts for MixedPortsNode {
+    fn provided_ports(&self) -> PortsList {
+        let mut target = PortInfo::new(PortDirection::Input);
+        target.set_description(String::from("Where to go"));
+
+        define_ports!(
+            ("target", target),
+            input_port!("speed", type: f64, 1.5),
+            output_port!("reached", type: bool)
+        )
+    }
+}
+
+impl AsyncHalt for MixedPortsNode {}
+
+#[cfg(feature = "serde")]
+#[test]
+fn export_node_library_json() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "MixedPortsNode", MixedPortsNode);
+    factory.register_subtree_as_node("MyBehavior", "behavior", define_ports!(input_port!("goal")));
+
+    let json = factory.export_node_library_json().unwrap();
+    let library: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let nodes = library.as_array().unwrap();
+
+    let node = nodes.iter().find(|n| n["id"] == "MixedPortsNode").unwrap();
+    assert_eq!(
+        node,
+        &serde_json::json!({
+            "type": "Action",
+            "id": "MixedPortsNode",
+            "description": "",
+            "ports": [
+                { "name": "reached", "direction": "Output", "description": "", "default": null, "type": "bool" },
+                { "name": "speed", "direction": "Input", "description": "", "default": "1.5", "type": "f64" },
+                { "name": "target", "direction": "Input", "description": "Where to go", "default": null },
+            ],
+        })
+    );
+
+    // Builtin nodes and subtrees registered as nodes are included too
+    let node = nodes.iter().find(|n| n["id"] == "Retry").unwrap();
+    assert_eq!(node["type"], "Decorator");
+    let ports = node["ports"].as_array().unwrap();
+    assert!(ports.iter().any(|port| port["name"] == "num_attempts"));
+    let node = nodes.iter().find(|n| n["id"] == "MyBehavior").unwrap();
+    assert_eq!(node["type"], "SubTree");
+    assert_eq!(node["ports"][0]["name"], "goal");
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+struct Position2D {
+    x: f64,
+    y: f64,
+}
+
+#[cfg(feature = "serde")]
+impl FromString for Position2D {
+    type Err = serde_json::Error;
+
+    fn from_string(value: impl AsRef<str>) -> Result<Self, Self::Err> {
+        behaviortree_rs::basic_types::from_json_string(value)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn json_port_values() {
+    let ports = define_ports!(
+        input_port!("pointA"),
+        input_port!("pointB"),
+        input_port!("pointE", r#"json:{"x": 9, "y": 10}"#),
+        input_port!("nested", serde_json::json!({ "points": [1, 2] }))
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard
+        .set("point", String::from(r#"json:{"x": 3, "y": 4}"#))
+        .await;
+
+    let mut config = NodeConfig::new(blackboard);
+    config.set_manifest(Arc::new(TreeNodeManifest::new(
+        NodeType::Action,
+        "Test",
+        ports,
+        "",
+    )));
+    config.add_port(
+        PortDirection::Input,
+        "pointA".into(),
+        r#"json:{"x": 1, "y": 2}"#.into(),
+    );
+    config.add_port(PortDirection::Input, "pointB".into(), "{point}".into());
+    config.add_port(PortDirection::Input, "pointE".into(), "".into());
+    config.add_port(PortDirection::Input, "nested".into(), "".into());
+
+    assert_eq!(
+        config.get_input::<Position2D>("pointA").await.unwrap(),
+        Position2D { x: 1.0, y: 2.0 }
+    );
+    assert_eq!(
+        config.get_input::<Position2D>("pointB").await.unwrap(),
+        Position2D { x: 3.0, y: 4.0 }
+    );
+    assert_eq!(
+        config.get_input::<Position2D>("pointE").await.unwrap(),
+        Position2D { x: 9.0, y: 10.0 }
+    );
+    let nested: serde_json::Value = config.get_input("nested").await.unwrap();
+    assert_eq!(nested["points"][1], 2);
+
+    config.add_port(
+        PortDirection::Input,
+        "pointA".into(),
+        r#"json:{"x": 1}"#.into(),
+    );
+    assert!(matches!(
+        config.get_input::<Position2D>("pointA").await,
+        Err(NodeError::InvalidPortValue(port, _, _)) if port == "pointA"
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn parse_error_json() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    let blackboard = Blackboard::create();
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <NotImplementedYet />
+            </BehaviorTree>
+        </root>
+    "#;
+    let err = factory
+        .create_sync_tree_from_text(xml.to_string(), &blackboard)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), "UnknownNode");
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        serde_json::json!({
+            "kind": "UnknownNode",
+            "message": "Attempted to parse node with unregistered name: NotImplementedYet",
+            "fields": { "node": "NotImplementedYet" },
+        })
+    );
+
+    let err = ParseError::InvalidPort(
+        String::from("speed"),
+        String::from("MoveTo"),
+        vec![String::from("goal")],
+    );
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        serde_json::json!({
+            "kind": "InvalidPort",
+            "message": err.to_string(),
+            "fields": { "port": "speed", "node": "MoveTo", "ports": ["goal"] },
+        })
+    );
+
+    // Variants without named values still have an (empty) fields object
+    let value = serde_json::to_value(ParseError::NoMainTree).unwrap();
+    assert_eq!(value["kind"], "NoMainTree");
+    assert_eq!(value["fields"], serde_json::json!({}));
+}
+
+#[test]
+fn empty_control_nodes() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Sequence />
+                    <Parallel />
+                    <ParallelAll max_failures="1" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Fallback />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    // Other control nodes need children unless they opt in
+    let err = factory
+        .create_sync_tree_from_text(xml.clone(), &blackboard)
+        .unwrap_err();
+    assert!(matches!(err, ParseError::MissingChild(..)));
+    assert_eq!(err.to_string(), "Node [Fallback] must have a child");
+
+    factory.allow_empty_control_node("Fallback");
+    assert!(factory.create_sync_tree_from_text(xml, &blackboard).is_ok());
+
+    // Same for a decorator
+    let xml = r#"
+        <root>
+            <BehaviorTree ID="main">
+                <Inverter />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let err = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Node [Inverter] must have a child");
+}
+
+#[tokio::test]
+async fn input_whitespace() {
+    let ports = define_ports!(
+        input_port!("number"),
+        input_port!("blank"),
+        input_port!("with_default", 3),
+        input_port!("pointer"),
+        raw_input_port!("raw")
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set("value", 7u32).await;
+
+    let mut config = NodeConfig::new(blackboard);
+    config.set_manifest(Arc::new(TreeNodeManifest::new(
+        NodeType::Action,
+        "Test",
+        ports,
+        "",
+    )));
+    config.add_port(PortDirection::Input, "number".into(), "  42\n ".into());
+    config.add_port(PortDirection::Input, "blank".into(), " \t ".into());
+    config.add_port(PortDirection::Input, "with_default".into(), "   ".into());
+    config.add_port(PortDirection::Input, "pointer".into(), " {value} ".into());
+    config.add_port(PortDirection::Input, "raw".into(), "  a  b ".into());
+
+    assert_eq!(config.get_input::<u32>("number").await.unwrap(), 42);
+    assert_eq!(config.get_input::<u32>("with_default").await.unwrap(), 3);
+    assert_eq!(config.get_input::<u32>("pointer").await.unwrap(), 7);
+    assert_eq!(config.get_input::<String>("raw").await.unwrap(), "  a  b ");
+    assert!(config.get_input::<String>("blank").await.is_err());
+
+    // Unset ports are `None` for `get_input_opt()`
+    assert_eq!(config.get_input_opt::<String>("blank").await.unwrap(), None);
+    assert_eq!(config.get_input_opt::<u32>("missing").await.unwrap(), None);
+    assert_eq!(
+        config.get_input_opt::<u32>("number").await.unwrap(),
+        Some(42)
+    );
+    assert_eq!(config.get_input_str("number").await.as_deref(), Some("42"));
+
+    // `get_input_or()` only falls back to the default when the value can't be used
+    assert_eq!(config.get_input_or("missing", 5u32).await, 5);
+    assert_eq!(config.get_input_or("blank", 5u32).await, 5);
+    assert_eq!(config.get_input_or("with_default", 5u32).await, 3);
+    assert_eq!(config.get_input_or("pointer", 5u32).await, 7);
+    assert_eq!(config.get_input_or("raw", 5u32).await, 5);
+    config.add_port(PortDirection::Input, "pointer".into(), "{unknown}".into());
+    assert_eq!(config.get_input_or("pointer", 5u32).await, 5);
+
+    // An entry of another type isn't converted, and is reported with a warning
+    config.blackboard.set("signed", 9i64).await;
+    config.add_port(PortDirection::Input, "pointer".into(), "{signed}".into());
+    assert_eq!(config.get_input_or("pointer", 5u32).await, 5);
+    assert_eq!(config.get_input_or("pointer", 5i64).await, 9);
+}
+
+#[tokio::test]
+async fn input_vec() {
+    let mut blackboard = Blackboard::create();
+    blackboard.set("ids", vec![4u32, 5]).await;
+    blackboard.set("text", "7;8").await;
+
+    let mut config = NodeConfig::new(blackboard);
+    config.add_port(PortDirection::Input, "commas".into(), "1,2,3".into());
+    config.add_port(PortDirection::Input, "mixed".into(), " 1 ; 2, 3 ".into());
+    config.add_port(PortDirection::Input, "brackets".into(), "[1, 2, 3]".into());
+    config.add_port(PortDirection::Input, "empty".into(), "[]".into());
+    config.add_port(PortDirection::Input, "vec".into(), "{ids}".into());
+    config.add_port(PortDirection::Input, "string".into(), "{text}".into());
+    config.add_port(PortDirection::Input, "invalid".into(), "1, two, 3".into());
+
+    for port in ["commas", "mixed", "brackets"] {
+        assert_eq!(
+            config.get_input_vec::<u32>(port).await.unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+    assert!(config
+        .get_input_vec::<u32>("empty")
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        config.get_input_vec::<u32>("vec").await.unwrap(),
+        vec![4, 5]
+    );
+    assert_eq!(
+        config.get_input_vec::<u64>("string").await.unwrap(),
+        vec![7, 8]
+    );
+    assert_eq!(
+        config.get_input_vec::<String>("mixed").await.unwrap(),
+        vec!["1", "2", "3"]
+    );
+
+    match config.get_input_vec::<u32>("invalid").await {
+        Err(NodeError::PortElementParseError(port, index, element, _)) => {
+            assert_eq!(port, "invalid");
+            assert_eq!(index, 1);
+            assert_eq!(element, "two");
+        }
+        other => panic!("Expected PortElementParseError, got {other:?}"),
+    }
+    assert!(matches!(
+        config.get_input_vec::<u32>("missing").await,
+        Err(NodeError::PortError(_))
+    ));
+}
+
+#[test]
+fn output_port_literal() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <MixedPortsNode target="1,2" reached="yes" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "MixedPortsNode", MixedPortsNode);
+    let blackboard = Blackboard::create();
+
+    let err = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap_err();
+    assert!(matches!(
+        &err,
+        ParseError::InvalidOutputPortValue(port, path, value)
+            if port == "reached" && path == "Sequence/MixedPortsNode" && value == "yes"
+    ));
+
+    // Blackboard pointers and "=" are fine
+    for reached in ["{reached}", "="] {
+        let xml = format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <MixedPortsNode target="1,2" reached="{reached}" />
+                </BehaviorTree>
+            </root>
+            "#
+        );
+
+        assert!(factory.create_sync_tree_from_text(xml, &blackboard).is_ok());
+    }
+}
+
+#[bt_node(SyncActionNode)]
+struct StrictNode {}
+
+impl AsyncTick for StrictNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Success) })
+    }
+}
+
+impl NodePorts for StrictNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(
+            input_port!("target"),
+            input_port!("speed", 1.5),
+            output_port!("reached")
+        )
+    }
+
+    fn strict_ports(&self) -> bool {
+        true
+    }
+}
+
+impl AsyncHalt for StrictNode {}
+
+#[test]
+fn strict_ports() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StrictNode", StrictNode);
+    let blackboard = Blackboard::create();
+
+    let tree = |ports: &str| {
+        format!(
+            r#"
+            <root main_tree_to_execute="main">
+                <BehaviorTree ID="main">
+                    <StrictNode {ports} />
+                </BehaviorTree>
+            </root>
+            "#
+        )
+    };
+
+    // Defaults don't count, and neither do blank values
+    for ports in [r#"target="home""#, r#"target="home" speed=" ""#] {
+        let result = factory.create_sync_tree_from_text(tree(ports), &blackboard);
+        assert!(matches!(
+            result,
+            Err(ParseError::MissingRequiredPort(port, path)) if port == "speed" && path == "StrictNode"
+        ));
+    }
+
+    // Output ports aren't required
+    let mut tree = factory
+        .create_sync_tree_from_text(tree(r#"target="home" speed="2""#), &blackboard)
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn node_description() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(
+        factory,
+        "MixedPortsNode",
+        MixedPortsNode,
+        description = "Drives to the target"
+    );
+    register_action_node!(factory, "StrictNode", StrictNode);
+    // A description comes before the node's parameters
+    register_action_node!(
+        factory,
+        "DataNode",
+        DataNode,
+        description = "Holds data",
+        String::from("hello")
+    );
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <MixedPortsNode target="home" />
+                    <StrictNode target="home" speed="2" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+    let mut descriptions = Vec::new();
+    tree.visit_nodes(|node| descriptions.push(node.description().to_string()));
+    assert_eq!(descriptions, vec!["", "Drives to the target", ""]);
+
+    let manifests = factory.node_manifests();
+    let manifest = manifests
+        .iter()
+        .find(|m| m.registration_id == "MixedPortsNode")
+        .unwrap();
+    assert_eq!(manifest.description, "Drives to the target");
+    let manifest = manifests
+        .iter()
+        .find(|m| m.registration_id == "DataNode")
+        .unwrap();
+    assert_eq!(manifest.description, "Holds data");
+}
+
+#[test]
+fn typed_ports() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "MixedPortsNode", MixedPortsNode);
+
+    let manifests = factory.node_manifests();
+    let manifest = manifests
+        .iter()
+        .find(|m| m.registration_id == "MixedPortsNode")
+        .unwrap();
+    let ports = &manifest.ports;
+    assert_eq!(ports["speed"].type_name(), Some("f64"));
+    assert_eq!(ports["speed"].default_value_str().as_deref(), Some("1.5"));
+    assert_eq!(ports["reached"].type_name(), Some("bool"));
+    assert_eq!(ports["target"].type_name(), None);
+
+    let (_, port) = raw_input_port!("path", type: Vec<String>);
+    assert!(port.is_raw());
+    assert_eq!(port.type_name(), Some(std::any::type_name::<Vec<String>>()));
+}
+
+#[test]
+fn write_tree_nodes_model() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "MixedPortsNode", MixedPortsNode);
+    factory.register_subtree_as_node("MyBehavior", "behavior", define_ports!(input_port!("goal")));
+
+    let model = factory.write_tree_nodes_model();
+    assert!(model.starts_with("<root BTCPP_format=\"4\">\n    <TreeNodesModel>\n"));
+    assert!(model.contains(
+        r#"
+        <Action ID="MixedPortsNode">
+            <output_port name="reached" type="bool"/>
+            <input_port name="speed" type="f64" default="1.5"/>
+            <input_port name="target">Where to go</input_port>
+        </Action>
+"#
+    ));
+    assert!(model.contains(
+        r#"
+        <SubTree ID="MyBehavior">
+            <input_port name="goal"/>
+        </SubTree>
+"#
+    ));
+
+    // Builtin nodes are included too
+    assert!(model.contains(r#"<Decorator ID="Retry">"#));
+    assert!(model.contains(r#"<Control ID="Sequence"/>"#));
+}
+
+#[test]
+fn validate_tree() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <UnknownAction />
+                    <StatusNode state="Success" />
+                    <UnknownControl>
+                        <AnotherUnknownAction />
+                    </UnknownControl>
+                    <Inverter></Inverter>
+                    <SubTree ID="missing" />
+                    <SubTree ID="sub" />
+                    <StatusNode status="Success" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Inverter>
+                    <StatusNode />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let errors = factory.validate_tree(xml).unwrap_err();
+    let errors: Vec<String> = errors
+        .into_iter()
+        .map(|e| match e {
+            ParseError::UnknownNode(name) => format!("unknown {name}"),
+            ParseError::InvalidPort(port, node, _) => format!("port {node}.{port}"),
+            ParseError::MissingChild(node) => format!("child {node}"),
+            ParseError::UnknownTree(id) => format!("tree {id}"),
+            e => panic!("unexpected error: {e}"),
+        })
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "unknown UnknownAction",
+            "port StatusNode.state",
+            "unknown UnknownControl",
+            "unknown AnotherUnknownAction",
+            "child Inverter",
+            "tree missing",
+        ]
+    );
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Inverter>
+                    <StatusNode status="Success" />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    assert!(factory.validate_tree(xml).is_ok());
+
+    // The validated trees aren't kept
+    assert!(matches!(
+        factory.instantiate_sync_tree(&Blackboard::create(), "main"),
+        Err(ParseError::UnknownTree(id)) if id == "main"
+    ));
+
+    // Only one error is reported when building the tree
+    let result = factory.create_sync_tree_from_text(
+        r#"<root><BehaviorTree ID="main"><Sequence><A /><B /></Sequence></BehaviorTree></root>"#
+            .to_string(),
+        &Blackboard::create(),
+    );
+    assert!(matches!(result, Err(ParseError::UnknownNode(name)) if name == "A"));
+
+    // Malformed XML ends the validation
+    let errors = factory
+        .validate_tree(r#"<root><BehaviorTree ID="main"><A></B></BehaviorTree></root>"#.to_string())
+        .unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], ParseError::XMLError(_)));
+
+    // Trees registered before are kept, and still the main tree
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory
+        .register_bt_from_text(
+            r#"<root main_tree_to_execute="kept"><BehaviorTree ID="kept"><StatusNode status="Success" /></BehaviorTree></root>"#
+                .to_string(),
+        )
+        .unwrap();
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Inverter>
+                    <SubTree ID="kept" />
+                </Inverter>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    assert!(factory.validate_tree(xml).is_ok());
+
+    let mut tree = factory
+        .create_sync_tree_from_text(
+            r#"<root><BehaviorTree ID="other"><AlwaysFailure /></BehaviorTree></root>"#.to_string(),
+            &Blackboard::create(),
+        )
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+}
+
+#[tokio::test]
+async fn tree_builder() {
+    nodes::test_setup();
+
+    let main = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <SubTree ID="echo" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#;
+    let echo = r#"
+        <root>
+            <BehaviorTree ID="echo">
+                <EchoNode msg="hello" />
+            </BehaviorTree>
+        </root>
+    "#;
+
+    let blackboard = Blackboard::create();
+    let mut tree = TreeBuilder::new()
+        .register(|factory| register_action_node!(factory, "StatusNode", StatusNode))
+        .register(|factory| register_action_node!(factory, "EchoNode", EchoNode))
+        .xml(echo)
+        .xml(main)
+        .build_async(&blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    // Registration errors surface when building
+    let result = TreeBuilder::new().xml(main).build_sync(&blackboard);
+    assert!(matches!(result, Err(ParseError::UnknownNode(name)) if name == "StatusNode"));
+}
+
+#[test]
+fn substitution_rules() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <LaunchMissiles status="Success" />
+                    <MoveBase goal="kitchen" />
+                    <StatusNode status="Failure" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    factory.add_substitution_rule(
+        "LaunchMissiles",
+        SubstitutionRule::Node(String::from("StatusNode")),
+    );
+    factory.add_substitution_rule(
+        "Move*",
+        SubstitutionRule::TestNode(TestNodeConfig {
+            statuses: vec![NodeStatus::Failure, NodeStatus::Success],
+            running_ticks: 2,
+            ..Default::default()
+        }),
+    );
+    // Matched by its path, and after the rule above
+    factory.add_substitution_rule(
+        "Sequence/Status?ode",
+        SubstitutionRule::TestNode(TestNodeConfig::default()),
+    );
+    factory.add_substitution_rule(
+        "MoveBase",
+        SubstitutionRule::TestNode(TestNodeConfig {
+            statuses: vec![NodeStatus::Skipped],
+            ..Default::default()
+        }),
+    );
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    // MoveBase runs for two ticks and fails the first time
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Failure);
+    assert_eq!(tree.tick_while_running().unwrap(), NodeStatus::Success);
+
+    let mut paths = Vec::new();
+    tree.visit_nodes(|node| paths.push(node.config().path.clone()));
+    assert_eq!(
+        paths,
+        [
+            "Sequence",
+            "Sequence/LaunchMissiles",
+            "Sequence/MoveBase",
+            "Sequence/StatusNode"
+        ]
+    );
+
+    // The replacement must be registered
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Grasp />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    factory.add_substitution_rule("Grasp", SubstitutionRule::Node(String::from("Missing")));
+    assert!(matches!(
+        factory.create_sync_tree_from_text(xml, &Blackboard::create()),
+        Err(ParseError::UnknownNode(name)) if name == "Missing"
+    ));
+}
+
+#[tokio::test(start_paused = true)]
+async fn substitution_rule_delay() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Grasp />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    // A delay keeps the test node running until it has passed
+    let mut factory = Factory::new();
+    factory.add_substitution_rule(
+        "*",
+        SubstitutionRule::TestNode(TestNodeConfig {
+            async_delay: std::time::Duration::from_millis(20),
+            ..Default::default()
+        }),
+    );
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &Blackboard::create())
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(std::time::Duration::from_millis(19)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    tokio::time::advance(std::time::Duration::from_millis(1)).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+}
+
+#[test]
+fn substituted_node_conditions() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <MoveBase goal="{target}" _skipIf="skip" />
+                    <Grasp object="cup" _onSuccess="grasped := true" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    // MoveBase would fail the sequence if it wasn't skipped
+    factory.add_substitution_rule(
+        "MoveBase",
+        SubstitutionRule::TestNode(TestNodeConfig {
+            statuses: vec![NodeStatus::Failure],
+            ..Default::default()
+        }),
+    );
+    factory.add_substitution_rule(
+        "Grasp",
+        SubstitutionRule::TestNode(TestNodeConfig::default()),
+    );
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("skip", true);
+    blackboard.set_sync("target", String::from("kitchen"));
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    // The conditions of the replaced nodes still apply
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get_sync::<bool>("grasped"), Some(true));
+
+    // So do their ports, which the test node keeps
+    let mut ports = Vec::new();
+    tree.visit_nodes(|node| {
+        ports.extend(
+            node.config()
+                .input_ports
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        )
+    });
+    ports.sort();
+    assert_eq!(
+        ports,
+        [
+            (String::from("goal"), String::from("{target}")),
+            (String::from("object"), String::from("cup"))
+        ]
+    );
+}
+
+#[test]
+fn dangling_ports() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <EchoNode msg="{preset}" />
+                    <EchoNode msg="{halted}" />
+                    <RunUntilHaltedNode halted="{halted}" />
+                    <Script code="scripted := 1" />
+                    <EchoNode msg="{scripted}" />
+                    <SetBlackboard output_key="{set}" value="1" />
+                    <EchoNode msg="{set}" _onSuccess="posted := 2" />
+                    <EchoNode msg="{posted}" />
+                    <SubTree ID="sub" goal="{GoalPosition}" />
+                    <EchoNode msg="{GoalPostion}" />
+                </Sequence>
+            </BehaviorTree>
+            <BehaviorTree ID="sub">
+                <Sequence>
+                    <Script code="goal := 3" />
+                    <EchoNode msg="{goal}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "EchoNode", EchoNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    factory.register_bt_from_text(xml).unwrap();
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("preset", 1);
+    factory.instantiate_sync_tree(&blackboard, "main").unwrap();
+
+    // Only the typo is reported, by the node's path
+    let warnings = factory.take_build_warnings();
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(
+        warnings[0],
+        ParseError::DanglingPort(
+            String::from("GoalPostion"),
+            String::from("msg"),
+            String::from("Sequence/EchoNode")
+        )
+        .to_string()
+    );
+    assert!(factory.take_build_warnings().is_empty());
+}
+
+#[test]
+fn dangling_ports_root_keys() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <Script code="@x := 1" />
+                    <EchoNode msg="{@x}" />
+                    <SetBlackboard output_key="{@y}" value="1" />
+                    <EchoNode msg="{@y}" />
+                    <SetBlackboard output_key="{z}" value="1" />
+                    <EchoNode msg="{@z}" />
+                    <EchoNode msg="{@missing}" />
+                    <MockedNode goal="{goal}" _onSuccess="@mocked := 1" />
+                    <EchoNode msg="{mocked}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "EchoNode", EchoNode);
+    factory.add_substitution_rule(
+        "MockedNode",
+        SubstitutionRule::TestNode(TestNodeConfig::default()),
+    );
+    factory.register_bt_from_text(xml).unwrap();
+    factory
+        .instantiate_sync_tree(&Blackboard::create(), "main")
+        .unwrap();
+
+    // Keys written with or without `@` satisfy reads of either form, and the
+    // post-conditions of substituted nodes count as writes. The ports of the
+    // replaced node have no known direction, so they aren't checked.
+    let warnings = factory.take_build_warnings();
+    assert_eq!(
+        warnings,
+        [ParseError::DanglingPort(
+            String::from("@missing"),
+            String::from("msg"),
+            String::from("Sequence/EchoNode")
+        )
+        .to_string()]
+    );
+}
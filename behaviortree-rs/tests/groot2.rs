@@ -0,0 +1,265 @@
+#![cfg(feature = "groot2")]
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+use behaviortree_rs::{basic_types::NodeStatus, groot2::Groot2Error, Blackboard, Factory};
+
+/// Minimal ZeroMQ client, like the sockets Groot2 uses.
+struct Client {
+    stream: TcpStream,
+}
+
+impl Client {
+    fn connect(port: u16, socket_type: &str) -> Client {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let mut greeting = [0; 64];
+        greeting[0] = 0xFF;
+        greeting[9] = 0x7F;
+        greeting[10] = 3;
+        greeting[12..16].copy_from_slice(b"NULL");
+        stream.write_all(&greeting).unwrap();
+        stream.read_exact(&mut greeting).unwrap();
+        assert_eq!((greeting[0], greeting[9], greeting[10]), (0xFF, 0x7F, 3));
+
+        let mut ready = b"\x05READY\x0bSocket-Type".to_vec();
+        ready.extend((socket_type.len() as u32).to_be_bytes());
+        ready.extend(socket_type.as_bytes());
+        let mut client = Client { stream };
+        client.write_frame(0x04, &ready);
+        let (flags, body) = client.read_frame();
+        assert_eq!(flags, 0x04);
+        assert!(body.starts_with(b"\x05READY\x0bSocket-Type"));
+
+        client
+    }
+
+    fn write_frame(&mut self, flags: u8, body: &[u8]) {
+        self.stream.write_all(&[flags, body.len() as u8]).unwrap();
+        self.stream.write_all(body).unwrap();
+    }
+
+    fn read_frame(&mut self) -> (u8, Vec<u8>) {
+        let mut flags = [0];
+        self.stream.read_exact(&mut flags).unwrap();
+        let len = match flags[0] & 0x02 {
+            0 => {
+                let mut len = [0];
+                self.stream.read_exact(&mut len).unwrap();
+                len[0] as usize
+            }
+            _ => {
+                let mut len = [0; 8];
+                self.stream.read_exact(&mut len).unwrap();
+                u64::from_be_bytes(len) as usize
+            }
+        };
+        let mut body = vec![0; len];
+        self.stream.read_exact(&mut body).unwrap();
+
+        (flags[0], body)
+    }
+
+    fn read_message(&mut self) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        loop {
+            let (flags, body) = self.read_frame();
+            frames.push(body);
+            if flags & 0x01 == 0 {
+                return frames;
+            }
+        }
+    }
+
+    /// Sends a request like a REQ socket and returns the reply.
+    fn request(&mut self, request_type: u8, id: u32) -> Vec<Vec<u8>> {
+        let mut header = vec![2, request_type];
+        header.extend(id.to_le_bytes());
+        self.write_frame(0x01, &[]);
+        self.write_frame(0x00, &header);
+
+        let mut reply = self.read_message();
+        assert!(reply.remove(0).is_empty());
+        assert_eq!(&reply[0][..6], &header[..]);
+        assert_eq!(reply[0].len(), 22);
+
+        reply
+    }
+}
+
+impl Client {
+    fn subscribe(port: u16) -> Client {
+        let mut subscriber = Client::connect(port, "SUB");
+        subscriber.write_frame(0x00, b"\x01");
+
+        subscriber
+    }
+
+    /// Calls `tick` until a status message is published, since subscribers
+    /// are added in the background. Returns the published changes.
+    fn wait_for_status(&mut self, mut tick: impl FnMut()) -> HashMap<u16, u8> {
+        let mut published = None;
+        self.stream
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        for _ in 0..100 {
+            tick();
+
+            let mut flags = [0];
+            if self.stream.read_exact(&mut flags).is_ok() {
+                published = Some(flags[0]);
+                break;
+            }
+        }
+        assert_eq!(published, Some(0x01));
+        self.stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let mut len = [0];
+        self.stream.read_exact(&mut len).unwrap();
+        let mut header = vec![0; len[0] as usize];
+        self.stream.read_exact(&mut header).unwrap();
+        assert_eq!(header[1], b'S');
+
+        parse_statuses(&self.read_message()[0])
+    }
+}
+
+fn parse_statuses(buffer: &[u8]) -> HashMap<u16, u8> {
+    buffer
+        .chunks(3)
+        .map(|chunk| (u16::from_le_bytes([chunk[0], chunk[1]]), chunk[2]))
+        .collect()
+}
+
+#[test]
+fn groot2_publisher() {
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SetBlackboard output_key="started" value="true" />
+                    <SubTree ID="sub" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <SetBlackboard output_key="done" value="true" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    assert_eq!(tree.groot2_port(), None);
+    tree.enable_groot2_publisher(0).unwrap();
+    let port = tree.groot2_port().unwrap();
+    assert!(matches!(
+        tree.enable_groot2_publisher(port),
+        Err(Groot2Error::AlreadyEnabled)
+    ));
+
+    let mut client = Client::connect(port, "REQ");
+
+    // The tree, with the uid of every node and each subtree on its own
+    let reply = client.request(b'T', 1);
+    let tree_xml = String::from_utf8(reply[1].clone()).unwrap();
+    assert!(tree_xml.contains(r#"<Sequence _uid="1" _fullpath="Sequence">"#));
+    assert!(tree_xml.contains(r#"<SubTree ID="sub::3" _uid="3" _fullpath="sub::3"/>"#));
+    assert!(tree_xml.contains(r#"<BehaviorTree ID="sub::3">"#));
+    assert!(tree_xml.contains(r#"<Action ID="SetBlackboard">"#));
+    let tree_id = reply[0][6..].to_vec();
+
+    // Before the first tick, nothing has a status
+    let reply = client.request(b'S', 2);
+    assert_eq!(reply[0][6..], tree_id);
+    assert!(reply[1].is_empty());
+
+    let mut subscriber = Client::subscribe(port + 1);
+    let changes = subscriber.wait_for_status(|| {
+        assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+    });
+    assert_eq!(changes.get(&1), Some(&12));
+
+    // Nodes that completed are IDLE again, and shown with their last status
+    let reply = client.request(b'S', 3);
+    let statuses = parse_statuses(&reply[1]);
+    assert_eq!(statuses.len(), 4);
+    assert!(statuses.values().all(|status| *status == 12));
+
+    // Unknown requests get an error
+    let mut header = vec![2, b'?'];
+    header.extend(4u32.to_le_bytes());
+    client.write_frame(0x01, &[]);
+    client.write_frame(0x00, &header);
+    let reply = client.read_message();
+    assert_eq!(reply[1], b"error");
+}
+
+#[test]
+fn groot2_needs_two_ports() {
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <SetBlackboard output_key="done" value="true" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut tree = Factory::new()
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+
+    assert!(matches!(
+        tree.enable_groot2_publisher(u16::MAX),
+        Err(Groot2Error::InvalidPort(u16::MAX))
+    ));
+}
+
+#[test]
+fn groot2_publishes_failed_ticks() {
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SetBlackboard output_key="started" value="true" />
+                    <Script code="done := missing" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut tree = Factory::new()
+        .create_sync_tree_from_text(xml, &Blackboard::create())
+        .unwrap();
+    tree.enable_groot2_publisher(0).unwrap();
+
+    // The statuses up to the error are published
+    let mut subscriber = Client::subscribe(tree.groot2_port().unwrap() + 1);
+    let mut tick = || {
+        assert!(tree.tick_once().is_err());
+        tree.halt_tree();
+    };
+    // Halting is published too, and may be the first message received
+    let mut changes = subscriber.wait_for_status(&mut tick);
+    if changes.get(&1) == Some(&11) {
+        changes = subscriber.wait_for_status(&mut tick);
+    }
+    // The Sequence was running when the script failed
+    assert_eq!(changes.get(&1), Some(&1));
+}
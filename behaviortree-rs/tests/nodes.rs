@@ -1,6 +1,6 @@
 use behaviortree_rs::{
     basic_types::{BTToString, NodeStatus, PortsList},
-    macros::{define_ports, input_port},
+    macros::{define_ports, input_port, output_port},
     nodes::{AsyncHalt, AsyncStatefulActionNode, AsyncTick, NodePorts, NodeResult},
 };
 use behaviortree_rs_derive::bt_node;
@@ -144,3 +144,30 @@ impl AsyncTick for DataNode {
 }
 
 impl AsyncHalt for DataNode {}
+
+/// Runs until halted, then writes `true` to its `halted` output port.
+#[bt_node(StatefulActionNode)]
+pub struct RunUntilHaltedNode {}
+
+impl NodePorts for RunUntilHaltedNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(output_port!("halted"))
+    }
+}
+
+impl AsyncStatefulActionNode for RunUntilHaltedNode {
+    fn on_start(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Running) })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Running) })
+    }
+
+    fn on_halted(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            info!("RunUntilHalted halted");
+            let _ = self.config.set_output("halted", true).await;
+        })
+    }
+}
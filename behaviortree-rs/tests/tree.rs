@@ -0,0 +1,1208 @@
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use behaviortree_rs::{
+    basic_types::{NodeStatus, NodeType, PortsList},
+    blackboard::Blackboard,
+    bt_node,
+    loggers::{FileLogger, StdoutLogger},
+    macros::{define_ports, input_port, output_port, register_action_node},
+    nodes::{
+        AsyncHalt, AsyncStatefulActionNode, AsyncTick, NodePorts, NodeResult, TreeNodeDefaults,
+    },
+    observer::TreeObserver,
+    tree::{BreakpointPhase, Factory, ParseError, TickStep},
+};
+use futures::future::BoxFuture;
+
+use crate::nodes::{RunForNode, RunUntilHaltedNode, StatusNode};
+
+mod nodes;
+
+#[tokio::test]
+async fn skipped_root() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Fallback>
+                    <StatusNode status="Skipped" />
+                    <StatusNode status="Skipped" />
+                </Fallback>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Skipped);
+    // The root was reset, so it can be ticked again
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Skipped);
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Skipped
+    );
+}
+
+#[tokio::test]
+async fn skip_subtree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SubTree ID="sub" _skipIf="{skip}" />
+                    <StatusNode status="Success" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // The condition is evaluated in the parent's scope
+    blackboard.set("skip", true).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    blackboard.set("skip", false).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+}
+
+#[tokio::test]
+async fn pre_conditions() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" _failureIf="fail" />
+                    <StatusNode status="Failure" _successIf="true" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // Missing condition key
+    assert!(tree.tick_once().await.is_err());
+
+    // The second child is forced to succeed
+    blackboard.set("fail", false).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    // The first child is forced to fail
+    blackboard.set("fail", true).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    // A bare key is read like "{fail}", so a string entry is converted
+    blackboard.set("fail", String::from("false")).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+    blackboard.set("fail", String::from("true")).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    // A condition must evaluate to a bool
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <StatusNode status="Success" _skipIf="count + 1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+    blackboard.set("count", 2).await;
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(
+        tree.tick_once().await.unwrap_err().to_string(),
+        "Couldn't evaluate the expression of port [_skipIf]: Expected the expression to be a bool, found int [3]"
+    );
+}
+
+#[tokio::test]
+async fn post_conditions() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" _skipIf="count >= 2" _onSuccess="count := count + 1" _post="ran := true" />
+                    <StatusNode status="Failure" _successIf="count == 1" _onSuccess="forced := true" _onFailure="failed := true" />
+                    <RunUntilHaltedNode _while="!stop" _onHalted="halted := true" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("count", 0).await;
+    blackboard.set("stop", false).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // The second child is forced to succeed, which counts as its success
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(blackboard.get::<i32>("count").await, Some(1));
+    assert_eq!(blackboard.get::<bool>("ran").await, Some(true));
+    assert_eq!(blackboard.get::<bool>("forced").await, Some(true));
+    assert_eq!(blackboard.get::<bool>("failed").await, None);
+
+    // Halted by `_while`, the running child is skipped
+    blackboard.set("stop", true).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+    assert_eq!(blackboard.get::<bool>("halted").await, Some(true));
+
+    // Now the second child fails on its own
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get::<i32>("count").await, Some(2));
+    assert_eq!(blackboard.get::<bool>("failed").await, Some(true));
+
+    // Skipped nodes run no scripts
+    blackboard.set("ran", false).await;
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(blackboard.get::<i32>("count").await, Some(2));
+    assert_eq!(blackboard.get::<bool>("ran").await, Some(false));
+
+    // A script that can't be parsed is an error when building the tree
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <StatusNode status="Success" _onSuccess="count + 1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let result = factory.create_async_tree_from_text(xml, &blackboard).await;
+    assert!(matches!(
+        result,
+        Err(ParseError::InvalidScript(attribute, _, _)) if attribute == "_onSuccess"
+    ));
+}
+
+/// Writes its own uid to the `uid` output port.
+#[bt_node(SyncActionNode)]
+struct UidNode {}
+
+impl AsyncTick for UidNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            let uid = self.uid();
+            self.config.set_output("uid", uid).await?;
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for UidNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(output_port!("uid"))
+    }
+}
+
+impl AsyncHalt for UidNode {}
+
+#[tokio::test]
+async fn deterministic_uids() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <UidNode uid="{first}" />
+                    <SubTree ID="sub" _autoremap="true" />
+                    <UidNode uid="{last}" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <UidNode uid="{inner}" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "UidNode", UidNode);
+    factory.register_bt_from_text(xml).unwrap();
+
+    // Building the same tree twice yields the same pre-order uids
+    for _ in 0..2 {
+        let mut blackboard = Blackboard::create();
+        let mut tree = factory
+            .instantiate_async_tree(&blackboard, "main")
+            .await
+            .unwrap();
+
+        assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+        assert_eq!(blackboard.get_exact::<u16>("first").await, Some(2));
+        assert_eq!(blackboard.get_exact::<u16>("inner").await, Some(4));
+        assert_eq!(blackboard.get_exact::<u16>("last").await, Some(5));
+    }
+}
+
+#[test]
+fn too_many_nodes() {
+    nodes::test_setup();
+
+    // Uids are a u16 starting at 1, so 65534 nodes fit
+    let xml = |children: usize| {
+        format!(
+            r#"<root><BehaviorTree ID="main"><Sequence>{}</Sequence></BehaviorTree></root>"#,
+            r#"<StatusNode status="Success" />"#.repeat(children)
+        )
+    };
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let mut tree = factory
+        .create_sync_tree_from_text(xml(65533), &Blackboard::create())
+        .unwrap();
+    assert_eq!(tree.tick_once().unwrap(), NodeStatus::Success);
+
+    let result = factory.create_sync_tree_from_text(xml(65534), &Blackboard::create());
+    assert!(matches!(result, Err(ParseError::TooManyNodes)));
+}
+
+#[tokio::test]
+async fn collect_blackboards() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <SubTree ID="outer" name="first" a="1" />
+                    <SubTree ID="inner" c="3" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="outer">
+                <SubTree ID="inner" name="nested" b="2" />
+            </BehaviorTree>
+
+            <BehaviorTree ID="inner">
+                <StatusNode status="Success" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("root", 0u32).await;
+
+    let tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    let blackboards = tree.collect_blackboards().await;
+    let paths: Vec<&str> = blackboards.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(paths, ["", "first", "first/nested", "inner::5"]);
+
+    // Each scope only holds its own keys
+    let keys = ["root", "a", "b", "c"];
+    for (i, (_, mut blackboard)) in blackboards.into_iter().enumerate() {
+        for (j, key) in keys.iter().enumerate() {
+            assert_eq!(blackboard.get::<u32>(key).await.is_some(), i == j);
+        }
+    }
+}
+
+/// Runs until halted. Its `on_halted()` only sets `halted` after yielding.
+#[bt_node(StatefulActionNode)]
+struct SlowHaltNode {
+    halted: Arc<AtomicBool>,
+}
+
+impl NodePorts for SlowHaltNode {}
+
+impl AsyncStatefulActionNode for SlowHaltNode {
+    fn on_start(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Running) })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { Ok(NodeStatus::Running) })
+    }
+
+    fn on_halted(&mut self) -> BoxFuture<()> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            self.halted.store(true, Ordering::SeqCst);
+        })
+    }
+}
+
+#[tokio::test]
+async fn halt_awaits_children() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <ReactiveSequence>
+                    <Inverter>
+                        <Sequence>
+                            <SlowHaltNode />
+                        </Sequence>
+                    </Inverter>
+                </ReactiveSequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let halted = Arc::new(AtomicBool::new(false));
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "SlowHaltNode", SlowHaltNode, halted);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert!(!halted.load(Ordering::SeqCst));
+
+    tree.halt_tree().await;
+    assert!(halted.load(Ordering::SeqCst));
+
+    // Halting a control node without children is fine too
+    let xml = r#"
+        <root main_tree_to_execute="empty">
+            <BehaviorTree ID="empty">
+                <Sequence />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    tree.halt_tree().await;
+}
+
+#[tokio::test]
+async fn graft() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                </Sequence>
+            </BehaviorTree>
+            <BehaviorTree ID="inverted">
+                <Inverter>
+                    <StatusNode status="Success" />
+                </Inverter>
+            </BehaviorTree>
+            <BehaviorTree ID="failure">
+                <StatusNode status="Failure" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    // Control nodes get the grafted node as their last child
+    let inverted = factory
+        .instantiate_async_tree(&blackboard, "inverted")
+        .await
+        .unwrap()
+        .into_root();
+    let replaced = tree.graft("Sequence", inverted).await.unwrap();
+    assert!(replaced.is_none());
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Failure);
+
+    // Decorators replace their child
+    let failure = factory
+        .instantiate_async_tree(&blackboard, "failure")
+        .await
+        .unwrap()
+        .into_root();
+    let replaced = tree.graft("Sequence/Inverter", failure).await.unwrap();
+    assert_eq!(replaced.unwrap().path(), "Sequence/Inverter/StatusNode");
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    // Action nodes can't have children, and the path must exist
+    let failure = factory
+        .instantiate_async_tree(&blackboard, "failure")
+        .await
+        .unwrap()
+        .into_root();
+    assert!(tree.graft("Sequence/StatusNode", failure).await.is_err());
+    let failure = factory
+        .instantiate_async_tree(&blackboard, "failure")
+        .await
+        .unwrap()
+        .into_root();
+    assert!(tree.graft("Missing", failure).await.is_err());
+}
+
+#[tokio::test]
+async fn halt_subtree() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Parallel>
+                    <Sequence>
+                        <StatusNode status="Success" />
+                        <RunUntilHaltedNode halted="{first_halted}" />
+                    </Sequence>
+                    <Fallback>
+                        <RunUntilHaltedNode halted="{second_halted}" />
+                    </Fallback>
+                </Parallel>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    tree.halt_subtree("Parallel/Sequence").await.unwrap();
+
+    // Only the first branch was halted
+    assert_eq!(blackboard.get::<bool>("first_halted").await, Some(true));
+    assert_eq!(blackboard.get::<bool>("second_halted").await, None);
+    let report = tree.status_report();
+    assert_eq!(report[&1], NodeStatus::Running);
+    assert_eq!(report[&2], NodeStatus::Idle);
+    assert_eq!(report[&4], NodeStatus::Idle);
+    assert_eq!(report[&5], NodeStatus::Running);
+    assert_eq!(report[&6], NodeStatus::Running);
+
+    // The parallel starts the halted branch again on the next tick
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(tree.status_report()[&4], NodeStatus::Running);
+
+    // Halting a parallel halts all of its running children
+    tree.halt_subtree("Parallel").await.unwrap();
+    assert_eq!(blackboard.get::<bool>("second_halted").await, Some(true));
+    assert!(tree
+        .status_report()
+        .values()
+        .all(|status| *status == NodeStatus::Idle));
+
+    assert!(tree.halt_subtree("Parallel/Missing").await.is_err());
+}
+
+#[tokio::test]
+async fn halt_node() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Parallel>
+                    <Sequence>
+                        <StatusNode status="Success" />
+                        <RunUntilHaltedNode halted="{first_halted}" />
+                    </Sequence>
+                    <Fallback>
+                        <RunUntilHaltedNode halted="{second_halted}" />
+                    </Fallback>
+                </Parallel>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunUntilHaltedNode", RunUntilHaltedNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    // Halting a node that isn't running does nothing
+    let report = tree.status_report();
+    assert_ne!(report[&3], NodeStatus::Running);
+    tree.halt_node(3).await.unwrap();
+    assert_eq!(tree.status_report(), report);
+
+    // Halting an interior node halts and resets its descendants
+    tree.halt_node(2).await.unwrap();
+    assert_eq!(blackboard.get::<bool>("first_halted").await, Some(true));
+    assert_eq!(blackboard.get::<bool>("second_halted").await, None);
+    let report = tree.status_report();
+    assert_eq!(report[&2], NodeStatus::Idle);
+    assert_eq!(report[&3], NodeStatus::Idle);
+    assert_eq!(report[&4], NodeStatus::Idle);
+    assert_eq!(report[&6], NodeStatus::Running);
+
+    // A single action can be cancelled as well
+    tree.halt_node(6).await.unwrap();
+    assert_eq!(blackboard.get::<bool>("second_halted").await, Some(true));
+    assert_eq!(tree.status_report()[&6], NodeStatus::Idle);
+
+    assert!(tree.halt_node(42).await.is_err());
+}
+
+#[tokio::test]
+async fn tick_with_breakpoints() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <StatusNode status="{second}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    let mut blackboard = Blackboard::create();
+    blackboard.set("second", NodeStatus::Success).await;
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    let mut session = tree.tick_with_breakpoints(|path, _| path == "Sequence/StatusNode");
+    let mut steps = Vec::new();
+    let status = loop {
+        match session.resume().await {
+            TickStep::Paused(mut breakpoint) => {
+                // Change the second node's input before it ticks
+                if steps.len() == 2 {
+                    breakpoint
+                        .blackboard
+                        .set("second", NodeStatus::Failure)
+                        .await;
+                }
+                steps.push((breakpoint.phase, breakpoint.status));
+            }
+            TickStep::Completed(result) => break result.unwrap(),
+        }
+    };
+    assert_eq!(status, NodeStatus::Failure);
+    assert_eq!(
+        steps,
+        vec![
+            (BreakpointPhase::BeforeTick, NodeStatus::Idle),
+            (BreakpointPhase::AfterTick, NodeStatus::Success),
+            (BreakpointPhase::BeforeTick, NodeStatus::Idle),
+            (BreakpointPhase::AfterTick, NodeStatus::Failure),
+        ]
+    );
+    assert!(matches!(
+        session.resume().await,
+        TickStep::Completed(Err(_))
+    ));
+    drop(session);
+
+    // The predicate also gets the status, e.g. to break on failures
+    let mut session = tree.tick_with_breakpoints(|_, status| status == NodeStatus::Failure);
+    let mut paths = Vec::new();
+    while let TickStep::Paused(breakpoint) = session.resume().await {
+        paths.push(breakpoint.path);
+    }
+    assert_eq!(paths, vec!["Sequence/StatusNode", "Sequence"]);
+    drop(session);
+
+    // Breakpoints are removed once the tick completes
+    assert_eq!(tree.tick_exactly_once().await.unwrap(), NodeStatus::Failure);
+
+    // And when the session is dropped while the tick is paused
+    let checks = Arc::new(AtomicUsize::new(0));
+    let counter = checks.clone();
+    let mut session = tree.tick_with_breakpoints(move |_, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        true
+    });
+    assert!(matches!(session.resume().await, TickStep::Paused(_)));
+    drop(session);
+    assert_eq!(checks.load(Ordering::SeqCst), 1);
+
+    tree.halt_tree().await;
+    assert_eq!(tree.tick_exactly_once().await.unwrap(), NodeStatus::Failure);
+    assert_eq!(checks.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn status_report() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <StatusNode status="Success" />
+                    <SubTree ID="sub" />
+                    <StatusNode status="Success" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <RunForNode iters="1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+    let blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    let mut uids = Vec::new();
+    tree.visit_nodes(|node| uids.push(node.uid()));
+    assert_eq!(uids, vec![1, 2, 3, 4, 5]);
+
+    assert!(tree
+        .status_report()
+        .values()
+        .all(|status| *status == NodeStatus::Idle));
+
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    let report = tree.status_report();
+    assert_eq!(report.len(), 5);
+    assert_eq!(report[&1], NodeStatus::Running);
+    assert_eq!(report[&3], NodeStatus::Running);
+    assert_eq!(report[&4], NodeStatus::Running);
+    assert_eq!(report[&5], NodeStatus::Idle);
+}
+
+const OBSERVED_TREE: &str = r#"
+    <root main_tree_to_execute="main">
+        <BehaviorTree ID="main">
+            <Sequence>
+                <StatusNode status="Success" />
+                <RunForNode iters="1" />
+                <StatusNode status="Failure" _skipIf="true" />
+            </Sequence>
+        </BehaviorTree>
+    </root>
+"#;
+
+#[tokio::test]
+async fn status_callback() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut tree = factory
+        .create_async_tree_from_text(OBSERVED_TREE.to_string(), &Blackboard::create())
+        .await
+        .unwrap();
+
+    let transitions = Arc::new(Mutex::new(Vec::new()));
+    let recorded = transitions.clone();
+    tree.add_status_callback(move |uid, previous, status| {
+        recorded.lock().unwrap().push((uid, previous, status));
+    });
+    let other_calls = Arc::new(AtomicUsize::new(0));
+    let counter = other_calls.clone();
+    let other = tree.add_status_callback(move |_, _, _| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+
+    // Ticks that return the same status again aren't reported
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            (2, NodeStatus::Idle, NodeStatus::Success),
+            (3, NodeStatus::Idle, NodeStatus::Running),
+            (1, NodeStatus::Idle, NodeStatus::Running),
+            (3, NodeStatus::Running, NodeStatus::Success),
+            (4, NodeStatus::Idle, NodeStatus::Skipped),
+            (1, NodeStatus::Running, NodeStatus::Success),
+        ]
+    );
+
+    // Every callback is called, until it's removed
+    assert_eq!(other_calls.load(Ordering::SeqCst), 6);
+    other.remove();
+    tree.tick_while_running().await.unwrap();
+    assert_eq!(other_calls.load(Ordering::SeqCst), 6);
+    assert_eq!(transitions.lock().unwrap().len(), 12);
+}
+
+#[tokio::test]
+async fn tree_observer() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut tree = factory
+        .create_async_tree_from_text(OBSERVED_TREE.to_string(), &Blackboard::create())
+        .await
+        .unwrap();
+
+    let observer = TreeObserver::new(&mut tree);
+    assert_eq!(observer.statistics(1), None);
+
+    for _ in 0..2 {
+        assert_eq!(
+            tree.tick_while_running().await.unwrap(),
+            NodeStatus::Success
+        );
+    }
+
+    let sequence = observer.statistics(1).unwrap();
+    assert_eq!(sequence.success_count, 2);
+    assert_eq!(sequence.transitions_count, 4);
+    assert_eq!(sequence.current_status, NodeStatus::Success);
+
+    let run_for = observer.statistics(3).unwrap();
+    assert_eq!(run_for.success_count, 2);
+    assert_eq!(run_for.failure_count, 0);
+    assert_eq!(run_for.transitions_count, 4);
+
+    let skipped = observer.statistics(4).unwrap();
+    assert_eq!(skipped.skip_count, 2);
+    assert_eq!(skipped.last_result, NodeStatus::Skipped);
+
+    observer.reset();
+    assert_eq!(observer.statistics(1), None);
+}
+
+#[tokio::test]
+async fn file_logger() {
+    nodes::test_setup();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "RunForNode", RunForNode);
+
+    let mut tree = factory
+        .create_async_tree_from_text(OBSERVED_TREE.to_string(), &Blackboard::create())
+        .await
+        .unwrap();
+
+    let path = std::env::temp_dir().join(format!("bt_file_logger_{}.jsonl", std::process::id()));
+    let logger = FileLogger::new(&mut tree, &path)
+        .unwrap()
+        .with_node_types([NodeType::Action]);
+    tree.tick_while_running().await.unwrap();
+    drop(logger);
+
+    // Not logged anymore
+    tree.tick_while_running().await.unwrap();
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("{\"timestamp\":"));
+    assert!(lines[0].ends_with(
+        r#""uid":2,"name":"StatusNode","path":"Sequence/StatusNode","type":"Action","previous":"IDLE","status":"SUCCESS"}"#
+    ));
+    assert!(lines[1].ends_with(r#""previous":"IDLE","status":"RUNNING"}"#));
+    assert!(lines[3].ends_with(r#""uid":4,"name":"StatusNode","path":"Sequence/StatusNode","type":"Action","previous":"IDLE","status":"SKIPPED"}"#));
+
+    // Loggers add their own callback, and remove it when dropped
+    let output = SharedBuffer::default();
+    let first = StdoutLogger::to_writer(&mut tree, output.clone());
+    let second =
+        StdoutLogger::to_writer(&mut tree, output.clone()).with_node_types([NodeType::Control]);
+    tree.tick_while_running().await.unwrap();
+    drop(second);
+    tree.tick_while_running().await.unwrap();
+    drop(first);
+    tree.tick_while_running().await.unwrap();
+
+    let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 6 + 2 + 6, "{output}");
+    assert!(lines[0].starts_with('['));
+    assert!(lines[0].contains("]: [StatusNode] "));
+    assert!(lines[0].contains(" -> "));
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|line| line.contains("[Sequence]"))
+            .count(),
+        2 + 2 + 2
+    );
+
+    let _logger = StdoutLogger::new(&mut tree);
+    tree.tick_while_running().await.unwrap();
+}
+
+/// Collects what a logger writes.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes the value set by `initialize()` to `out`.
+#[bt_node(SyncActionNode)]
+struct InitializedNode {
+    #[bt(default)]
+    value: Option<u32>,
+}
+
+impl InitializedNode {
+    fn initialize(&mut self, value: u32) {
+        self.value = Some(value);
+    }
+}
+
+impl AsyncTick for InitializedNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            match self.value {
+                Some(value) => {
+                    self.config.set_output("out", value).await?;
+                    Ok(NodeStatus::Success)
+                }
+                None => Ok(NodeStatus::Failure),
+            }
+        })
+    }
+}
+
+impl NodePorts for InitializedNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(output_port!("out"))
+    }
+}
+
+impl AsyncHalt for InitializedNode {}
+
+#[tokio::test]
+async fn visit_nodes_mut() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <InitializedNode out="{first}" />
+                    <StatusNode status="Success" />
+                    <InitializedNode out="{second}" />
+                </Sequence>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "StatusNode", StatusNode);
+    register_action_node!(factory, "InitializedNode", InitializedNode);
+    let mut blackboard = Blackboard::create();
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+
+    // Same order as `visit_nodes()`
+    let mut uids = Vec::new();
+    tree.visit_nodes(|node| uids.push(node.uid()));
+    let mut uids_mut = Vec::new();
+    tree.visit_nodes_mut(|node| uids_mut.push(node.uid()));
+    assert_eq!(uids_mut, uids);
+
+    tree.visit_nodes_mut(|node| {
+        let uid = node.uid();
+        if let Some(node) = node.as_any_mut().downcast_mut::<InitializedNode>() {
+            node.initialize(u32::from(uid) * 10);
+        }
+    });
+
+    assert_eq!(
+        tree.tick_while_running().await.unwrap(),
+        NodeStatus::Success
+    );
+    assert_eq!(blackboard.get::<u32>("first").await, Some(20));
+    assert_eq!(blackboard.get::<u32>("second").await, Some(40));
+}
+
+/// Writes its position to `x` and `y` every tick, and `done` once `x` is 2.
+#[bt_node(SyncActionNode)]
+struct OdometryNode {
+    #[bt(default)]
+    x: u32,
+}
+
+impl AsyncTick for OdometryNode {
+    fn tick(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move {
+            self.x += 1;
+            self.config.set_output("x", self.x).await?;
+            self.config.set_output("y", 0u32).await?;
+            if self.x == 2 {
+                self.config.set_output("done", true).await?;
+            }
+
+            Ok(NodeStatus::Success)
+        })
+    }
+}
+
+impl NodePorts for OdometryNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(output_port!("x"), output_port!("y"), output_port!("done"))
+    }
+}
+
+impl AsyncHalt for OdometryNode {}
+
+#[test]
+fn tick_once_tracked() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <Sequence>
+                    <OdometryNode x="{x}" y="{y}" done="{done}" />
+                    <SubTree ID="sub" />
+                </Sequence>
+            </BehaviorTree>
+
+            <BehaviorTree ID="sub">
+                <Script code="local := 1" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "OdometryNode", OdometryNode);
+
+    let mut blackboard = Blackboard::create();
+    blackboard.set_sync("unrelated", String::from("1.5"));
+
+    let mut tree = factory
+        .create_sync_tree_from_text(xml, &blackboard)
+        .unwrap();
+
+    // `y` counts although its value didn't change, the subtree's entry doesn't
+    assert_eq!(
+        tree.tick_once_tracked().unwrap(),
+        (
+            NodeStatus::Success,
+            vec![String::from("x"), String::from("y")]
+        )
+    );
+
+    // Converting a string entry on read isn't a write
+    assert_eq!(blackboard.get_sync::<f64>("unrelated"), Some(1.5));
+    assert_eq!(
+        tree.tick_once_tracked().unwrap(),
+        (
+            NodeStatus::Success,
+            vec![String::from("done"), String::from("x"), String::from("y")]
+        )
+    );
+    assert_eq!(blackboard.sequence_id_sync("x"), Some(2));
+    assert_eq!(blackboard.sequence_id_sync("done"), Some(1));
+    assert_eq!(blackboard.sequence_id_sync("unrelated"), Some(1));
+}
+
+/// Returns SUCCESS once the entry in its `key` port is `true`. Until then,
+/// it returns RUNNING and wakes up the tree when the entry is set.
+#[bt_node(StatefulActionNode)]
+struct WaitForKeyNode {}
+
+impl WaitForKeyNode {
+    async fn check(&mut self) -> NodeResult {
+        let key: String = self.config.get_input("key").await?;
+
+        match self.config.blackboard_mut().get::<bool>(&key).await {
+            Some(true) => Ok(NodeStatus::Success),
+            _ => {
+                self.config.wake_up_on_change(&key).await;
+                Ok(NodeStatus::Running)
+            }
+        }
+    }
+}
+
+impl NodePorts for WaitForKeyNode {
+    fn provided_ports(&self) -> PortsList {
+        define_ports!(input_port!("key"))
+    }
+}
+
+impl AsyncStatefulActionNode for WaitForKeyNode {
+    fn on_start(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { self.check().await })
+    }
+
+    fn on_running(&mut self) -> BoxFuture<NodeResult> {
+        Box::pin(async move { self.check().await })
+    }
+}
+
+#[tokio::test]
+async fn wake_up() {
+    nodes::test_setup();
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <ParallelAll>
+                    <WaitForKeyNode key="ready" />
+                    <SetBlackboard output_key="ready" value="true" />
+                </ParallelAll>
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let mut factory = Factory::new();
+    register_action_node!(factory, "WaitForKeyNode", WaitForKeyNode);
+
+    // The key is set after it's watched, so the tree is ticked again
+    let mut tree = factory
+        .create_async_tree_from_text(xml.clone(), &Blackboard::create())
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &Blackboard::create())
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_exactly_once().await.unwrap(), NodeStatus::Running);
+    assert_eq!(tree.tick_exactly_once().await.unwrap(), NodeStatus::Success);
+
+    let xml = r#"
+        <root main_tree_to_execute="main">
+            <BehaviorTree ID="main">
+                <WaitForKeyNode key="ready" />
+            </BehaviorTree>
+        </root>
+    "#
+    .to_string();
+
+    let blackboard = Blackboard::create();
+    let mut tree = factory
+        .create_async_tree_from_text(xml, &blackboard)
+        .await
+        .unwrap();
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Running);
+
+    // Sleeping ends as soon as the watched key is set
+    let mut writer = blackboard.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        writer.set("ready", true).await;
+    });
+    let start = Instant::now();
+    assert!(tree.sleep(Duration::from_secs(5)).await);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(tree.tick_once().await.unwrap(), NodeStatus::Success);
+
+    // Nothing is watched once the node succeeded
+    assert!(!tree.sleep(Duration::from_millis(10)).await);
+}